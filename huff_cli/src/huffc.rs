@@ -7,21 +7,31 @@
 #![forbid(where_clauses_object_safety)]
 #![allow(deprecated)]
 
-use clap::{App, CommandFactory, Parser as ClapParser, Subcommand};
-use ethers_core::utils::hex;
+use clap::{App, ArgEnum, CommandFactory, Parser as ClapParser, Subcommand};
+use ethers_core::utils::{hex, to_checksum};
 use huff_codegen::Codegen;
-use huff_core::Compiler;
+use huff_core::{create2::search_salt, standard_json::compile_standard_json, Compiler};
 use huff_tests::{
     prelude::{print_test_report, ReportKind},
     HuffTester,
 };
 use huff_utils::prelude::{
-    export_interfaces, gen_sol_interfaces, str_to_bytes32, unpack_files, AstSpan, CodegenError,
-    CodegenErrorKind, CompilerError, FileSource, Literal, OutputLocation, Span,
+    check_bindings, create2_address, diff_storage_layouts, disassemble, export_bindings,
+    export_interfaces, format_disassembly, gen_bindings, gen_huff_interface, gen_sol_interfaces,
+    hash_raw_bytes, parse_sol_interface, str_to_bytes32, unpack_files, Abi, Allocation, Artifact,
+    ArtifactFormat, AstSpan, BindingsLanguage, CodegenError, CodegenErrorKind, CompilerError,
+    FileSource, Literal, OutputLocation, Span, StorageOrder,
 };
 use isatty::stdout_isatty;
 use spinners::{Spinner, Spinners};
-use std::{collections::BTreeMap, io::Write, path::Path, rc::Rc, sync::Arc, time::Instant};
+use std::{
+    collections::BTreeMap,
+    io::{Read, Write},
+    path::Path,
+    rc::Rc,
+    sync::Arc,
+    time::Instant,
+};
 use yansi::Paint;
 
 /// The Huff CLI Args
@@ -55,6 +65,18 @@ struct Huff {
     #[clap(short = 'a', long = "artifacts")]
     artifacts: bool,
 
+    /// The format of generated artifacts: huff, foundry or hardhat.
+    #[clap(long = "artifact-format", default_value = "huff")]
+    artifact_format: String,
+
+    /// The order storage pointers are assigned slots in: traversal or definition.
+    #[clap(long = "storage-order", default_value = "traversal")]
+    storage_order: String,
+
+    /// The hex offset static memory regions start at.
+    #[clap(long = "memory-base", default_value = "0x80")]
+    memory_base: String,
+
     /// Optimize compilation [WIP]
     #[clap(short = 'z', long = "optimize")]
     optimize: bool,
@@ -63,6 +85,14 @@ struct Huff {
     #[clap(short = 'g', min_values = 0, long = "interface")]
     interface: Option<String>,
 
+    /// The SPDX license identifier of generated solidity interfaces
+    #[clap(long = "license")]
+    license: Option<String>,
+
+    /// The solidity version pragma of generated solidity interfaces
+    #[clap(long = "pragma")]
+    pragma: Option<String>,
+
     /// Generate and log bytecode.
     #[clap(short = 'b', long = "bytecode")]
     bytecode: bool,
@@ -71,6 +101,10 @@ struct Huff {
     #[clap(short = 'r', long = "bin-runtime")]
     bin_runtime: bool,
 
+    /// Generate and log the standard JSON ABI.
+    #[clap(long = "abi")]
+    abi: bool,
+
     /// Prints out to the terminal.
     #[clap(short = 'p', long = "print")]
     print: bool,
@@ -83,13 +117,31 @@ struct Huff {
     #[clap(short = 'c', long = "constants", multiple_values = true)]
     constants: Option<Vec<String>>,
 
-    /// Test subcommand
+    /// Check selectors for collisions against another artifact's ABI (eg. a proxy's
+    /// implementation).
+    #[clap(long = "collisions")]
+    collisions: Option<String>,
+
+    /// Compile a solc Standard JSON input read from stdin, writing the output to stdout.
+    #[clap(long = "standard-json")]
+    standard_json: bool,
+
+    /// Subcommands
     #[clap(subcommand)]
-    test: Option<TestCommands>,
+    command: Option<Commands>,
+}
+
+/// The output format of the selectors subcommand
+#[derive(ArgEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum SelectorsFormat {
+    /// A table of selectors and signatures
+    Table,
+    /// The selectors as JSON
+    Json,
 }
 
 #[derive(Subcommand, Clone, Debug)]
-enum TestCommands {
+enum Commands {
     /// Test subcommand
     Test {
         /// Format the test output as a list, table, or JSON.
@@ -100,6 +152,77 @@ enum TestCommands {
         #[clap(short = 'm', long = "match")]
         match_: Option<String>,
     },
+    /// Compare the storage layouts of two artifacts
+    StorageDiff {
+        /// The artifact of the deployed contract
+        old: String,
+
+        /// The artifact of the upgraded contract
+        new: String,
+    },
+    /// Print the fully inlined Huff source of a macro
+    Expand {
+        /// The contract containing the macro
+        path: String,
+
+        /// The macro to expand
+        #[clap(short = 'm', long = "macro", default_value = "MAIN")]
+        macro_name: String,
+    },
+    /// Disassemble bytecode or an artifact
+    Disasm {
+        /// The hex encoded bytecode, or the path of an artifact
+        input: String,
+
+        /// Disassemble the artifact's runtime bytecode instead of its deployed bytecode
+        #[clap(short = 'r', long = "runtime")]
+        runtime: bool,
+    },
+    /// Print the Huff declarations of a JSON ABI or a Solidity interface
+    AbiImport {
+        /// The JSON ABI, an artifact containing one, or a Solidity interface file
+        path: String,
+    },
+    /// Print the function selectors, event topics and error selectors of a contract
+    Selectors {
+        /// The contract to print the selectors of
+        path: String,
+
+        /// Format the selectors as a table, or JSON.
+        #[clap(short = 'f', long = "format", arg_enum, default_value = "table")]
+        format: SelectorsFormat,
+    },
+    /// Generate typed Rust or TypeScript bindings of the compiled contracts
+    Bindings {
+        /// The contract(s) to generate bindings for
+        path: String,
+
+        /// The language of the bindings: rust or ts
+        #[clap(short = 'l', long = "lang")]
+        lang: String,
+
+        /// The directory to write the bindings to
+        #[clap(short = 'o', long = "out", default_value = "./bindings")]
+        out: String,
+
+        /// Check that the bindings are up to date instead of writing them
+        #[clap(long = "check")]
+        check: bool,
+    },
+    /// Predict the CREATE2 address of the contract, or search for a vanity salt
+    Create2 {
+        /// The address of the deploying contract
+        #[clap(long = "deployer")]
+        deployer: String,
+
+        /// The salt to deploy with
+        #[clap(long = "salt", conflicts_with = "search-prefix")]
+        salt: Option<String>,
+
+        /// Search for a salt that deploys to an address starting with this prefix
+        #[clap(long = "search-prefix")]
+        search_prefix: Option<String>,
+    },
 }
 
 /// Helper function to read an stdin input
@@ -126,6 +249,90 @@ fn main() {
         Compiler::init_tracing_subscriber(Some(vec![tracing::Level::DEBUG.into()]));
     }
 
+    // Standard JSON sources are read from stdin rather than the filesystem
+    if cli.standard_json {
+        let mut input = String::new();
+        if let Err(e) = std::io::stdin().read_to_string(&mut input) {
+            eprintln!("{}", Paint::red(format!("Failed to read Standard JSON input: {}", e)));
+            std::process::exit(1);
+        }
+        println!("{}", compile_standard_json(&input));
+        return
+    }
+
+    // Storage layout comparison doesn't compile anything
+    if let Some(Commands::StorageDiff { old, new }) = &cli.command {
+        let (old_artifact, new_artifact) = match (Artifact::import(old), Artifact::import(new)) {
+            (Ok(o), Ok(n)) => (o, n),
+            (Err(e), _) | (_, Err(e)) => {
+                eprintln!("{}", Paint::red(format!("Failed to read artifact: {}", e)));
+                std::process::exit(1);
+            }
+        };
+        let changes =
+            diff_storage_layouts(&old_artifact.storage_layout, &new_artifact.storage_layout);
+        if changes.is_empty() {
+            println!("{}", Paint::green("Storage layouts are compatible"));
+            return
+        }
+        changes.iter().for_each(|c| eprintln!("{}", Paint::red(c)));
+        std::process::exit(1);
+    }
+
+    // Disassembly doesn't compile anything
+    if let Some(Commands::Disasm { input, runtime }) = &cli.command {
+        let (bytecode, source_map) = if Path::new(input).is_file() {
+            match Artifact::import(input) {
+                Ok(a) if *runtime => (a.runtime, a.runtime_source_map),
+                Ok(a) => (a.bytecode, a.source_map),
+                Err(e) => {
+                    eprintln!("{}", Paint::red(format!("Failed to read artifact: {}", e)));
+                    std::process::exit(1);
+                }
+            }
+        } else {
+            (input.clone(), vec![])
+        };
+        let code = match parse_hex(bytecode.trim()) {
+            Some(c) => c,
+            None => {
+                eprintln!("{}", Paint::red(format!("Invalid bytecode: {}", bytecode)));
+                std::process::exit(1);
+            }
+        };
+        print!("{}", format_disassembly(&disassemble(&code, &source_map), &source_map));
+        return
+    }
+
+    // ABI imports don't compile anything
+    if let Some(Commands::AbiImport { path }) = &cli.command {
+        let abi =
+            match std::fs::read_to_string(path).map_err(|e| e.to_string()).and_then(|source| {
+                match FileSource::is_sol_interface(path) {
+                    true => parse_sol_interface(&source),
+                    false => Abi::from_json(&source).map_err(|e| e.to_string()),
+                }
+            }) {
+                Ok(abi) => abi,
+                Err(e) => {
+                    eprintln!("{}", Paint::red(format!("Failed to read ABI \"{}\": {}", path, e)));
+                    std::process::exit(1);
+                }
+            };
+        print!("{}", gen_huff_interface(&abi));
+        return
+    }
+
+    // Expansion and selectors take the contract path after the subcommand
+    if let Some(
+        Commands::Expand { path, .. } |
+        Commands::Selectors { path, .. } |
+        Commands::Bindings { path, .. },
+    ) = &cli.command
+    {
+        cli.path = Some(path.clone());
+    }
+
     // Create compiler from the Huff Args
     let sources: Arc<Vec<String>> = match cli.get_inputs() {
         Ok(s) => Arc::new(s),
@@ -180,6 +387,39 @@ fn main() {
         _ => None,
     };
 
+    // Load the external ABI to check selector collisions against
+    let external_abi = cli.collisions.as_ref().map(|path| match Artifact::import(path) {
+        Ok(artifact) => artifact.abi.unwrap_or_default(),
+        Err(e) => {
+            eprintln!("{}", Paint::red(format!("Failed to read artifact \"{}\": {}", path, e)));
+            std::process::exit(1);
+        }
+    });
+
+    let artifact_format = match ArtifactFormat::try_from(cli.artifact_format.as_str()) {
+        Ok(f) => f,
+        Err(e) => {
+            eprintln!("{}", Paint::red(e));
+            std::process::exit(1);
+        }
+    };
+
+    let storage_order = match StorageOrder::try_from(cli.storage_order.as_str()) {
+        Ok(o) => o,
+        Err(e) => {
+            eprintln!("{}", Paint::red(e));
+            std::process::exit(1);
+        }
+    };
+
+    let memory_base = match usize::from_str_radix(cli.memory_base.trim_start_matches("0x"), 16) {
+        Ok(b) => b,
+        Err(_) => {
+            eprintln!("{}", Paint::red(format!("Invalid memory base: {}", cli.memory_base)));
+            std::process::exit(1);
+        }
+    };
+
     let compiler: Compiler = Compiler {
         sources: Arc::clone(&sources),
         output,
@@ -188,15 +428,25 @@ fn main() {
         optimize: cli.optimize,
         bytecode: cli.bytecode,
         cached: use_cache,
+        external_abi,
+        artifact_format,
+        allocation: Allocation { storage_order, memory_base },
     };
 
-    if let Some(TestCommands::Test { format, match_ }) = cli.test {
+    if let Some(Commands::Test { format, match_ }) = cli.command {
         match compiler.grab_contracts() {
             Ok(contracts) => {
                 let match_ = Rc::new(match_);
 
                 for contract in &contracts {
-                    let tester = HuffTester::new(contract, Rc::clone(&match_));
+                    let env = match compiler.constant_env(contract) {
+                        Ok(env) => env,
+                        Err(e) => {
+                            eprintln!("{}", Paint::red(CompilerError::CodegenError(e)));
+                            std::process::exit(1);
+                        }
+                    };
+                    let tester = HuffTester::new(contract, &env, Rc::clone(&match_));
 
                     let start = Instant::now();
                     match tester.execute() {
@@ -219,6 +469,213 @@ fn main() {
         return
     }
 
+    if let Some(Commands::Expand { macro_name, .. }) = &cli.command {
+        match compiler.grab_contracts() {
+            Ok(contracts) => {
+                for contract in &contracts {
+                    let expanded = compiler
+                        .constant_env(contract)
+                        .and_then(|env| Codegen::expand_macro(macro_name, contract, &env));
+                    match expanded {
+                        Ok(expanded) => print!("{}", expanded),
+                        Err(e) => {
+                            let e = CompilerError::CodegenError(e);
+                            tracing::error!(target: "cli", "EXPANSION ERRORED: {}", e);
+                            eprintln!("{}", Paint::red(format!("{}", e)));
+                            std::process::exit(1);
+                        }
+                    }
+                }
+            }
+            Err(e) => {
+                tracing::error!(target: "cli", "PARSER ERRORED!");
+                eprintln!("{}", Paint::red(e));
+                std::process::exit(1);
+            }
+        }
+        return
+    }
+
+    if let Some(Commands::Selectors { format, .. }) = &cli.command {
+        match compiler.grab_contracts() {
+            Ok(contracts) => {
+                for contract in &contracts {
+                    let selectors = contract.selectors();
+                    match format {
+                        SelectorsFormat::Json => match selectors.to_json() {
+                            Ok(json) => println!("{}", json),
+                            Err(e) => {
+                                eprintln!("{}", Paint::red(format!("{}", e)));
+                                std::process::exit(1);
+                            }
+                        },
+                        SelectorsFormat::Table => print!("{}", selectors),
+                    }
+                }
+            }
+            Err(e) => {
+                tracing::error!(target: "cli", "PARSER ERRORED!");
+                eprintln!("{}", Paint::red(e));
+                std::process::exit(1);
+            }
+        }
+        return
+    }
+
+    if let Some(Commands::Bindings { path, lang, out, check }) = &cli.command {
+        let language = match BindingsLanguage::try_from(lang.as_str()) {
+            Ok(l) => l,
+            Err(e) => {
+                eprintln!("{}", Paint::red(e));
+                std::process::exit(1);
+            }
+        };
+
+        // Bindings are generated from the sources, without reading or writing artifacts
+        let artifacts = match compiler.compile() {
+            Ok(artifacts) => artifacts,
+            Err(e) => {
+                tracing::error!(target: "cli", "COMPILER ERRORED: {}", e);
+                eprintln!("{}", Paint::red(format!("{}", e)));
+                std::process::exit(1);
+            }
+        };
+
+        let bindings = match gen_bindings(&artifacts, language) {
+            Ok(b) => b,
+            Err(e) => {
+                eprintln!("{}", Paint::red(e));
+                std::process::exit(1);
+            }
+        };
+
+        if *check {
+            let stale = check_bindings(out, &bindings, language);
+            if !stale.is_empty() {
+                eprintln!(
+                    "{}",
+                    Paint::red(format!(
+                        "Bindings in \"{}\" are out of date: {}\nRegenerate them with `huffc bindings {} --lang {} --out {}`",
+                        out,
+                        stale.join(", "),
+                        path,
+                        language.arg(),
+                        out
+                    ))
+                );
+                std::process::exit(1);
+            }
+            println!("Bindings in \"{}\" are up to date", out);
+        } else if let Err(e) = export_bindings(out, &bindings) {
+            eprintln!("{}", Paint::red(format!("Failed to write bindings to \"{}\": {}", out, e)));
+            std::process::exit(1);
+        } else {
+            println!(
+                "Generated bindings: {}",
+                Paint::blue(
+                    bindings
+                        .iter()
+                        .map(|(f, _)| format!("{}/{}", out, f))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            );
+        }
+        return
+    }
+
+    if let Some(Commands::Create2 { deployer, salt, search_prefix }) = cli.command {
+        let deployer: [u8; 20] = match parse_hex(&deployer).and_then(|d| d.try_into().ok()) {
+            Some(d) => d,
+            None => {
+                eprintln!("{}", Paint::red(format!("Invalid deployer address: {}", deployer)));
+                std::process::exit(1);
+            }
+        };
+
+        // Only the init code is needed, so artifacts are neither read from nor written to disk
+        let artifacts = match compiler.compile() {
+            Ok(artifacts) => artifacts,
+            Err(e) => {
+                tracing::error!(target: "cli", "COMPILER ERRORED: {}", e);
+                eprintln!("{}", Paint::red(format!("{}", e)));
+                std::process::exit(1);
+            }
+        };
+
+        for artifact in &artifacts {
+            // The artifact bytecode includes any appended constructor arguments
+            let init_code = match parse_hex(&artifact.bytecode) {
+                Some(c) => c,
+                None => {
+                    eprintln!(
+                        "{}",
+                        Paint::red(format!(
+                            "Invalid bytecode for \"{}\": {}",
+                            artifact.file.path, artifact.bytecode
+                        ))
+                    );
+                    std::process::exit(1);
+                }
+            };
+            let mut init_code_hash = [0u8; 32];
+            hash_raw_bytes(&mut init_code_hash, &init_code);
+
+            let (salt, address) = match (&salt, &search_prefix) {
+                (_, Some(prefix)) => {
+                    let nibbles = match parse_nibbles(prefix) {
+                        Some(n) if n.len() <= 40 => n,
+                        _ => {
+                            eprintln!(
+                                "{}",
+                                Paint::red(format!("Invalid address prefix: {}", prefix))
+                            );
+                            std::process::exit(1);
+                        }
+                    };
+                    let start = Instant::now();
+                    let found =
+                        search_salt(&deployer, &init_code_hash, &nibbles, None, |tried, best| {
+                            eprint!(
+                                "\rSearched {} salts in {:.1}s, best match: {}",
+                                tried,
+                                start.elapsed().as_secs_f64(),
+                                to_checksum(&best.address.into(), None)
+                            );
+                        });
+                    eprintln!();
+                    (found.salt, found.address)
+                }
+                (Some(salt), None) => {
+                    let salt = match parse_hex(salt).filter(|s| s.len() <= 32) {
+                        Some(s) => {
+                            let mut padded = [0u8; 32];
+                            padded[32 - s.len()..].copy_from_slice(&s);
+                            padded
+                        }
+                        None => {
+                            eprintln!("{}", Paint::red(format!("Invalid salt: {}", salt)));
+                            std::process::exit(1);
+                        }
+                    };
+                    (salt, create2_address(&deployer, &salt, &init_code_hash))
+                }
+                (None, None) => {
+                    eprintln!("{}", Paint::red("Either a --salt or a --search-prefix is required"));
+                    std::process::exit(1);
+                }
+            };
+
+            println!(
+                "\"{}\" salt: 0x{} address: {}",
+                artifact.file.path,
+                hex::encode(salt),
+                Paint::green(to_checksum(&address.into(), None))
+            );
+        }
+        return
+    }
+
     // Create compiling spinner
     tracing::debug!(target: "cli", "[⠔] COMPILING");
     let mut sp: Option<Spinner> = None;
@@ -256,6 +713,7 @@ fn main() {
                             .collect::<Vec<Span>>(),
                     ),
                     token: None,
+                    backtrace: Box::default(),
                 });
                 tracing::error!(target: "cli", "COMPILER ERRORED: {}", e);
                 eprintln!("{}", Paint::red(format!("{}", e)));
@@ -290,7 +748,7 @@ fn main() {
                     tracing::warn!(target: "cli", "Interface override ignored since multiple artifacts were generated");
                 }
                 tracing::info!(target: "cli", "GENERATING SOLIDITY INTERFACES FROM ARTIFACTS");
-                let interfaces = gen_sol_interfaces(&artifacts, interface);
+                let interfaces = gen_sol_interfaces(&artifacts, interface, cli.license, cli.pragma);
                 if export_interfaces(&interfaces).is_ok() {
                     tracing::info!(target: "cli", "GENERATED SOLIDITY INTERFACES FROM ARTIFACTS SUCCESSFULLY");
                     println!(
@@ -368,6 +826,7 @@ fn main() {
                     Compiler::export_artifacts(
                         &artifacts,
                         &OutputLocation(cli.output.unwrap_or_else(|| cli.outputdir.clone())),
+                        artifact_format,
                     );
                     tracing::info!(target: "cli", "RE-EXPORTED INTERACTIVE ARTIFACTS");
                 }
@@ -399,6 +858,23 @@ fn main() {
                         .for_each(|a| println!("\"{}\" runtime: {}", a.file.path, a.runtime)),
                 }
             }
+
+            if cli.abi {
+                for artifact in &artifacts {
+                    let abi = artifact.abi.clone().unwrap_or_default();
+                    let json = match abi.to_json() {
+                        Ok(json) => json,
+                        Err(e) => {
+                            eprintln!("{}", Paint::red(format!("Failed to serialize ABI: {}", e)));
+                            std::process::exit(1);
+                        }
+                    };
+                    match sources.len() {
+                        1 => println!("{}", json),
+                        _ => println!("\"{}\" abi: {}", artifact.file.path, json),
+                    }
+                }
+            }
         }
         Err(e) => {
             tracing::error!(target: "cli", "COMPILER ERRORED: {}", e);
@@ -408,6 +884,16 @@ fn main() {
     }
 }
 
+/// Decodes a hex string with an optional `0x` prefix, rejecting odd-length input
+fn parse_hex(s: &str) -> Option<Vec<u8>> {
+    hex::decode(s.trim_start_matches("0x")).ok()
+}
+
+/// Splits a hex string with an optional `0x` prefix into its nibbles
+fn parse_nibbles(s: &str) -> Option<Vec<u8>> {
+    s.trim_start_matches("0x").chars().map(|c| c.to_digit(16).map(|d| d as u8)).collect()
+}
+
 impl Huff {
     /// Preprocesses input files for compiling
     pub fn get_inputs(&self) -> Result<Vec<String>, CompilerError> {
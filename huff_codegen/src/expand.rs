@@ -0,0 +1,357 @@
+use huff_utils::prelude::*;
+use std::{collections::BTreeMap, str::FromStr};
+
+use crate::{irgen::prelude::*, Codegen};
+
+/// A macro being expanded
+struct Frame {
+    /// The macro definition
+    macro_def: MacroDefinition,
+    /// The invocation of the macro, or `None` for the expanded macro
+    invocation: Option<MacroInvocation>,
+    /// The unique expansion id, used to rename labels
+    id: usize,
+}
+
+/// Expands a macro into fully inlined Huff source
+pub(crate) struct Expander<'a> {
+    /// The contract being expanded
+    contract: &'a Contract,
+    /// The resolved constants of the contract
+    env: &'a ConstantEnv,
+    /// The macros being expanded, outermost first
+    frames: Vec<Frame>,
+    /// The number of expansions so far
+    expansions: usize,
+    /// The bytecode offsets of tables utilized by the expanded macro
+    table_offsets: BTreeMap<String, usize>,
+    /// The expanded source
+    out: String,
+}
+
+impl<'a> Expander<'a> {
+    /// Public associated function to instantiate a new Expander.
+    pub(crate) fn new(contract: &'a Contract, env: &'a ConstantEnv) -> Self {
+        Self {
+            contract,
+            env,
+            frames: vec![],
+            expansions: 0,
+            table_offsets: BTreeMap::new(),
+            out: String::new(),
+        }
+    }
+
+    /// Expands the macro definition
+    pub(crate) fn expand(mut self, macro_def: MacroDefinition) -> Result<String, CodegenError> {
+        // Tables are appended after the code, so their offsets require the full bytecode
+        let res = Codegen::macro_to_bytecode(
+            &macro_def,
+            self.contract,
+            self.env,
+            &mut vec![macro_def.clone()],
+            0,
+            &mut Vec::default(),
+        )?;
+        let mut table_offset = res.bytes.iter().map(|(_, b)| b.0.len()).sum::<usize>() / 2;
+        for table in res.utilized_tables {
+            self.table_offsets.insert(table.name.clone(), table_offset);
+            table_offset +=
+                hex_to_usize(&bytes32_to_string(&table.size, false)).unwrap_or_default();
+        }
+
+        let kind = match (macro_def.outlined, macro_def.test) {
+            (true, _) => "fn",
+            (_, true) => "test",
+            _ => "macro",
+        };
+        self.out = format!(
+            "#define {} {}({}) = takes ({}) returns ({}) {{\n",
+            kind,
+            macro_def.name,
+            macro_def
+                .parameters
+                .iter()
+                .filter_map(|p| p.name.clone())
+                .collect::<Vec<String>>()
+                .join(", "),
+            macro_def.takes,
+            macro_def.returns
+        );
+
+        let statements = macro_def.statements.clone();
+        self.frames.push(Frame { macro_def, invocation: None, id: 0 });
+        self.expand_statements(&statements, 1)?;
+        self.out.push_str("}\n");
+
+        Ok(self.out)
+    }
+
+    /// Writes an indented line
+    fn line(&mut self, depth: usize, line: &str) {
+        self.out.push_str(&format!("{}{}\n", "    ".repeat(depth), line));
+    }
+
+    /// Expands statements of the innermost frame
+    fn expand_statements(
+        &mut self,
+        statements: &[Statement],
+        depth: usize,
+    ) -> Result<(), CodegenError> {
+        let frame = self.frames.len() - 1;
+
+        for s in &expand_directives(statements, self.contract, self.env)? {
+            match &s.ty {
+                StatementType::Literal(l) => self.line(depth, &bytes32_to_string(l, true)),
+                StatementType::Opcode(o) => self.line(depth, o.mnemonic()),
+                StatementType::Code(c) => self.line(depth, c),
+                StatementType::Constant(name) => {
+                    let push_bytes = constant_gen(name, self.env, s.span.clone())?;
+                    self.line(depth, &format!("{} // [{}]", push_literal(&push_bytes), name));
+                }
+                StatementType::ArgCall(arg_name) => {
+                    match self.resolve_arg(arg_name, frame, &s.span)? {
+                        Some(arg) => self.line(depth, &format!("{} // <{}>", arg, arg_name)),
+                        None => self.line(depth, &format!("<{}>", arg_name)),
+                    }
+                }
+                StatementType::LabelCall(label) => {
+                    let label = self.resolve_label(label, frame);
+                    self.line(depth, &label);
+                }
+                StatementType::Label(label) => {
+                    let name = self.label_name(&label.name, frame);
+                    self.line(depth, &format!("{}:", name));
+                    self.expand_statements(&label.inner, depth + 1)?;
+                }
+                StatementType::MacroInvocation(mi) => {
+                    let ir_macro = match self.contract.find_macro_by_name(&mi.macro_name) {
+                        Some(m) => m.clone(),
+                        None => {
+                            tracing::error!(
+                                target: "codegen",
+                                "MISSING MACRO INVOCATION \"{}\"",
+                                mi.macro_name
+                            );
+                            return Err(CodegenError {
+                                kind: CodegenErrorKind::InvalidMacroInvocation(
+                                    mi.macro_name.clone(),
+                                ),
+                                span: mi.span.clone(),
+                                token: None,
+                                backtrace: Box::default(),
+                            })
+                        }
+                    };
+                    self.expand_invocation(mi, ir_macro, depth)?;
+                }
+                StatementType::ArgMacroInvocation(mi) => {
+                    let (scope, mis) = self.scope();
+                    match bubble_macro_arg(&mi.macro_name, self.contract, &scope, &mis, &mi.span) {
+                        Ok(ir_macro) => {
+                            let mi =
+                                MacroInvocation { macro_name: ir_macro.name.clone(), ..mi.clone() };
+                            self.expand_invocation(&mi, ir_macro, depth)?;
+                        }
+                        // Arguments of the expanded macro itself can't be resolved
+                        Err(CodegenError {
+                            kind: CodegenErrorKind::MissingMacroArgument(_),
+                            ..
+                        }) => self.line(
+                            depth,
+                            &format!(
+                                "<{}>({})",
+                                mi.macro_name,
+                                mi.args
+                                    .iter()
+                                    .map(macro_arg_string)
+                                    .collect::<Vec<String>>()
+                                    .join(", ")
+                            ),
+                        ),
+                        Err(e) => return Err(e),
+                    }
+                }
+                // Conditionals are resolved by `expand_directives`
+                StatementType::Conditional(_) => {}
+                StatementType::BuiltinFunctionCall(bf) => {
+                    let call = format!(
+                        "{}({})",
+                        bf.kind,
+                        bf.args
+                            .iter()
+                            .filter_map(|a| a.name.clone())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    );
+
+                    let value = if matches!(bf.kind, BuiltinFunctionKind::Tablestart) {
+                        bf.args[0]
+                            .name
+                            .as_ref()
+                            .and_then(|t| self.table_offsets.get(t))
+                            .map(|o| format!("0x{}", format_even_bytes(format!("{:x}", o))))
+                    } else {
+                        Some(push_literal(&self.builtin_gen(s)?))
+                    };
+
+                    match value {
+                        Some(v) => self.line(depth, &format!("{} // {}", v, call)),
+                        None => self.line(depth, &call),
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Inlines an invocation of a macro
+    fn expand_invocation(
+        &mut self,
+        mi: &MacroInvocation,
+        ir_macro: MacroDefinition,
+        depth: usize,
+    ) -> Result<(), CodegenError> {
+        let invocation = format!(
+            "{}({})",
+            mi.macro_name,
+            mi.args.iter().map(macro_arg_string).collect::<Vec<String>>().join(", ")
+        );
+
+        // Outlined macros are jumped to rather than inlined
+        if ir_macro.outlined {
+            self.line(depth, &format!("{} // outlined", invocation));
+            return Ok(())
+        }
+
+        let (scope, mis) = self.scope();
+        check_recursion(&ir_macro.name, &scope, &mis, &mi.span)?;
+        check_macro_arity(&ir_macro, mi)?;
+        check_macro_arg_kinds(&ir_macro, mi, &scope, &mis, self.contract, self.env)?;
+
+        self.line(depth, &format!("// {} {{", invocation));
+        self.expansions += 1;
+        let (macro_name, invoked_from) =
+            (ir_macro.name.clone(), self.frames[self.frames.len() - 1].macro_def.name.clone());
+        let statements = ir_macro.statements.clone();
+        self.frames.push(Frame {
+            macro_def: ir_macro,
+            invocation: Some(mi.clone()),
+            id: self.expansions,
+        });
+        self.expand_statements(&statements, depth + 1)
+            .map_err(|e| e.in_expansion_of(&macro_name, &invoked_from, &mi.span))?;
+        self.frames.pop();
+        self.line(depth, "// }");
+
+        Ok(())
+    }
+
+    /// The codegen scope and macro invocations of the frames
+    fn scope(&self) -> (Vec<MacroDefinition>, Vec<(usize, MacroInvocation)>) {
+        let scope = self.frames.iter().map(|f| f.macro_def.clone()).collect::<Vec<_>>();
+        let mis = self
+            .frames
+            .iter()
+            .filter_map(|f| f.invocation.clone().map(|mi| (0, mi)))
+            .collect::<Vec<_>>();
+        (scope, mis)
+    }
+
+    /// Evaluates a builtin function call into its push bytes
+    fn builtin_gen(&self, s: &Statement) -> Result<String, CodegenError> {
+        let (mut scope, mut mis) = self.scope();
+        let macro_def = scope[scope.len() - 1].clone();
+
+        let bytes = statement_gen(
+            s,
+            self.contract,
+            self.env,
+            &macro_def,
+            &mut scope,
+            &mut 0,
+            &mut mis,
+            &mut JumpTable::new(),
+            &mut LabelIndices::new(),
+            &mut Jumps::new(),
+            &mut Vec::new(),
+            &mut Vec::new(),
+            0,
+        )?;
+
+        Ok(bytes.into_iter().map(|(_, b)| b.0).collect())
+    }
+
+    /// Resolves an arg call in the given frame by bubbling up through the macro invocations.
+    ///
+    /// Returns `None` for arguments of the expanded macro itself, which can't be resolved.
+    fn resolve_arg(
+        &self,
+        arg_name: &str,
+        frame: usize,
+        span: &AstSpan,
+    ) -> Result<Option<String>, CodegenError> {
+        let Frame { macro_def, invocation, .. } = &self.frames[frame];
+        let (param, arg) = match macro_def
+            .parameters
+            .iter()
+            .position(|p| p.name.as_deref() == Some(arg_name))
+            .and_then(|pos| Some((&macro_def.parameters[pos], invocation.as_ref()?.args.get(pos)?)))
+        {
+            Some(found) => found,
+            None => return Ok(None),
+        };
+
+        match arg {
+            MacroArg::Literal(l) => Ok(Some(bytes32_to_string(l, true))),
+            MacroArg::ArgCall(ac) => self.resolve_arg(ac, frame - 1, span),
+            MacroArg::Ident(iden) => {
+                let is_constant = self.env.find_constant_by_name(iden).is_some();
+                let label_param = param.kind == Some(MacroParamKind::Label);
+
+                if label_param {
+                    Ok(Some(self.resolve_label(iden, frame - 1)))
+                } else if is_constant {
+                    Ok(Some(push_literal(&constant_gen(iden, self.env, span.clone())?)))
+                } else if let Ok(o) = Opcode::from_str(iden) {
+                    Ok(Some(o.mnemonic().to_string()))
+                } else {
+                    // Labels passed as arguments belong to the invoking macro
+                    Ok(Some(self.resolve_label(iden, frame - 1)))
+                }
+            }
+        }
+    }
+
+    /// Resolves a label call to the renamed label of the closest enclosing macro defining it
+    fn resolve_label(&self, label: &str, frame: usize) -> String {
+        (0..=frame)
+            .rev()
+            .find(|f| MacroDefinition::defines_label(&self.frames[*f].macro_def.statements, label))
+            .map(|f| self.label_name(label, f))
+            .unwrap_or_else(|| label.to_string())
+    }
+
+    /// The unique name of a label defined in the given frame
+    fn label_name(&self, label: &str, frame: usize) -> String {
+        match self.frames[frame].id {
+            0 => label.to_string(),
+            id => format!("{}_{}", label, id),
+        }
+    }
+}
+
+/// Formats push bytes as a literal
+fn push_literal(push_bytes: &str) -> String {
+    format!("0x{}", &push_bytes[2..])
+}
+
+/// Formats a macro argument as it's written in source
+fn macro_arg_string(arg: &MacroArg) -> String {
+    match arg {
+        MacroArg::Literal(l) => bytes32_to_string(l, true),
+        MacroArg::Ident(i) => i.clone(),
+        MacroArg::ArgCall(ac) => format!("<{}>", ac),
+    }
+}
@@ -12,7 +12,7 @@ pub fn bubble_arg_call(
     arg_name: &str,
     bytes: &mut Vec<(usize, Bytes)>,
     macro_def: &MacroDefinition,
-    contract: &Contract,
+    env: &ConstantEnv,
     scope: &mut Vec<MacroDefinition>,
     offset: &mut usize,
     // mis: Parent macro invocations and their indices
@@ -63,6 +63,7 @@ pub fn bubble_arg_call(
                                     ),
                                     span: bubbled_macro_invocation.span,
                                     token: None,
+                                    backtrace: Box::default(),
                                 })
                             }
                         };
@@ -71,7 +72,7 @@ pub fn bubble_arg_call(
                                 arg_name,
                                 bytes,
                                 &bubbled_macro_invocation,
-                                contract,
+                                env,
                                 &mut new_scope,
                                 offset,
                                 &mut Vec::from(&mis[..mis.len().saturating_sub(1)]),
@@ -82,7 +83,7 @@ pub fn bubble_arg_call(
                                 arg_name,
                                 bytes,
                                 &bubbled_macro_invocation,
-                                contract,
+                                env,
                                 &mut new_scope,
                                 offset,
                                 mis,
@@ -93,19 +94,13 @@ pub fn bubble_arg_call(
                     MacroArg::Ident(iden) => {
                         tracing::debug!(target: "codegen", "Found MacroArg::Ident IN \"{}\" Macro Invocation: \"{}\"!", macro_invoc.1.macro_name, iden);
 
+                        // Idents passed to `label` parameters are always label calls
+                        let label_param =
+                            macro_def.parameters[pos].kind == Some(MacroParamKind::Label);
+
                         // Check for a constant first
-                        if let Some(constant) = contract
-                            .constants
-                            .lock()
-                            .map_err(|_| {
-                                CodegenError::new(
-                                    CodegenErrorKind::LockingError,
-                                    AstSpan(vec![]),
-                                    None,
-                                )
-                            })?
-                            .iter()
-                            .find(|const_def| const_def.name.eq(iden))
+                        if let Some(constant) =
+                            env.find_constant_by_name(iden).filter(|_| !label_param)
                         {
                             tracing::info!(target: "codegen", "ARGCALL IS CONSTANT: {:?}", constant);
                             let push_bytes = match &constant.value {
@@ -113,22 +108,26 @@ pub fn bubble_arg_call(
                                     let hex_literal: String = bytes32_to_string(l, false);
                                     format!("{:02x}{}", 95 + hex_literal.len() / 2, hex_literal)
                                 }
-                                ConstVal::FreeStoragePointer(fsp) => {
-                                    // If this is reached in codegen stage,
-                                    // `derive_storage_pointers`
-                                    // method was not called on the AST.
-                                    tracing::error!(target: "codegen", "STORAGE POINTERS INCORRECTLY DERIVED FOR \"{:?}\"", fsp);
+                                fp => {
+                                    // If this is reached in codegen stage, the pointer is
+                                    // only passed as an argument, which deriving storage
+                                    // pointers in traversal order doesn't reach.
+                                    tracing::error!(target: "codegen", "STORAGE POINTERS INCORRECTLY DERIVED FOR \"{:?}\"", fp);
                                     return Err(CodegenError {
-                                        kind: CodegenErrorKind::StoragePointersNotDerived,
-                                        span: AstSpan(vec![]),
+                                        kind: CodegenErrorKind::StoragePointerNotDerived(
+                                            constant.name.clone(),
+                                        ),
+                                        span: constant.span.clone(),
                                         token: None,
+                                        backtrace: Box::default(),
                                     })
                                 }
                             };
                             *offset += push_bytes.len() / 2;
                             tracing::info!(target: "codegen", "OFFSET: {}, PUSH BYTES: {:?}", offset, push_bytes);
                             bytes.push((starting_offset, Bytes(push_bytes)));
-                        } else if let Ok(o) = Opcode::from_str(iden) {
+                        } else if let Some(o) = Opcode::from_str(iden).ok().filter(|_| !label_param)
+                        {
                             tracing::debug!(target: "codegen", "Found Opcode: {}", o);
                             let b = Bytes(o.to_string());
                             *offset += b.0.len() / 2;
@@ -173,3 +172,169 @@ pub fn bubble_arg_call(
 
     Ok(())
 }
+
+/// Resolves an argument invoked as a macro, ie. `<body>()`, to the macro it was passed as.
+///
+/// Arg calls are bubbled up through the parent macro invocations until the macro is found.
+pub fn bubble_macro_arg(
+    arg_name: &str,
+    contract: &Contract,
+    scope: &[MacroDefinition],
+    mis: &[(usize, MacroInvocation)],
+    span: &AstSpan,
+) -> Result<MacroDefinition, CodegenError> {
+    let arg = match (scope.last(), mis.last()) {
+        (Some(macro_def), Some(macro_invoc)) => macro_def
+            .parameters
+            .iter()
+            .position(|p| p.name.as_deref() == Some(arg_name))
+            .and_then(|pos| macro_invoc.1.args.get(pos)),
+        _ => None,
+    };
+
+    match arg {
+        Some(MacroArg::Ident(name)) => contract.find_macro_by_name(name).cloned().ok_or_else(|| {
+            tracing::error!(target: "codegen", "ARGUMENT \"{}\" IS NOT A MACRO: {}", arg_name, name);
+            CodegenError {
+                kind: CodegenErrorKind::InvalidMacroArgument(name.clone()),
+                span: span.clone(),
+                token: None,
+                backtrace: Box::default(),
+            }
+        }),
+        Some(MacroArg::ArgCall(ac)) => {
+            tracing::debug!(target: "codegen", "~~~ BUBBLING UP MACRO ARGUMENT \"{}\"", ac);
+            bubble_macro_arg(
+                ac,
+                contract,
+                &scope[..scope.len() - 1],
+                &mis[..mis.len() - 1],
+                span,
+            )
+        }
+        Some(MacroArg::Literal(l)) => {
+            tracing::error!(target: "codegen", "ARGUMENT \"{}\" IS A LITERAL", arg_name);
+            Err(CodegenError {
+                kind: CodegenErrorKind::InvalidMacroArgument(bytes32_to_string(l, true)),
+                span: span.clone(),
+                token: None,
+                backtrace: Box::default(),
+            })
+        }
+        None => {
+            tracing::error!(target: "codegen", "MISSING MACRO ARGUMENT \"{}\"", arg_name);
+            Err(CodegenError {
+                kind: CodegenErrorKind::MissingMacroArgument(arg_name.to_string()),
+                span: span.clone(),
+                token: None,
+                backtrace: Box::default(),
+            })
+        }
+    }
+}
+
+/// Checks that a macro is invoked with as many arguments as it has parameters.
+pub fn check_macro_arity(
+    ir_macro: &MacroDefinition,
+    mi: &MacroInvocation,
+) -> Result<(), CodegenError> {
+    if ir_macro.parameters.len() != mi.args.len() {
+        tracing::error!(target: "codegen", "MACRO ARITY MISMATCH: {}", ir_macro.name);
+        return Err(CodegenError {
+            kind: CodegenErrorKind::MacroArityMismatch(
+                ir_macro.name.clone(),
+                ir_macro.parameters.len(),
+                mi.args.len(),
+            ),
+            span: mi.span.clone(),
+            token: None,
+            backtrace: Box::default(),
+        })
+    }
+    Ok(())
+}
+
+/// Checks the arguments of a macro invocation against the kinds of the macro's typed parameters.
+///
+/// Label arguments must be defined by the invoked macro or a macro in scope. Arg calls are checked
+/// against the kind of the invoking macro's parameter, or, if it's untyped, bubbled up through the
+/// parent macro invocations to the argument it was passed.
+pub fn check_macro_arg_kinds(
+    ir_macro: &MacroDefinition,
+    mi: &MacroInvocation,
+    scope: &[MacroDefinition],
+    mis: &[(usize, MacroInvocation)],
+    contract: &Contract,
+    env: &ConstantEnv,
+) -> Result<(), CodegenError> {
+    for (param, arg) in ir_macro.parameters.iter().zip(mi.args.iter()) {
+        let kind = match param.kind {
+            Some(k) => k,
+            None => continue,
+        };
+        if !arg_is_kind(kind, arg, ir_macro, scope, mis, contract, env) {
+            let param_name = param.name.clone().unwrap_or_default();
+            tracing::error!(target: "codegen", "ARGUMENT PASSED TO \"{}\" IN \"{}\" IS NOT A {}", param_name, ir_macro.name, kind);
+            return Err(CodegenError {
+                kind: CodegenErrorKind::MacroArgumentKindMismatch(kind, param_name),
+                span: mi.span.clone(),
+                token: None,
+                backtrace: Box::default(),
+            })
+        }
+    }
+
+    Ok(())
+}
+
+/// Whether a macro argument can be passed to a parameter of the given kind
+fn arg_is_kind(
+    kind: MacroParamKind,
+    arg: &MacroArg,
+    ir_macro: &MacroDefinition,
+    scope: &[MacroDefinition],
+    mis: &[(usize, MacroInvocation)],
+    contract: &Contract,
+    env: &ConstantEnv,
+) -> bool {
+    match arg {
+        MacroArg::Literal(_) => kind == MacroParamKind::Literal,
+        MacroArg::Ident(iden) => match kind {
+            MacroParamKind::Label => std::iter::once(ir_macro)
+                .chain(scope.iter())
+                .any(|m| MacroDefinition::defines_label(&m.statements, iden)),
+            MacroParamKind::Literal | MacroParamKind::Constant => {
+                env.find_constant_by_name(iden).is_some()
+            }
+            MacroParamKind::Opcode => Opcode::from_str(iden).is_ok(),
+            MacroParamKind::Macro => contract.find_macro_by_name(iden).is_some(),
+        },
+        MacroArg::ArgCall(ac) => {
+            let (outer, invocation) = match (scope.last(), mis.last()) {
+                (Some(m), Some(mi)) => (m, mi),
+                _ => return true,
+            };
+            let pos = match outer.parameters.iter().position(|p| p.name.as_deref() == Some(ac)) {
+                Some(pos) => pos,
+                None => return true,
+            };
+            match (outer.parameters[pos].kind, invocation.1.args.get(pos)) {
+                (Some(outer_kind), _) => {
+                    outer_kind == kind ||
+                        (outer_kind == MacroParamKind::Constant &&
+                            kind == MacroParamKind::Literal)
+                }
+                (None, Some(arg)) => arg_is_kind(
+                    kind,
+                    arg,
+                    ir_macro,
+                    &scope[..scope.len() - 1],
+                    &mis[..mis.len() - 1],
+                    contract,
+                    env,
+                ),
+                (None, None) => true,
+            }
+        }
+    }
+}
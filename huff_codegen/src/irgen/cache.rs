@@ -0,0 +1,94 @@
+use huff_utils::prelude::*;
+use std::{
+    collections::BTreeSet,
+    sync::{Mutex, MutexGuard},
+};
+
+/// Locks an environment's expansion cache
+fn lock(cache: &Mutex<ExpansionCache>) -> Result<MutexGuard<'_, ExpansionCache>, CodegenError> {
+    cache
+        .lock()
+        .map_err(|_| CodegenError::new(CodegenErrorKind::LockingError, AstSpan(vec![]), None))
+}
+
+/// Whether any of the macros are being generated, ie. are the root of the scope or an active
+/// invocation.
+fn is_generating(
+    macros: &BTreeSet<String>,
+    scope: &[MacroDefinition],
+    mis: &[(usize, MacroInvocation)],
+) -> bool {
+    scope.first().is_some_and(|root| macros.contains(&root.name)) ||
+        mis.iter().any(|(_, mi)| macros.contains(&mi.macro_name))
+}
+
+/// The names of the macros a bytecode result was generated from
+fn expanded_macros(res: &BytecodeRes) -> BTreeSet<String> {
+    res.source_map.iter().filter(|e| !e.data).map(|e| e.name.clone()).collect()
+}
+
+/// The cache key of an invocation, if its expansion doesn't depend on the invoking macros
+pub fn invocation_cache_key(mi: &MacroInvocation) -> Option<(String, Vec<MacroArg>)> {
+    if mi.args.iter().any(|arg| matches!(arg, MacroArg::ArgCall(_))) {
+        return None
+    }
+    Some((mi.macro_name.clone(), mi.args.clone()))
+}
+
+/// Returns the cached expansion of an invocation, relocated to the offset.
+///
+/// Expansions of macros that are being generated are not returned, so that the recursion is
+/// reported when generating the invocation.
+pub fn cached_invocation(
+    env: &ConstantEnv,
+    key: &(String, Vec<MacroArg>),
+    offset: usize,
+    scope: &[MacroDefinition],
+    mis: &[(usize, MacroInvocation)],
+) -> Result<Option<BytecodeRes>, CodegenError> {
+    Ok(lock(&env.expansions)?
+        .invocations
+        .get(key)
+        .filter(|cached| !is_generating(&cached.macros, scope, mis))
+        .map(|cached| cached.res.relocate(0, offset)))
+}
+
+/// Caches the expansion of an invocation generated at the offset, if it is position independent
+pub fn cache_invocation(
+    env: &ConstantEnv,
+    key: (String, Vec<MacroArg>),
+    res: &BytecodeRes,
+    offset: usize,
+) -> Result<(), CodegenError> {
+    if res.is_position_independent() {
+        tracing::debug!(target: "codegen", "CACHING EXPANSION OF \"{}\"", key.0);
+        let cached = CachedExpansion { res: res.relocate(offset, 0), macros: expanded_macros(res) };
+        lock(&env.expansions)?.invocations.insert(key, cached);
+    }
+    Ok(())
+}
+
+/// Returns the cached size of a macro passed to `__codesize`
+pub fn cached_codesize(
+    env: &ConstantEnv,
+    key: &(String, bool),
+    scope: &[MacroDefinition],
+    mis: &[(usize, MacroInvocation)],
+) -> Result<Option<usize>, CodegenError> {
+    Ok(lock(&env.expansions)?
+        .codesizes
+        .get(key)
+        .filter(|(_, macros)| !is_generating(macros, scope, mis))
+        .map(|(size, _)| *size))
+}
+
+/// Caches the size of a macro passed to `__codesize`
+pub fn cache_codesize(
+    env: &ConstantEnv,
+    key: (String, bool),
+    res: &BytecodeRes,
+) -> Result<usize, CodegenError> {
+    let size = res.bytes.iter().map(|(_, b)| b.0.len()).sum::<usize>() / 2;
+    lock(&env.expansions)?.codesizes.insert(key, (size, expanded_macros(res)));
+    Ok(size)
+}
@@ -1,19 +1,25 @@
 use huff_utils::prelude::{
-    bytes32_to_string, AstSpan, CodegenError, CodegenErrorKind, ConstVal, Contract,
+    bytes32_to_string, AstSpan, CodegenError, CodegenErrorKind, ConstVal, ConstantEnv, Literal,
 };
 
 /// Transforms a constant definition into it's respective bytecode
 pub fn constant_gen(
     name: &str,
-    contract: &Contract,
+    env: &ConstantEnv,
     ir_byte_span: AstSpan,
 ) -> Result<String, CodegenError> {
-    // Get the first `ConstantDefinition` that matches the constant's name
-    let constants = contract
-        .constants
-        .lock()
-        .map_err(|_| CodegenError::new(CodegenErrorKind::LockingError, AstSpan(vec![]), None))?;
-    let constant = if let Some(m) = constants.iter().find(|const_def| const_def.name.eq(&name)) {
+    let hex_literal: String = bytes32_to_string(&constant_value(name, env, ir_byte_span)?, false);
+    Ok(format!("{:02x}{}", 95 + hex_literal.len() / 2, hex_literal))
+}
+
+/// Evaluates a constant definition into its literal value
+pub fn constant_value(
+    name: &str,
+    env: &ConstantEnv,
+    ir_byte_span: AstSpan,
+) -> Result<Literal, CodegenError> {
+    // Get the resolved `ConstantDefinition` that matches the constant's name
+    let constant = if let Some(m) = env.find_constant_by_name(name) {
         m
     } else {
         tracing::error!(target: "codegen", "MISSING CONSTANT DEFINITION \"{}\"", name);
@@ -22,29 +28,25 @@ pub fn constant_gen(
             kind: CodegenErrorKind::MissingConstantDefinition(name.to_string()),
             span: ir_byte_span,
             token: None,
+            backtrace: Box::default(),
         })
     };
 
     // Generate bytecode for the constant
-    // Should always be a `Literal` if storage pointers were derived in the AST
-    // prior to generating the IR bytes.
+    // Always a `Literal`, unless it is a storage pointer the environment left underived
     tracing::info!(target: "codegen", "FOUND CONSTANT DEFINITION: {}", constant.name);
-    let push_bytes = match &constant.value {
-        ConstVal::Literal(l) => {
-            let hex_literal: String = bytes32_to_string(l, false);
-            format!("{:02x}{}", 95 + hex_literal.len() / 2, hex_literal)
-        }
-        ConstVal::FreeStoragePointer(fsp) => {
-            // If this is reached in codegen stage, the `derive_storage_pointers`
-            // method was not called on the AST.
-            tracing::error!(target: "codegen", "STORAGE POINTERS INCORRECTLY DERIVED FOR \"{:?}\"", fsp);
-            return Err(CodegenError {
-                kind: CodegenErrorKind::StoragePointersNotDerived,
+    match &constant.value {
+        ConstVal::Literal(l) => Ok(*l),
+        fp => {
+            // If this is reached in codegen stage, the pointer wasn't reached deriving storage
+            // pointers in traversal order, or the environment was built by hand.
+            tracing::error!(target: "codegen", "STORAGE POINTERS INCORRECTLY DERIVED FOR \"{:?}\"", fp);
+            Err(CodegenError {
+                kind: CodegenErrorKind::StoragePointerNotDerived(constant.name.clone()),
                 span: constant.span.clone(),
                 token: None,
+                backtrace: Box::default(),
             })
         }
-    };
-
-    Ok(push_bytes)
+    }
 }
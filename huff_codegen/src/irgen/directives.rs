@@ -0,0 +1,139 @@
+use huff_utils::prelude::*;
+
+use crate::irgen::constants::constant_value;
+
+/// The most code a `__REPEAT` call may unroll to, in bytes (the EIP-170 contract size limit)
+pub const MAX_REPEAT_SIZE: usize = 0x6000;
+
+/// Expands compile-time directives in a list of statements.
+///
+/// Conditional blocks are replaced by the statements of the branch selected by their constant,
+/// and `__REPEAT(n, MACRO)` calls are unrolled into `n` invocations of the macro. Macros taking a
+/// single parameter are passed the index of the repetition. Repetitions are capped at
+/// [MAX_REPEAT_SIZE], which bounds the unrolled code of any macro emitting bytecode.
+pub fn expand_directives(
+    statements: &[Statement],
+    contract: &Contract,
+    env: &ConstantEnv,
+) -> Result<Vec<Statement>, CodegenError> {
+    let mut expanded = vec![];
+
+    for s in statements {
+        match &s.ty {
+            StatementType::Conditional(c) => {
+                let value = constant_value(&c.condition, env, c.span.clone())?;
+                tracing::info!(target: "codegen", "EVALUATED CONDITION \"{}\": {}", c.condition, bytes32_to_string(&value, true));
+                let branch =
+                    if value.iter().any(|b| *b != 0) { &c.statements } else { &c.else_statements };
+                expanded.append(&mut expand_directives(branch, contract, env)?);
+            }
+            StatementType::BuiltinFunctionCall(bf) if bf.kind == BuiltinFunctionKind::Repeat => {
+                expanded.append(&mut expand_repeat(bf, contract, env)?);
+            }
+            StatementType::Label(l) => expanded.push(Statement {
+                ty: StatementType::Label(Label {
+                    inner: expand_directives(&l.inner, contract, env)?,
+                    ..l.clone()
+                }),
+                span: s.span.clone(),
+            }),
+            _ => expanded.push(s.clone()),
+        }
+    }
+
+    Ok(expanded)
+}
+
+/// Unrolls a `__REPEAT(n, MACRO)` call into macro invocations
+fn expand_repeat(
+    bf: &BuiltinFunctionCall,
+    contract: &Contract,
+    env: &ConstantEnv,
+) -> Result<Vec<Statement>, CodegenError> {
+    let (count, macro_name) = match bf.args.as_slice() {
+        [Argument { name: Some(count), .. }, Argument { name: Some(macro_name), .. }] => {
+            (count, macro_name)
+        }
+        _ => {
+            tracing::error!(target: "codegen", "INVALID __REPEAT ARGUMENTS: {:?}", bf.args);
+            return Err(CodegenError {
+                kind: CodegenErrorKind::InvalidArguments(
+                    "__REPEAT expects a count and a macro".to_string(),
+                ),
+                span: bf.span.clone(),
+                token: None,
+                backtrace: Box::default(),
+            })
+        }
+    };
+
+    // The count is either a literal or a constant
+    let count_hex = if env.find_constant_by_name(count).is_some() {
+        bytes32_to_string(&constant_value(count, env, bf.span.clone())?, false)
+    } else {
+        count.clone()
+    };
+    let count = hex_to_usize(&count_hex).map_err(|_| {
+        tracing::error!(target: "codegen", "INVALID __REPEAT COUNT: {}", count);
+        CodegenError {
+            kind: CodegenErrorKind::InvalidArguments(format!("Invalid __REPEAT count: {}", count)),
+            span: bf.span.clone(),
+            token: None,
+            backtrace: Box::default(),
+        }
+    })?;
+
+    let ir_macro = match contract.find_macro_by_name(macro_name) {
+        Some(m) => m,
+        None => {
+            tracing::error!(target: "codegen", "MISSING MACRO PASSED TO __REPEAT \"{}\"", macro_name);
+            return Err(CodegenError {
+                kind: CodegenErrorKind::InvalidMacroInvocation(macro_name.clone()),
+                span: bf.span.clone(),
+                token: None,
+                backtrace: Box::default(),
+            })
+        }
+    };
+    if ir_macro.parameters.len() > 1 {
+        tracing::error!(target: "codegen", "MACRO PASSED TO __REPEAT TAKES TOO MANY ARGUMENTS: {}", ir_macro.name);
+        return Err(CodegenError {
+            kind: CodegenErrorKind::MacroArityMismatch(
+                ir_macro.name.clone(),
+                ir_macro.parameters.len(),
+                1,
+            ),
+            span: bf.span.clone(),
+            token: None,
+            backtrace: Box::default(),
+        })
+    }
+
+    if count > MAX_REPEAT_SIZE {
+        tracing::error!(target: "codegen", "__REPEAT COUNT {} EXCEEDS {}", count, MAX_REPEAT_SIZE);
+        return Err(CodegenError {
+            kind: CodegenErrorKind::RepeatTooLarge(ir_macro.name.clone()),
+            span: bf.span.clone(),
+            token: None,
+            backtrace: Box::default(),
+        })
+    }
+
+    Ok((0..count)
+        .map(|i| {
+            let args = if ir_macro.parameters.is_empty() {
+                vec![]
+            } else {
+                vec![MacroArg::Literal(str_to_bytes32(&format!("{:x}", i)))]
+            };
+            Statement {
+                ty: StatementType::MacroInvocation(MacroInvocation {
+                    macro_name: ir_macro.name.clone(),
+                    args,
+                    span: bf.span.clone(),
+                }),
+                span: bf.span.clone(),
+            }
+        })
+        .collect())
+}
@@ -7,7 +7,13 @@ pub mod statements;
 /// Argument Call Module
 pub mod arg_calls;
 
+/// Directive Expansion Module
+pub mod directives;
+
+/// Expansion Cache Module
+pub mod cache;
+
 /// Prelude wraps common utilities.
 pub mod prelude {
-    pub use super::{arg_calls::*, constants::*, statements::*};
+    pub use super::{arg_calls::*, constants::*, directives::*, statements::*};
 }
@@ -1,12 +1,19 @@
 use huff_utils::prelude::*;
 
-use crate::Codegen;
+use crate::{
+    irgen::{
+        arg_calls::{bubble_macro_arg, check_macro_arg_kinds, check_macro_arity},
+        cache::*,
+    },
+    Codegen,
+};
 
 /// Generates the respective Bytecode for a given Statement
 #[allow(clippy::too_many_arguments)]
 pub fn statement_gen(
     s: &Statement,
     contract: &Contract,
+    env: &ConstantEnv,
     macro_def: &MacroDefinition,
     scope: &mut Vec<MacroDefinition>,
     offset: &mut usize,
@@ -15,6 +22,7 @@ pub fn statement_gen(
     label_indices: &mut LabelIndices,
     table_instances: &mut Jumps,
     utilized_tables: &mut Vec<TableDefinition>,
+    source_map: &mut Vec<SourceMapEntry>,
     starting_offset: usize,
 ) -> Result<Vec<(usize, Bytes)>, CodegenError> {
     let mut bytes = vec![];
@@ -36,6 +44,7 @@ pub fn statement_gen(
                     kind: CodegenErrorKind::InvalidMacroInvocation(mi.macro_name.clone()),
                     span: mi.span.clone(),
                     token: None,
+                    backtrace: Box::default(),
                 })
             };
 
@@ -46,11 +55,15 @@ pub fn statement_gen(
                 tracing::error!(target: "codegen", "Tests may not be invoked: {}", ir_macro.name);
                 return Err(CodegenError {
                     kind: CodegenErrorKind::TestInvocation(ir_macro.name.clone()),
-                    span: ir_macro.span,
+                    span: ir_macro.span.clone(),
                     token: None,
+                    backtrace: Box::default(),
                 })
             }
 
+            check_macro_arity(ir_macro, mi)?;
+            check_macro_arg_kinds(ir_macro, mi, scope, mis, contract, env)?;
+
             // If invoked macro is a function (outlined), insert a jump to the function's code and a
             // jumpdest to return to. If it is inlined, insert the macro's code at the
             // current offset.
@@ -91,25 +104,36 @@ pub fn statement_gen(
                 // PUSH2 + 2 bytes + stack_swaps.len() + PUSH2 + 2 bytes + JUMP + JUMPDEST
                 *offset += stack_swaps.len() + 8;
             } else {
-                // Recurse into macro invocation
-                scope.push(ir_macro.clone());
-                mis.push((*offset, mi.clone()));
-
-                let mut res: BytecodeRes = match Codegen::macro_to_bytecode(
-                    ir_macro.clone(),
-                    contract,
-                    scope,
-                    *offset,
-                    mis,
-                ) {
-                    Ok(r) => r,
-                    Err(e) => {
-                        tracing::error!(
-                            target: "codegen",
-                            "FAILED TO RECURSE INTO MACRO \"{}\"",
-                            ir_macro.name
-                        );
-                        return Err(e)
+                check_recursion(&ir_macro.name, scope, mis, &mi.span)?;
+
+                let cache_key = invocation_cache_key(mi);
+                let cached = match &cache_key {
+                    Some(key) => cached_invocation(env, key, *offset, scope, mis)?,
+                    None => None,
+                };
+                let mut res: BytecodeRes = if let Some(res) = cached {
+                    tracing::debug!(target: "codegen", "USING CACHED EXPANSION OF \"{}\"", ir_macro.name);
+                    res
+                } else {
+                    // Recurse into macro invocation
+                    scope.push(ir_macro.clone());
+                    mis.push((*offset, mi.clone()));
+
+                    match Codegen::macro_to_bytecode(ir_macro, contract, env, scope, *offset, mis) {
+                        Ok(r) => {
+                            if let Some(key) = cache_key {
+                                cache_invocation(env, key, &r, *offset)?;
+                            }
+                            r
+                        }
+                        Err(e) => {
+                            tracing::error!(
+                                target: "codegen",
+                                "FAILED TO RECURSE INTO MACRO \"{}\"",
+                                ir_macro.name
+                            );
+                            return Err(e.in_expansion_of(&ir_macro.name, &macro_def.name, &mi.span))
+                        }
                     }
                 };
 
@@ -129,6 +153,7 @@ pub fn statement_gen(
                 table_instances.extend(res.table_instances);
                 label_indices.extend(res.label_indices);
                 utilized_tables.extend(res.utilized_tables);
+                source_map.extend(res.source_map);
 
                 // Increase offset by byte length of recursed macro
                 *offset += res.bytes.iter().map(|(_, b)| b.0.len()).sum::<usize>() / 2;
@@ -176,31 +201,38 @@ pub fn statement_gen(
                             ),
                             span: bf.span.clone(),
                             token: None,
+                            backtrace: Box::default(),
                         })
                     };
 
-                    let res: BytecodeRes = match Codegen::macro_to_bytecode(
-                        ir_macro.clone(),
-                        contract,
-                        scope,
-                        *offset,
-                        mis,
-                    ) {
-                        Ok(r) => r,
-                        Err(e) => {
-                            tracing::error!(
-                                target: "codegen",
-                                "FAILED TO RECURSE INTO MACRO \"{}\"",
-                                ir_macro.name
-                            );
-                            return Err(e)
+                    check_recursion(&ir_macro.name, scope, mis, &bf.span)?;
+
+                    // Outlined functions are appended to macros generated at the top level
+                    let top_level = scope.len() == 1;
+                    let cache_key = (ir_macro.name.clone(), top_level);
+                    let size = if let Some(size) = cached_codesize(env, &cache_key, scope, mis)? {
+                        size
+                    } else {
+                        match Codegen::macro_to_bytecode(
+                            ir_macro, contract, env, scope, *offset, mis,
+                        ) {
+                            Ok(r) => cache_codesize(env, cache_key, &r)?,
+                            Err(e) => {
+                                tracing::error!(
+                                    target: "codegen",
+                                    "FAILED TO RECURSE INTO MACRO \"{}\"",
+                                    ir_macro.name
+                                );
+                                return Err(e.in_expansion_of(
+                                    &ir_macro.name,
+                                    &macro_def.name,
+                                    &bf.span,
+                                ))
+                            }
                         }
                     };
 
-                    let size = format_even_bytes(format!(
-                        "{:02x}",
-                        (res.bytes.iter().map(|(_, b)| b.0.len()).sum::<usize>() / 2)
-                    ));
+                    let size = format_even_bytes(format!("{:02x}", size));
                     let push_bytes = format!("{:02x}{}", 95 + size.len() / 2, size);
 
                     *offset += push_bytes.len() / 2;
@@ -223,14 +255,15 @@ pub fn statement_gen(
                             ),
                             span: bf.span.clone(),
                             token: None,
+                            backtrace: Box::default(),
                         })
                     };
 
                     let size = bytes32_to_string(&ir_table.size, false);
                     let push_bytes = format!("{:02x}{}", 95 + size.len() / 2, size);
 
-                    if !utilized_tables.contains(&ir_table) {
-                        utilized_tables.push(ir_table);
+                    if !utilized_tables.contains(ir_table) {
+                        utilized_tables.push(ir_table.clone());
                     }
 
                     *offset += push_bytes.len() / 2;
@@ -245,8 +278,8 @@ pub fn statement_gen(
                             bytecode_index: *offset,
                             span: bf.span.clone(),
                         });
-                        if !utilized_tables.contains(&t) {
-                            utilized_tables.push(t);
+                        if !utilized_tables.contains(t) {
+                            utilized_tables.push(t.clone());
                         }
 
                         bytes.push((*offset, Bytes(format!("{}xxxx", Opcode::Push2))));
@@ -263,6 +296,7 @@ pub fn statement_gen(
                             ),
                             span: bf.span.clone(),
                             token: None,
+                            backtrace: Box::default(),
                         })
                     }
                 }
@@ -282,14 +316,17 @@ pub fn statement_gen(
                             ),
                             span: bf.span.clone(),
                             token: None,
+                            backtrace: Box::default(),
                         })
                     }
 
-                    if let Some(func) = contract
-                        .functions
-                        .iter()
-                        .find(|f| bf.args[0].name.as_ref().unwrap().eq(&f.name))
-                    {
+                    if let Some(func) = resolve_overload(
+                        bf.args[0].name.as_ref().unwrap(),
+                        &contract.functions,
+                        |f| &f.name,
+                        |f| contract.signature(&f.name, &f.inputs),
+                        &bf.span,
+                    )? {
                         let push_bytes =
                             format!("{}{}", Opcode::Push4, hex::encode(func.signature));
                         *offset += push_bytes.len() / 2;
@@ -313,6 +350,7 @@ pub fn statement_gen(
                             ),
                             span: bf.span.clone(),
                             token: None,
+                            backtrace: Box::default(),
                         })
                     }
                 }
@@ -332,14 +370,17 @@ pub fn statement_gen(
                             ),
                             span: bf.span.clone(),
                             token: None,
+                            backtrace: Box::default(),
                         })
                     }
 
-                    if let Some(event) = contract
-                        .events
-                        .iter()
-                        .find(|e| bf.args[0].name.as_ref().unwrap().eq(&e.name))
-                    {
+                    if let Some(event) = resolve_overload(
+                        bf.args[0].name.as_ref().unwrap(),
+                        &contract.events,
+                        |e| &e.name,
+                        |e| contract.signature(&e.name, &e.parameters),
+                        &bf.span,
+                    )? {
                         let hash = bytes32_to_string(&event.hash, false);
                         let push_bytes = format!("{}{}", Opcode::Push32, hash);
                         *offset += push_bytes.len() / 2;
@@ -363,6 +404,7 @@ pub fn statement_gen(
                             ),
                             span: bf.span.clone(),
                             token: None,
+                            backtrace: Box::default(),
                         })
                     }
                 }
@@ -380,14 +422,17 @@ pub fn statement_gen(
                             )),
                             span: bf.span.clone(),
                             token: None,
+                            backtrace: Box::default(),
                         })
                     }
 
-                    if let Some(error) = contract
-                        .errors
-                        .iter()
-                        .find(|e| bf.args[0].name.as_ref().unwrap().eq(&e.name))
-                    {
+                    if let Some(error) = resolve_overload(
+                        bf.args[0].name.as_ref().unwrap(),
+                        &contract.errors,
+                        |e| &e.name,
+                        |e| contract.signature(&e.name, &e.parameters),
+                        &bf.span,
+                    )? {
                         // Add 28 bytes to left-pad the 4 byte selector
                         let selector =
                             format!("{}{}", hex::encode(error.selector), "00".repeat(28));
@@ -406,6 +451,7 @@ pub fn statement_gen(
                             ),
                             span: bf.span.clone(),
                             token: None,
+                            backtrace: Box::default(),
                         })
                     }
                 }
@@ -423,6 +469,7 @@ pub fn statement_gen(
                             )),
                             span: bf.span.clone(),
                             token: None,
+                            backtrace: Box::default(),
                         })
                     }
 
@@ -432,8 +479,47 @@ pub fn statement_gen(
                     *offset += push_bytes.len() / 2;
                     bytes.push((starting_offset, Bytes(push_bytes)));
                 }
+                BuiltinFunctionKind::Repeat => {
+                    // Repetitions are unrolled by `expand_directives` before codegen
+                    tracing::error!(target: "codegen", "UNEXPANDED __REPEAT IN MACRO: {}", macro_def.name);
+                    return Err(CodegenError {
+                        kind: CodegenErrorKind::InvalidMacroStatement,
+                        span: bf.span.clone(),
+                        token: None,
+                        backtrace: Box::default(),
+                    })
+                }
             }
         }
+        StatementType::ArgMacroInvocation(mi) => {
+            let ir_macro = bubble_macro_arg(&mi.macro_name, contract, scope, mis, &mi.span)?;
+
+            tracing::info!(target: "codegen", "RESOLVED MACRO ARGUMENT <{}> TO: {}", mi.macro_name, ir_macro.name);
+
+            let invocation = Statement {
+                ty: StatementType::MacroInvocation(MacroInvocation {
+                    macro_name: ir_macro.name.clone(),
+                    args: mi.args.clone(),
+                    span: mi.span.clone(),
+                }),
+                span: s.span.clone(),
+            };
+            return statement_gen(
+                &invocation,
+                contract,
+                env,
+                macro_def,
+                scope,
+                offset,
+                mis,
+                jump_table,
+                label_indices,
+                table_instances,
+                utilized_tables,
+                source_map,
+                starting_offset,
+            )
+        }
         sty => {
             tracing::error!(target: "codegen", "CURRENT MACRO DEF: {}", macro_def.name);
             tracing::error!(target: "codegen", "UNEXPECTED STATEMENT: {:?}", sty);
@@ -441,9 +527,73 @@ pub fn statement_gen(
                 kind: CodegenErrorKind::InvalidMacroStatement,
                 span: s.span.clone(),
                 token: None,
+                backtrace: Box::default(),
             })
         }
     }
 
     Ok(bytes)
 }
+
+/// Checks that generating a macro doesn't recurse into a macro that is already being generated.
+///
+/// The macros being generated are the root of the scope followed by the active invocations.
+pub fn check_recursion(
+    macro_name: &str,
+    scope: &[MacroDefinition],
+    mis: &[(usize, MacroInvocation)],
+    span: &AstSpan,
+) -> Result<(), CodegenError> {
+    let root = scope.first().map(|m| m.name.clone());
+    if root.as_deref() != Some(macro_name) && !mis.iter().any(|(_, mi)| mi.macro_name == macro_name)
+    {
+        return Ok(())
+    }
+
+    let chain = root
+        .into_iter()
+        .chain(mis.iter().map(|(_, mi)| mi.macro_name.clone()))
+        .chain(std::iter::once(macro_name.to_string()))
+        .collect::<Vec<String>>();
+    tracing::error!(target: "codegen", "RECURSIVE MACRO INVOCATION: {}", chain.join(" -> "));
+    Err(CodegenError {
+        kind: CodegenErrorKind::RecursiveMacroInvocation(chain),
+        span: AstSpan(
+            mis.iter().flat_map(|(_, mi)| mi.span.0.clone()).chain(span.0.clone()).collect(),
+        ),
+        token: None,
+        backtrace: Box::default(),
+    })
+}
+
+/// Resolves the definition a builtin argument refers to, either by its signature, ie.
+/// `transfer(address,uint256)`, or by its name if the name isn't overloaded.
+pub fn resolve_overload<'a, T>(
+    arg: &str,
+    defs: &'a [T],
+    name: impl Fn(&T) -> &str,
+    signature: impl Fn(&T) -> String,
+    span: &AstSpan,
+) -> Result<Option<&'a T>, CodegenError> {
+    if arg.contains('(') {
+        let arg = arg.replace(' ', "");
+        return Ok(defs.iter().find(|d| signature(d) == arg))
+    }
+
+    // Redefinitions share a signature, overloads don't
+    let mut signatures =
+        defs.iter().filter(|d| name(d) == arg).map(&signature).collect::<Vec<String>>();
+    signatures.sort();
+    signatures.dedup();
+    if signatures.len() > 1 {
+        tracing::error!(target: "codegen", "AMBIGUOUS OVERLOAD \"{}\": {}", arg, signatures.join(", "));
+        return Err(CodegenError {
+            kind: CodegenErrorKind::AmbiguousOverload(signatures),
+            span: span.clone(),
+            token: None,
+            backtrace: Box::default(),
+        })
+    }
+
+    Ok(defs.iter().find(|d| name(d) == arg))
+}
@@ -10,9 +10,10 @@ use huff_utils::{
     ast::*,
     bytecode::*,
     bytes_util,
+    env::ConstantEnv,
     error::CodegenError,
     evm::Opcode,
-    prelude::{format_even_bytes, pad_n_bytes, CodegenErrorKind, FileSource, Span},
+    prelude::{format_even_bytes, pad_n_bytes, CodegenErrorKind, FileSource, SourceMapEntry, Span},
     types::EToken,
 };
 use std::{collections::HashMap, fs, path::Path, sync::Arc};
@@ -20,6 +21,9 @@ use std::{collections::HashMap, fs, path::Path, sync::Arc};
 mod irgen;
 use crate::irgen::prelude::*;
 
+mod expand;
+use crate::expand::Expander;
+
 /// ### Codegen
 ///
 /// Code Generation Manager responsible for generating bytecode from a
@@ -45,24 +49,47 @@ pub struct Codegen {
     pub main_bytecode: Option<String>,
     /// Intermediate constructor bytecode store
     pub constructor_bytecode: Option<String>,
+    /// Intermediate main bytecode source map store
+    pub main_source_map: Vec<SourceMapEntry>,
+    /// Intermediate constructor bytecode source map store
+    pub constructor_source_map: Vec<SourceMapEntry>,
 }
 
 impl Codegen {
     /// Public associated function to instantiate a new Codegen instance.
     pub fn new() -> Self {
-        Self { ast: None, artifact: None, main_bytecode: None, constructor_bytecode: None }
+        Self {
+            ast: None,
+            artifact: None,
+            main_bytecode: None,
+            constructor_bytecode: None,
+            main_source_map: vec![],
+            constructor_source_map: vec![],
+        }
     }
 
-    /// Generates main bytecode from a Contract AST
-    pub fn generate_main_bytecode(contract: &Contract) -> Result<String, CodegenError> {
+    /// Generates main bytecode from a Contract AST and its resolved constants
+    pub fn generate_main_bytecode(
+        contract: &Contract,
+        env: &ConstantEnv,
+    ) -> Result<String, CodegenError> {
+        Codegen::generate_main_bytecode_with_source_map(contract, env).map(|(bytecode, _)| bytecode)
+    }
+
+    /// Generates main bytecode and its source map from a Contract AST and its resolved constants
+    pub fn generate_main_bytecode_with_source_map(
+        contract: &Contract,
+        env: &ConstantEnv,
+    ) -> Result<(String, Vec<SourceMapEntry>), CodegenError> {
         // Find the main macro
         let m_macro = Codegen::get_macro_by_name("MAIN", contract)?;
 
         // For each MacroInvocation Statement, recurse into bytecode
         let bytecode_res: BytecodeRes = Codegen::macro_to_bytecode(
-            m_macro.clone(),
+            m_macro,
             contract,
-            &mut vec![m_macro],
+            env,
+            &mut vec![m_macro.clone()],
             0,
             &mut Vec::default(),
         )?;
@@ -70,31 +97,59 @@ impl Codegen {
         tracing::debug!(target: "codegen", "Generated main bytecode. Appending table bytecode...");
 
         // Generate the fully baked bytecode
-        Codegen::gen_table_bytecode(bytecode_res)
+        Codegen::gen_table_bytecode_with_source_map(bytecode_res)
+    }
+
+    /// Generates constructor bytecode from a Contract AST and its resolved constants
+    pub fn generate_constructor_bytecode(
+        contract: &Contract,
+        env: &ConstantEnv,
+    ) -> Result<String, CodegenError> {
+        Codegen::generate_constructor_bytecode_with_source_map(contract, env)
+            .map(|(bytecode, _)| bytecode)
     }
 
-    /// Generates constructor bytecode from a Contract AST
-    pub fn generate_constructor_bytecode(contract: &Contract) -> Result<String, CodegenError> {
+    /// Generates constructor bytecode and its source map from a Contract AST and its resolved
+    /// constants
+    pub fn generate_constructor_bytecode_with_source_map(
+        contract: &Contract,
+        env: &ConstantEnv,
+    ) -> Result<(String, Vec<SourceMapEntry>), CodegenError> {
         // Find the constructor macro
         let c_macro = Codegen::get_macro_by_name("CONSTRUCTOR", contract)?;
 
         // For each MacroInvocation Statement, recurse into bytecode
         let bytecode_res: BytecodeRes = Codegen::macro_to_bytecode(
-            c_macro.clone(),
+            c_macro,
             contract,
-            &mut vec![c_macro],
+            env,
+            &mut vec![c_macro.clone()],
             0,
             &mut Vec::default(),
         )?;
 
-        Codegen::gen_table_bytecode(bytecode_res)
+        Codegen::gen_table_bytecode_with_source_map(bytecode_res)
     }
 
-    /// Helper function to find a macro or generate a CodegenError
-    pub(crate) fn get_macro_by_name(
+    /// Expands a macro into fully inlined Huff source.
+    ///
+    /// Invoked macros are inlined with comments marking where each begins and ends, arg calls are
+    /// substituted, constants and builtins are evaluated, and labels of invoked macros are
+    /// renamed to be unique.
+    pub fn expand_macro(
         name: &str,
         contract: &Contract,
-    ) -> Result<MacroDefinition, CodegenError> {
+        env: &ConstantEnv,
+    ) -> Result<String, CodegenError> {
+        let macro_def = Codegen::get_macro_by_name(name, contract)?;
+        Expander::new(contract, env).expand(macro_def.clone())
+    }
+
+    /// Helper function to find a macro or generate a CodegenError
+    pub(crate) fn get_macro_by_name<'a>(
+        name: &str,
+        contract: &'a Contract,
+    ) -> Result<&'a MacroDefinition, CodegenError> {
         if let Some(m) = contract.find_macro_by_name(name) {
             Ok(m)
         } else {
@@ -103,6 +158,7 @@ impl Codegen {
                 kind: CodegenErrorKind::MissingMacroDefinition(name.to_string()),
                 span: AstSpan(vec![Span { start: 0, end: 0, file: None }]),
                 token: None,
+                backtrace: Box::default(),
             })
         }
     }
@@ -110,6 +166,15 @@ impl Codegen {
     /// Appends table bytecode to the end of the BytecodeRes output.
     /// Fills table JUMPDEST placeholders.
     pub fn gen_table_bytecode(res: BytecodeRes) -> Result<String, CodegenError> {
+        Codegen::gen_table_bytecode_with_source_map(res).map(|(bytecode, _)| bytecode)
+    }
+
+    /// Appends table bytecode to the end of the BytecodeRes output, extending its source map with
+    /// the table data.
+    /// Fills table JUMPDEST placeholders.
+    pub fn gen_table_bytecode_with_source_map(
+        res: BytecodeRes,
+    ) -> Result<(String, Vec<SourceMapEntry>), CodegenError> {
         if !res.unmatched_jumps.is_empty() {
             tracing::error!(
                 target: "codegen",
@@ -125,17 +190,20 @@ impl Codegen {
                         .collect::<Vec<Span>>(),
                 ),
                 token: None,
+                backtrace: Box::default(),
             })
         }
 
         tracing::info!(target: "codegen", "GENERATING JUMPTABLE BYTECODE");
 
         let mut bytecode = res.bytes.into_iter().map(|(_, b)| b.0).collect::<String>();
+        let mut source_map = res.source_map;
         let mut table_offsets: HashMap<String, usize> = HashMap::new(); // table name -> bytecode offset
         let mut table_offset = bytecode.len() / 2;
 
         res.utilized_tables.iter().try_for_each(|jt| {
             table_offsets.insert(jt.name.to_string(), table_offset);
+            let table_start = table_offset;
             let size = match bytes_util::hex_to_usize(bytes_util::bytes32_to_string(&jt.size, false).as_str()) {
                 Ok(s) => s,
                 Err(e) => {
@@ -143,11 +211,13 @@ impl Codegen {
                     return Err(CodegenError {
                         kind: CodegenErrorKind::UsizeConversion(format!("{:?}", jt.size)),
                         span: jt.span.clone(),
-                        token: None
+                        token: None,
+                        backtrace: Box::default()
                     })
                 }
             };
             table_offset += size;
+            source_map.push(SourceMapEntry::new(table_start, size, &jt.name, true, &jt.span));
 
             tracing::info!(target: "codegen", "GENERATING BYTECODE FOR TABLE: \"{}\"", jt.name);
 
@@ -170,6 +240,7 @@ impl Codegen {
                                         kind: CodegenErrorKind::UnmatchedJumpLabel,
                                         span: s.span.clone(),
                                         token: None,
+                                        backtrace: Box::default(),
                                     });
                                 }
                             };
@@ -187,6 +258,7 @@ impl Codegen {
                                     kind: CodegenErrorKind::InvalidCodeLength(code.len()),
                                     span: s.span.clone(),
                                     token: None,
+                                    backtrace: Box::default(),
                                 });
                             }
 
@@ -196,7 +268,8 @@ impl Codegen {
                             return Err(CodegenError {
                                 kind: CodegenErrorKind::InvalidMacroStatement,
                                 span: jt.span.clone(),
-                                token: None
+                                token: None,
+                                backtrace: Box::default()
                             })
                         }
                     }
@@ -225,7 +298,7 @@ impl Codegen {
             }
         });
 
-        Ok(bytecode)
+        Ok((bytecode, source_map))
     }
 
     /// Recurses a MacroDefinition to generate Bytecode
@@ -245,36 +318,52 @@ impl Codegen {
     ///
     /// * `macro_def` - Macro definition to convert to bytecode
     /// * `contract` - Reference to the `Contract` AST generated by the parser
+    /// * `env` - The resolved constants of the `Contract`
     /// * `scope` - Current scope of the recursion. Contains all macro definitions recursed so far.
     /// * `offset` - Current bytecode offset
     /// * `mis` - Vector of tuples containing parent macro invocations as well as their offsets.
     pub fn macro_to_bytecode(
-        macro_def: MacroDefinition,
+        macro_def: &MacroDefinition,
         contract: &Contract,
+        env: &ConstantEnv,
         scope: &mut Vec<MacroDefinition>,
         mut offset: usize,
         mis: &mut Vec<(usize, MacroInvocation)>,
     ) -> Result<BytecodeRes, CodegenError> {
         // Get intermediate bytecode representation of the macro definition
+        // Compile-time directives are expanded first
         let mut bytes: Vec<(usize, Bytes)> = Vec::default();
-        let ir_bytes = macro_def.to_irbytecode()?.0;
+        let ir_bytes =
+            MacroDefinition::to_irbytes(&expand_directives(&macro_def.statements, contract, env)?);
 
         // Define outer loop variables
         let mut jump_table = JumpTable::new();
         let mut label_indices = LabelIndices::new();
         let mut table_instances = Jumps::new();
         let mut utilized_tables: Vec<TableDefinition> = Vec::new();
+        let mut source_map: Vec<SourceMapEntry> = Vec::new();
+
+        // Invocations unrolled from a `__REPEAT` are consecutive and share the span of the call,
+        // so the span and starting offset of the last invocation group are tracked
+        let mut repeat: Option<(AstSpan, usize)> = None;
 
         // Loop through all intermediate bytecode representations generated from the AST
         for (_ir_bytes_index, ir_byte) in ir_bytes.into_iter().enumerate() {
             let starting_offset = offset;
+            let span = ir_byte.span.clone();
+            let repeat_invocation = match &ir_byte.ty {
+                IRByteType::Statement(Statement {
+                    ty: StatementType::MacroInvocation(mi), ..
+                }) => Some(mi.macro_name.clone()),
+                _ => None,
+            };
             match ir_byte.ty {
                 IRByteType::Bytes(b) => {
                     offset += b.0.len() / 2;
                     bytes.push((starting_offset, b));
                 }
                 IRByteType::Constant(name) => {
-                    let push_bytes = constant_gen(&name, contract, ir_byte.span)?;
+                    let push_bytes = constant_gen(&name, env, ir_byte.span)?;
                     offset += push_bytes.len() / 2;
                     tracing::debug!(target: "codegen", "OFFSET: {}, PUSH BYTES: {:?}", offset, push_bytes);
                     bytes.push((starting_offset, Bytes(push_bytes)));
@@ -283,7 +372,8 @@ impl Codegen {
                     let mut push_bytes = statement_gen(
                         &s,
                         contract,
-                        &macro_def,
+                        env,
+                        macro_def,
                         scope,
                         &mut offset,
                         mis,
@@ -291,6 +381,7 @@ impl Codegen {
                         &mut label_indices,
                         &mut table_instances,
                         &mut utilized_tables,
+                        &mut source_map,
                         starting_offset,
                     )?;
                     bytes.append(&mut push_bytes);
@@ -301,8 +392,8 @@ impl Codegen {
                     bubble_arg_call(
                         &arg_name,
                         &mut bytes,
-                        &macro_def,
-                        contract,
+                        macro_def,
+                        env,
                         scope,
                         &mut offset,
                         mis,
@@ -310,6 +401,36 @@ impl Codegen {
                     )?
                 }
             }
+
+            // Bound the code unrolled by a `__REPEAT`
+            repeat = match (repeat_invocation, repeat) {
+                (Some(name), Some((repeat_span, start))) if repeat_span == span => {
+                    if offset - start > MAX_REPEAT_SIZE {
+                        tracing::error!(target: "codegen", "__REPEAT OF \"{}\" EXCEEDS {} BYTES", name, MAX_REPEAT_SIZE);
+                        return Err(CodegenError {
+                            kind: CodegenErrorKind::RepeatTooLarge(name),
+                            span,
+                            token: None,
+                            backtrace: Box::default(),
+                        })
+                    }
+                    Some((repeat_span, start))
+                }
+                (Some(_), _) => Some((span.clone(), starting_offset)),
+                (None, _) => None,
+            };
+
+            // Map the generated bytes back to their source. Ranges of nested macros are pushed
+            // first, so they take precedence over the invocation's range.
+            if offset > starting_offset {
+                source_map.push(SourceMapEntry::new(
+                    starting_offset,
+                    offset - starting_offset,
+                    &macro_def.name,
+                    false,
+                    &span,
+                ));
+            }
         }
 
         // We're done, let's pop off the macro invocation
@@ -322,12 +443,14 @@ impl Codegen {
         if scope.len() == 1 {
             bytes = Codegen::append_functions(
                 contract,
+                env,
                 scope,
                 &mut offset,
                 mis,
                 &mut jump_table,
                 &mut label_indices,
                 &mut table_instances,
+                &mut source_map,
                 bytes,
             )?;
         } else {
@@ -339,7 +462,14 @@ impl Codegen {
         // Fill JUMPDEST placeholders
         let (bytes, unmatched_jumps) = Codegen::fill_unmatched(bytes, &jump_table, &label_indices)?;
 
-        Ok(BytecodeRes { bytes, label_indices, unmatched_jumps, table_instances, utilized_tables })
+        Ok(BytecodeRes {
+            bytes,
+            label_indices,
+            unmatched_jumps,
+            table_instances,
+            utilized_tables,
+            source_map,
+        })
     }
 
     /// Helper associated function to fill unmatched jump dests.
@@ -421,12 +551,14 @@ impl Codegen {
     #[allow(clippy::too_many_arguments)]
     pub fn append_functions(
         contract: &Contract,
+        env: &ConstantEnv,
         scope: &mut Vec<MacroDefinition>,
         offset: &mut usize,
         mis: &mut Vec<(usize, MacroInvocation)>,
         jump_table: &mut JumpTable,
         label_indices: &mut LabelIndices,
         table_instances: &mut Jumps,
+        source_map: &mut Vec<SourceMapEntry>,
         mut bytes: Vec<(usize, Bytes)>,
     ) -> Result<Vec<(usize, Bytes)>, CodegenError> {
         for macro_def in contract.macros.iter().filter(|m| m.outlined) {
@@ -435,7 +567,7 @@ impl Codegen {
 
             // Add 1 to starting offset to account for the JUMPDEST opcode
             let mut res =
-                Codegen::macro_to_bytecode(macro_def.clone(), contract, scope, *offset + 1, mis)?;
+                Codegen::macro_to_bytecode(macro_def, contract, env, scope, *offset + 1, mis)?;
 
             for j in res.unmatched_jumps.iter_mut() {
                 let new_index = j.bytecode_index;
@@ -450,6 +582,7 @@ impl Codegen {
             }
             table_instances.extend(res.table_instances);
             label_indices.extend(res.label_indices);
+            source_map.extend(res.source_map);
 
             let macro_code_len = res.bytes.iter().map(|(_, b)| b.0.len()).sum::<usize>() / 2;
 
@@ -467,6 +600,13 @@ impl Codegen {
             bytes = [bytes, res.bytes].concat();
             // Add the jumpdest to the beginning of the outlined macro.
             label_indices.insert(format!("goto_{}", macro_def.name.clone()), *offset);
+            source_map.push(SourceMapEntry::new(
+                *offset,
+                macro_code_len + stack_swaps.len() + 2,
+                &macro_def.name,
+                false,
+                &macro_def.span,
+            ));
             *offset += macro_code_len + stack_swaps.len() + 2; // JUMPDEST + MACRO_CODE_LEN +
                                                                // stack_swaps.len() + JUMP
         }
@@ -535,6 +675,13 @@ impl Codegen {
 
         // Generate the final bytecode
         let constructor_code = format!("{}{}", constructor_bytecode, bootstrap_code);
+        artifact.source_map = self
+            .constructor_source_map
+            .iter()
+            .cloned()
+            .chain(self.main_source_map.iter().map(|e| e.shifted(constructor_code.len() / 2)))
+            .collect();
+        artifact.runtime_source_map = self.main_source_map.clone();
         artifact.bytecode =
             format!("{}{}{}", constructor_code, main_bytecode, constructor_args).to_lowercase();
         artifact.runtime = main_bytecode.to_string().to_lowercase();
@@ -576,6 +723,7 @@ impl Codegen {
                         })),
                     }]),
                     token: None,
+                    backtrace: Box::default(),
                 })
             }
         }
@@ -594,34 +742,113 @@ impl Codegen {
                     })),
                 }]),
                 token: None,
+                backtrace: Box::default(),
             })
         }
         Ok(())
     }
 
+    /// Selector Collision Check
+    ///
+    /// Verifies that no two function definitions, and no two error definitions, in the Contract
+    /// share a 4-byte selector. Errors with a
+    /// [SelectorCollision](CodegenErrorKind::SelectorCollision) spanning both definitions.
+    pub fn check_selector_collisions(contract: &Contract) -> Result<(), CodegenError> {
+        let functions = contract
+            .functions
+            .iter()
+            .map(|f| (f.name.clone(), f.signature, f.span.clone()))
+            .collect::<Vec<_>>();
+        let errors = contract
+            .errors
+            .iter()
+            .map(|e| (e.name.clone(), e.selector, e.span.clone()))
+            .collect::<Vec<_>>();
+
+        for defs in [functions, errors] {
+            let mut seen: HashMap<[u8; 4], &(String, [u8; 4], AstSpan)> = HashMap::new();
+            for def in &defs {
+                match seen.get(&def.1) {
+                    // Identical names are redefinitions, not collisions
+                    Some(prev) if prev.0 != def.0 => {
+                        tracing::error!(target: "codegen", "SELECTOR COLLISION BETWEEN \"{}\" AND \"{}\"", prev.0, def.0);
+                        return Err(CodegenError {
+                            kind: CodegenErrorKind::SelectorCollision(
+                                vec![prev.0.clone(), def.0.clone()],
+                                def.1,
+                            ),
+                            span: AstSpan(
+                                prev.2 .0.iter().chain(def.2 .0.iter()).cloned().collect(),
+                            ),
+                            token: None,
+                            backtrace: Box::default(),
+                        })
+                    }
+                    Some(_) => {}
+                    None => {
+                        seen.insert(def.1, def);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// External Selector Collision Check
+    ///
+    /// Verifies that the Contract's function and error selectors do not collide with those of
+    /// another contract's ABI, eg. a proxy and its implementation.
+    pub fn check_abi_selector_collisions(
+        contract: &Contract,
+        abi: &Abi,
+    ) -> Result<(), CodegenError> {
+        let external_functions =
+            abi.functions.values().map(|f| (f.selector(), &f.name)).collect::<HashMap<_, _>>();
+        let external_errors =
+            abi.errors.values().map(|e| (e.selector(), &e.name)).collect::<HashMap<_, _>>();
+
+        let functions = contract.functions.iter().map(|f| (&f.name, f.signature, &f.span));
+        let errors = contract.errors.iter().map(|e| (&e.name, e.selector, &e.span));
+        let checks = functions
+            .map(|f| (f, &external_functions))
+            .chain(errors.map(|e| (e, &external_errors)));
+
+        for ((name, selector, span), external) in checks {
+            if let Some(external_name) = external.get(&selector) {
+                tracing::error!(target: "codegen", "SELECTOR COLLISION BETWEEN \"{}\" AND EXTERNAL \"{}\"", name, external_name);
+                return Err(CodegenError {
+                    kind: CodegenErrorKind::SelectorCollision(
+                        vec![name.clone(), external_name.to_string()],
+                        selector,
+                    ),
+                    span: span.clone(),
+                    token: None,
+                    backtrace: Box::default(),
+                })
+            }
+        }
+
+        Ok(())
+    }
+
     /// Abi Generation
     ///
     /// Generates an ABI for the given Ast.
-    /// Stores the generated ABI in the Codegen `artifact`.
+    /// Stores the generated ABI and selector tables in the Codegen `artifact`.
     ///
     /// # Arguments
     ///
     /// * `ast` - The Contract Abstract Syntax Tree
     /// * `output` - An optional output path
     pub fn abi_gen(&mut self, ast: Contract, output: Option<String>) -> Result<Abi, CodegenError> {
+        let selectors = ast.selectors();
         let abi: Abi = ast.into();
 
-        // Set the abi on self
-        let art: &Artifact = match &mut self.artifact {
-            Some(artifact) => {
-                artifact.abi = Some(abi.clone());
-                artifact
-            }
-            None => {
-                self.artifact = Some(Artifact { abi: Some(abi.clone()), ..Default::default() });
-                self.artifact.as_ref().unwrap()
-            }
-        };
+        // Set the abi and selectors on self
+        let art: &mut Artifact = self.artifact.get_or_insert_with(Artifact::default);
+        art.abi = Some(abi.clone());
+        art.selectors = selectors;
 
         // If an output's specified, write the artifact out
         if let Some(o) = output {
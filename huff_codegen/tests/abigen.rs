@@ -1,7 +1,4 @@
-use std::{
-    collections::BTreeMap,
-    sync::{Arc, Mutex},
-};
+use std::collections::BTreeMap;
 
 use huff_codegen::Codegen;
 use huff_utils::prelude::*;
@@ -23,11 +20,13 @@ fn constructs_valid_abi() {
         macros: vec![constructor],
         invocations: vec![],
         imports: vec![],
-        constants: Arc::new(Mutex::new(vec![])),
+        constants: vec![],
         errors: vec![],
         functions: vec![],
         events: vec![],
         tables: vec![],
+        structs: vec![],
+        symbols: SymbolIndex::default(),
     };
 
     // Generate the abi from the contract
@@ -63,11 +62,13 @@ fn missing_constructor_fails() {
         macros: vec![],
         invocations: vec![],
         imports: vec![],
-        constants: Arc::new(Mutex::new(vec![])),
+        constants: vec![],
         errors: vec![],
         functions: vec![],
         events: vec![],
         tables: vec![],
+        structs: vec![],
+        symbols: SymbolIndex::default(),
     };
 
     // Generate the abi from the contract
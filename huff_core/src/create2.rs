@@ -0,0 +1,76 @@
+//! ## Create2
+//!
+//! Deterministic CREATE2 address prediction and vanity salt searching.
+
+use huff_utils::prelude::create2_address;
+use rayon::prelude::*;
+
+/// The number of salts tried between progress reports
+const BATCH_SIZE: u64 = 1 << 16;
+
+/// A salt and the address it deploys to
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SaltMatch {
+    /// The salt
+    pub salt: [u8; 32],
+    /// The deployed contract address
+    pub address: [u8; 20],
+    /// The number of leading prefix nibbles the address matches
+    pub matched: usize,
+}
+
+/// Searches salts in parallel for an address starting with the given prefix nibbles.
+///
+/// Salts are tried in ascending order from `0`, in batches spread across all cores. After each
+/// batch, `progress` is called with the number of salts tried and the best match so far. The
+/// search stops once the full prefix is matched or `max_attempts` salts were tried, returning the
+/// best match.
+pub fn search_salt(
+    deployer: &[u8; 20],
+    init_code_hash: &[u8; 32],
+    prefix: &[u8],
+    max_attempts: Option<u64>,
+    progress: impl Fn(u64, &SaltMatch),
+) -> SaltMatch {
+    let mut best = SaltMatch::default();
+    let mut attempts = 0;
+
+    loop {
+        let batch_size = max_attempts.map_or(BATCH_SIZE, |max| BATCH_SIZE.min(max - attempts));
+        let batch_best = (attempts..attempts + batch_size)
+            .into_par_iter()
+            .map(|i| {
+                let mut salt = [0u8; 32];
+                salt[24..].copy_from_slice(&i.to_be_bytes());
+                let address = create2_address(deployer, &salt, init_code_hash);
+                SaltMatch { salt, address, matched: matched_nibbles(&address, prefix) }
+            })
+            // Prefer the lowest salt between equal matches so results are deterministic
+            .max_by(|a, b| a.matched.cmp(&b.matched).then_with(|| b.salt.cmp(&a.salt)));
+
+        if let Some(m) = batch_best {
+            if attempts == 0 || m.matched > best.matched {
+                best = m;
+            }
+        }
+        attempts += batch_size;
+        progress(attempts, &best);
+
+        if best.matched == prefix.len() || matches!(max_attempts, Some(max) if attempts >= max) {
+            return best
+        }
+    }
+}
+
+/// Counts the leading nibbles of an address that match the prefix nibbles
+pub fn matched_nibbles(address: &[u8; 20], prefix: &[u8]) -> usize {
+    prefix
+        .iter()
+        .enumerate()
+        .take_while(|(i, nibble)| {
+            let byte = address[i / 2];
+            let actual = if i % 2 == 0 { byte >> 4 } else { byte & 0x0f };
+            actual == **nibble
+        })
+        .count()
+}
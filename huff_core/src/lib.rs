@@ -7,7 +7,6 @@
 use ethers_core::utils::hex;
 use huff_codegen::*;
 use huff_lexer::*;
-use huff_parser::*;
 use huff_utils::prelude::*;
 use rayon::prelude::*;
 use std::{
@@ -23,6 +22,13 @@ use uuid::Uuid;
 
 pub(crate) mod cache;
 
+pub mod create2;
+
+pub mod session;
+use session::Session;
+
+pub mod standard_json;
+
 /// ## The Core Huff Compiler
 ///
 /// #### Usage
@@ -64,6 +70,12 @@ pub struct Compiler<'a> {
     pub bytecode: bool,
     /// Whether to check cached artifacts
     pub cached: bool,
+    /// An external ABI to check for selector collisions against
+    pub external_abi: Option<Abi>,
+    /// The format artifacts are exported in
+    pub artifact_format: ArtifactFormat,
+    /// How free pointer constants are allocated
+    pub allocation: Allocation,
 }
 
 impl<'a> Compiler<'a> {
@@ -87,6 +99,9 @@ impl<'a> Compiler<'a> {
             optimize: false,
             bytecode: false,
             cached,
+            external_abi: None,
+            artifact_format: ArtifactFormat::Huff,
+            allocation: Allocation::default(),
         }
     }
 
@@ -115,30 +130,13 @@ impl<'a> Compiler<'a> {
     /// 1. Transform inputs into File Paths with [transform_paths](Compiler::transform_paths).
     /// 2. Fetch file sources in parallel with [fetch_sources](Compiler::fetch_sources).
     /// 3. Recurse file dependencies in parallel with [recurse_deps](Compiler::recurse_deps).
-    /// 4. For each top-level file [Parallelized], generate the artifact using
-    /// [gen_artifact](Compiler::gen_artifact).
-    /// 5. Return the compiling error(s) or successfully generated artifacts.
+    /// 4. Parse each file once [Parallelized] in a [Session], sharing the ASTs of common
+    ///    dependencies.
+    /// 5. For each top-level file [Parallelized], generate the artifact using
+    ///    [gen_session_artifact](Compiler::gen_session_artifact).
+    /// 6. Return the compiling error(s) or successfully generated artifacts.
     pub fn execute(&self) -> Result<Vec<Arc<Artifact>>, Arc<CompilerError<'a>>> {
-        // Grab the input files
-        let file_paths: Vec<PathBuf> = Compiler::transform_paths(&self.sources)?;
-
-        // Parallel file fetching
-        let files: Vec<Result<Arc<FileSource>, CompilerError>> =
-            Compiler::fetch_sources(file_paths);
-
-        // Unwrap errors
-        let mut errors =
-            files.iter().filter_map(|rfs| rfs.as_ref().err()).collect::<Vec<&CompilerError>>();
-        if !errors.is_empty() {
-            let error = errors.remove(0);
-            return Err(Arc::new(error.clone()))
-        }
-
-        // Unpack files into their file sources
-        let files = files
-            .iter()
-            .filter_map(|fs| fs.as_ref().map(Arc::clone).ok())
-            .collect::<Vec<Arc<FileSource>>>();
+        let files = self.fetch_inputs()?;
 
         // Grab the output
         let output = self.get_outputs();
@@ -160,59 +158,109 @@ impl<'a> Compiler<'a> {
         let constructor_args = encoded.iter().map(|tok| hex::encode(tok.as_slice())).collect();
 
         // Get Cached or Generate Artifacts
+        // Only Huff artifacts can be read back from the output directory
         tracing::debug!(target: "core", "Output directory: {}", output.0);
-        match cache::get_cached_artifacts(&files, &output, constructor_args) {
+        let cached = match self.artifact_format {
+            ArtifactFormat::Huff => cache::get_cached_artifacts(&files, &output, constructor_args),
+            _ => None,
+        };
+        match cached {
             Some(arts) => artifacts = arts,
             None => {
-                tracing::debug!(target: "core", "FINISHED RECURSING DEPENDENCIES!");
-                // Parallel Dependency Resolution
-                let recursed_file_sources: Vec<Result<Arc<FileSource>, Arc<CompilerError<'a>>>> =
-                    files
-                        .into_par_iter()
-                        .map(|v| Compiler::recurse_deps(v, &Remapper::new("./")))
-                        .collect();
-
-                // Collect Recurse Deps errors and try to resolve to the first one
-                let mut errors = recursed_file_sources
-                    .iter()
-                    .filter_map(|rfs| rfs.as_ref().err())
-                    .collect::<Vec<&Arc<CompilerError>>>();
-                if !errors.is_empty() {
-                    let error = errors.remove(0);
-                    return Err(Arc::clone(error))
-                }
+                artifacts = self.gen_artifacts(files)?;
 
-                // Unpack recursed dependencies into FileSources
-                let files = recursed_file_sources
-                    .into_iter()
-                    .filter_map(|fs| fs.ok())
-                    .collect::<Vec<Arc<FileSource>>>();
-                tracing::info!(target: "core", "COMPILER RECURSED {} FILE DEPENDENCIES", files.len());
+                // Export
+                Compiler::export_artifacts(&artifacts, &output, self.artifact_format);
+            }
+        }
 
-                // Parallel Compilation
-                let potential_artifacts: Vec<Result<Artifact, CompilerError<'a>>> =
-                    files.into_par_iter().map(|f| self.gen_artifact(f)).collect();
+        Ok(artifacts)
+    }
 
-                let mut gen_errors: Vec<CompilerError<'a>> = vec![];
+    /// Compiles the input files in memory, without reading cached artifacts from the output
+    /// directory or exporting the generated artifacts to it.
+    pub fn compile(&self) -> Result<Vec<Arc<Artifact>>, Arc<CompilerError<'a>>> {
+        let files = self.fetch_inputs()?;
+        self.gen_artifacts(files)
+    }
 
-                // Output errors + return OR print # of successfully compiled files
-                for r in potential_artifacts {
-                    match r {
-                        Ok(a) => artifacts.push(Arc::new(a)),
-                        Err(ce) => gen_errors.push(ce),
-                    }
-                }
+    /// Transforms the input paths and fetches their file sources
+    fn fetch_inputs(&self) -> Result<Vec<Arc<FileSource>>, Arc<CompilerError<'a>>> {
+        // Grab the input files
+        let file_paths: Vec<PathBuf> = Compiler::transform_paths(&self.sources)?;
 
-                if !gen_errors.is_empty() {
-                    tracing::error!(target: "core", "{} FILES FAILED TO COMPILE", gen_errors.len());
-                    return Err(Arc::new(CompilerError::FailedCompiles(gen_errors)))
-                }
+        // Parallel file fetching
+        let files: Vec<Result<Arc<FileSource>, CompilerError>> =
+            Compiler::fetch_sources(file_paths);
 
-                // Export
-                Compiler::export_artifacts(&artifacts, &output);
+        // Unwrap errors
+        let mut errors =
+            files.iter().filter_map(|rfs| rfs.as_ref().err()).collect::<Vec<&CompilerError>>();
+        if !errors.is_empty() {
+            let error = errors.remove(0);
+            return Err(Arc::new(error.clone()))
+        }
+
+        // Unpack files into their file sources
+        Ok(files
+            .iter()
+            .filter_map(|fs| fs.as_ref().map(Arc::clone).ok())
+            .collect::<Vec<Arc<FileSource>>>())
+    }
+
+    /// Recurses the dependencies of the files, parses them once in a [Session] and generates
+    /// the artifact of each file
+    fn gen_artifacts(
+        &self,
+        files: Vec<Arc<FileSource>>,
+    ) -> Result<Vec<Arc<Artifact>>, Arc<CompilerError<'a>>> {
+        tracing::debug!(target: "core", "FINISHED RECURSING DEPENDENCIES!");
+        // Parallel Dependency Resolution
+        let recursed_file_sources: Vec<Result<Arc<FileSource>, Arc<CompilerError<'a>>>> = files
+            .into_par_iter()
+            .map(|v| Compiler::recurse_deps(v, &Remapper::new("./")))
+            .collect();
+
+        // Collect Recurse Deps errors and try to resolve to the first one
+        let mut errors = recursed_file_sources
+            .iter()
+            .filter_map(|rfs| rfs.as_ref().err())
+            .collect::<Vec<&Arc<CompilerError>>>();
+        if !errors.is_empty() {
+            let error = errors.remove(0);
+            return Err(Arc::clone(error))
+        }
+
+        // Unpack recursed dependencies into FileSources
+        let files = recursed_file_sources
+            .into_iter()
+            .filter_map(|fs| fs.ok())
+            .collect::<Vec<Arc<FileSource>>>();
+        tracing::info!(target: "core", "COMPILER RECURSED {} FILE DEPENDENCIES", files.len());
+
+        // Parse each file once, sharing common dependencies across files
+        let session = Session::new(&files).map_err(Arc::new)?;
+
+        // Parallel Compilation
+        let potential_artifacts: Vec<Result<Artifact, CompilerError<'a>>> =
+            files.into_par_iter().map(|f| self.gen_session_artifact(&session, f)).collect();
+
+        let mut artifacts: Vec<Arc<Artifact>> = vec![];
+        let mut gen_errors: Vec<CompilerError<'a>> = vec![];
+
+        // Output errors + return OR print # of successfully compiled files
+        for r in potential_artifacts {
+            match r {
+                Ok(a) => artifacts.push(Arc::new(a)),
+                Err(ce) => gen_errors.push(ce),
             }
         }
 
+        if !gen_errors.is_empty() {
+            tracing::error!(target: "core", "{} FILES FAILED TO COMPILE", gen_errors.len());
+            return Err(Arc::new(CompilerError::FailedCompiles(gen_errors)))
+        }
+
         Ok(artifacts)
     }
 
@@ -223,8 +271,8 @@ impl<'a> Compiler<'a> {
     /// 1. Transform inputs into File Paths with [transform_paths](Compiler::transform_paths).
     /// 2. Fetch file sources in parallel with [fetch_sources](Compiler::fetch_sources).
     /// 3. Recurse file dependencies in parallel with [recurse_deps](Compiler::recurse_deps).
-    /// 4. For each top-level file, parse its contents and return a vec of [Contract](Contract)
-    ///    ASTs.
+    /// 4. Parse each file once in a [Session] and return a vec of the top-level files'
+    ///    [Contract](Contract) ASTs.
     pub fn grab_contracts(&self) -> Result<Vec<Contract>, Arc<CompilerError<'a>>> {
         // Grab the input files
         let file_paths: Vec<PathBuf> = Compiler::transform_paths(&self.sources)?;
@@ -269,95 +317,101 @@ impl<'a> Compiler<'a> {
             .collect::<Vec<Arc<FileSource>>>();
         tracing::info!(target: "core", "COMPILER RECURSED {} FILE DEPENDENCIES", files.len());
 
-        // Parse file sources and collect ASTs in parallel
+        // Parse file sources once and assemble their ASTs
+        let session = Session::new(&files).map_err(Arc::new)?;
         files
-            .into_par_iter()
-            .map(|file| {
-                // Fully Flatten a file into a source string containing source code of file and all
-                // its dependencies
-                let flattened = FileSource::fully_flatten(Arc::clone(&file));
-                tracing::info!(target: "core", "FLATTENED SOURCE FILE \"{}\"", file.path);
-                let full_source = FullFileSource {
-                    source: &flattened.0,
-                    file: Some(Arc::clone(&file)),
-                    spans: flattened.1,
-                };
-                tracing::debug!(target: "core", "GOT FULL SOURCE FOR PATH: {:?}", file.path);
-
-                // Perform Lexical Analysis
-                // Create a new lexer from the FileSource, flattening dependencies
-                let lexer: Lexer = Lexer::new(full_source);
-
-                // Grab the tokens from the lexer
-                let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
-                tracing::info!(target: "core", "LEXICAL ANALYSIS COMPLETE FOR \"{}\"", file.path);
-                tracing::info!(target: "core", "└─ TOKEN COUNT: {}", tokens.len());
-
-                // Parser incantation
-                let mut parser = Parser::new(tokens, Some(file.path.clone()));
-
-                // Parse into an AST
-                let parse_res = parser.parse().map_err(CompilerError::ParserError);
-                let mut contract = parse_res?;
-                contract.derive_storage_pointers();
-                contract.add_override_constants(&self.constant_overrides);
-                tracing::info!(target: "core", "PARSED CONTRACT [{}]", file.path);
-                Ok(contract)
-            })
+            .iter()
+            .map(|file| session.contract(file).map_err(Arc::new))
             .collect::<Result<Vec<Contract>, Arc<CompilerError<'a>>>>()
     }
 
+    /// Resolves the constants of a contract with the compiler's constant overrides and allocation
+    pub fn constant_env(&self, contract: &Contract) -> Result<ConstantEnv, CodegenError> {
+        ConstantEnv::with_allocation(contract, self.constant_overrides.as_ref(), self.allocation)
+    }
+
     /// Artifact Generation
     ///
     /// Compiles a FileSource into an Artifact.
     pub fn gen_artifact(&self, file: Arc<FileSource>) -> Result<Artifact, CompilerError<'a>> {
-        // Fully Flatten a file into a source string containing source code of file and all
-        // its dependencies
-        let flattened = FileSource::fully_flatten(Arc::clone(&file));
-        tracing::info!(target: "core", "FLATTENED SOURCE FILE \"{}\"", file.path);
-        let full_source = FullFileSource {
-            source: &flattened.0,
-            file: Some(Arc::clone(&file)),
-            spans: flattened.1,
-        };
-        tracing::debug!(target: "core", "GOT FULL SOURCE FOR PATH: {:?}", file.path);
+        let session = Session::new(&[Arc::clone(&file)])?;
+        self.gen_session_artifact(&session, file)
+    }
 
-        // Perform Lexical Analysis
-        // Create a new lexer from the FileSource, flattening dependencies
-        let lexer: Lexer = Lexer::new(full_source);
+    /// Session Artifact Generation
+    ///
+    /// Compiles a FileSource into an Artifact from the ASTs parsed by a [Session], which must
+    /// include the file.
+    pub fn gen_session_artifact(
+        &self,
+        session: &Session,
+        file: Arc<FileSource>,
+    ) -> Result<Artifact, CompilerError<'a>> {
+        let contract = session.contract(&file)?;
+        self.gen_variant(&file, &contract, self.constant_overrides.as_ref())
+    }
 
-        // Grab the tokens from the lexer
-        let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
-        tracing::info!(target: "core", "LEXICAL ANALYSIS COMPLETE FOR \"{}\"", file.path);
-        tracing::info!(target: "core", "└─ TOKEN COUNT: {}", tokens.len());
+    /// Variant Artifact Generation
+    ///
+    /// Compiles a FileSource into one Artifact per set of constant overrides. The file is parsed
+    /// once, and the variants are generated in parallel from the shared AST.
+    ///
+    /// Each set replaces the compiler's own `constant_overrides`.
+    pub fn gen_artifact_variants(
+        &self,
+        file: Arc<FileSource>,
+        variants: &[BTreeMap<&'a str, Literal>],
+    ) -> Result<Vec<Artifact>, CompilerError<'a>> {
+        let session = Session::new(&[Arc::clone(&file)])?;
+        let contract = session.contract(&file)?;
+        variants
+            .par_iter()
+            .map(|overrides| self.gen_variant(&file, &contract, Some(overrides)))
+            .collect()
+    }
 
-        // Parser incantation
-        let mut parser = Parser::new(tokens, Some(file.path.clone()));
+    /// Generates the Artifact of a parsed Contract under a set of constant overrides
+    fn gen_variant(
+        &self,
+        file: &Arc<FileSource>,
+        contract: &Contract,
+        overrides: Option<&BTreeMap<&str, Literal>>,
+    ) -> Result<Artifact, CompilerError<'a>> {
+        let env = ConstantEnv::with_allocation(contract, overrides, self.allocation).map_err(
+            |mut e| {
+                e.span = e.span.with_default_file(file);
+                CompilerError::CodegenError(e)
+            },
+        )?;
+
+        // Validate Function and Error Selectors
+        let collisions =
+            Codegen::check_selector_collisions(contract).and_then(|_| match &self.external_abi {
+                Some(abi) => Codegen::check_abi_selector_collisions(contract, abi),
+                None => Ok(()),
+            });
+        if let Err(mut e) = collisions {
+            tracing::error!(target: "core", "SELECTOR COLLISION IN CONTRACT [{}]", file.path);
+            e.span = e.span.with_default_file(file);
+            return Err(CompilerError::CodegenError(e))
+        }
 
-        // Parse into an AST
-        let parse_res = parser.parse().map_err(CompilerError::ParserError);
-        let mut contract = parse_res?;
-        contract.derive_storage_pointers();
-        contract.add_override_constants(&self.constant_overrides);
-        tracing::info!(target: "core", "PARSED CONTRACT [{}]", file.path);
+        // Generate the Main and Constructor Bytecode in parallel
+        let (main, constructor) = rayon::join(
+            || Codegen::generate_main_bytecode_with_source_map(contract, &env),
+            || Codegen::generate_constructor_bytecode_with_source_map(contract, &env),
+        );
 
         // Primary Bytecode Generation
         let mut cg = Codegen::new();
-        let main_bytecode = match Codegen::generate_main_bytecode(&contract) {
-            Ok(mb) => mb,
+        let main_bytecode = match main {
+            Ok((mb, source_map)) => {
+                cg.main_source_map = source_map;
+                mb
+            }
             Err(mut e) => {
                 tracing::error!(target: "core", "FAILED TO GENERATE MAIN BYTECODE FOR CONTRACT");
-                // Add File Source to Span
-                e.span = AstSpan(
-                    e.span
-                        .0
-                        .into_iter()
-                        .map(|mut s| {
-                            s.file = Some(Arc::clone(&file));
-                            s
-                        })
-                        .collect::<Vec<Span>>(),
-                );
+                e.span = e.span.with_default_file(file);
                 tracing::error!(target: "core", "Roll Failed with CodegenError: {:?}", e.kind);
                 return Err(CompilerError::CodegenError(e))
             }
@@ -366,26 +420,18 @@ impl<'a> Compiler<'a> {
 
         // Generate Constructor Bytecode
         let inputs = self.get_constructor_args();
-        let constructor_bytecode = match Codegen::generate_constructor_bytecode(&contract) {
-            Ok(mb) => mb,
+        let constructor_bytecode = match constructor {
+            Ok((mb, source_map)) => {
+                cg.constructor_source_map = source_map;
+                mb
+            }
             Err(mut e) => {
                 // Return any errors except if the inputs is empty and the constructor definition is
                 // missing
                 if e.kind != CodegenErrorKind::MissingMacroDefinition("CONSTRUCTOR".to_string()) ||
                     !inputs.is_empty()
                 {
-                    // Add File Source to Span
-                    let mut errs = e
-                        .span
-                        .0
-                        .into_iter()
-                        .map(|mut s| {
-                            s.file = Some(Arc::clone(&file));
-                            s
-                        })
-                        .collect::<Vec<Span>>();
-                    errs.dedup();
-                    e.span = AstSpan(errs);
+                    e.span = e.span.with_default_file(file);
                     tracing::error!(target: "codegen", "Constructor inputs provided, but contract missing \"CONSTRUCTOR\" macro!");
                     return Err(CompilerError::CodegenError(e))
                 }
@@ -402,15 +448,23 @@ impl<'a> Compiler<'a> {
         tracing::info!(target: "core", "ENCODED {} INPUTS", encoded_inputs.len());
 
         // Generate Artifact with ABI
-        let churn_res = cg.churn(file, encoded_inputs, &main_bytecode, &constructor_bytecode);
+        let churn_res =
+            cg.churn(Arc::clone(file), encoded_inputs, &main_bytecode, &constructor_bytecode);
         match churn_res {
             Ok(mut artifact) => {
+                artifact.storage_layout = env.layout.storage.clone();
+                artifact.transient_layout = env.layout.transient.clone();
+                artifact.memory_layout = env.layout.memory.clone();
+
                 // Then we can have the code gen output the artifact
-                let abiout = cg.abi_gen(contract, None);
+                let abiout = cg.abi_gen(contract.clone(), None);
                 match abiout {
                     Ok(abi) => {
                         tracing::info!(target: "core", "GENERATED ABI");
-                        artifact.abi = Some(abi)
+                        artifact.abi = Some(abi);
+                        if let Some(generated) = &cg.artifact {
+                            artifact.selectors = generated.selectors.clone();
+                        }
                     }
                     Err(e) => {
                         tracing::error!(target: "core", "ARTIFACT GENERATION FAILED: {:?}", e)
@@ -528,8 +582,12 @@ impl<'a> Compiler<'a> {
     /// Export Artifacts
     ///
     /// 1. Cleans any previous artifacts in the output directory.
-    /// 2. Exports artifacts in parallel as serialized json `Artifact` objects.
-    pub fn export_artifacts(artifacts: &Vec<Arc<Artifact>>, output: &OutputLocation) {
+    /// 2. Exports artifacts in parallel as json in the given [ArtifactFormat].
+    pub fn export_artifacts(
+        artifacts: &Vec<Arc<Artifact>>,
+        output: &OutputLocation,
+        format: ArtifactFormat,
+    ) {
         // Exit if empty output location
         if output.0.is_empty() {
             tracing::warn!(target: "core", "Exiting artifact export with empty output location!");
@@ -550,14 +608,10 @@ impl<'a> Compiler<'a> {
             // If it's a file type, we just export to `output.0`
             let json_out = match is_file {
                 true => output.0.clone(),
-                false => format!(
-                    "{}/{}.json",
-                    output.0,
-                    a.file.path.to_uppercase().replacen("./", "", 1)
-                ),
+                false => format.path(&output.0, a),
             };
 
-            if let Err(e) = a.export(&json_out) {
+            if let Err(e) = a.export_as(&json_out, format) {
                 tracing::error!(target: "core", "ARTIFACT EXPORT FAILED!\nError: {:?}", e);
             }
             tracing::info!(target: "core", "EXPORTED ARTIFACT TO \"{}\"", json_out);
@@ -568,9 +622,9 @@ impl<'a> Compiler<'a> {
     pub fn transform_paths(sources: &Vec<String>) -> Result<Vec<PathBuf>, CompilerError<'a>> {
         let mut paths = vec![];
         for f in sources {
-            // If the file is huff, use the path, otherwise unpack
+            // If the file is huff, an ABI or a Solidity interface, use the path, otherwise unpack
             let ext = Path::new(&f).extension().unwrap_or_default();
-            if ext.eq("huff") {
+            if ext.eq("huff") || FileSource::is_abi(f) || FileSource::is_sol_interface(f) {
                 paths.push(Path::new(&f).to_path_buf())
             } else {
                 // Otherwise, override the source files and use all files in the provided dir
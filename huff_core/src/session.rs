@@ -0,0 +1,150 @@
+//! ## Session
+//!
+//! A compilation session, which parses every file of a compilation once.
+
+use huff_lexer::*;
+use huff_parser::*;
+use huff_utils::prelude::*;
+use rayon::prelude::*;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+/// The parsed files of a compilation
+///
+/// Each file is lexed and parsed on its own, so that entry files including the same dependencies
+/// share the dependencies' ASTs. The AST of an entry file is then assembled from the ASTs of the
+/// files it includes.
+#[derive(Debug, Default)]
+pub struct Session {
+    /// The AST of each file, by path
+    asts: HashMap<String, Arc<Contract>>,
+}
+
+impl Session {
+    /// Parses the files and all of their dependencies in parallel, each file once.
+    pub fn new<'a>(files: &[Arc<FileSource>]) -> Result<Self, CompilerError<'a>> {
+        // Collect each file once, however many files include it
+        let mut unique: HashMap<String, Arc<FileSource>> = HashMap::new();
+        let mut stack = files.to_vec();
+        while let Some(file) = stack.pop() {
+            if unique.contains_key(&file.path) {
+                continue
+            }
+            stack.extend(file.dependencies.iter().flatten().cloned());
+            unique.insert(file.path.clone(), file);
+        }
+
+        let asts = unique
+            .into_par_iter()
+            .map(|(path, file)| Session::parse(&file).map(|ast| (path, Arc::new(ast))))
+            .collect::<Result<HashMap<String, Arc<Contract>>, CompilerError<'a>>>()?;
+        tracing::info!(target: "core", "SESSION PARSED {} FILES", asts.len());
+
+        Ok(Session { asts })
+    }
+
+    /// Returns the number of parsed files
+    pub fn len(&self) -> usize {
+        self.asts.len()
+    }
+
+    /// Returns whether the session has no files
+    pub fn is_empty(&self) -> bool {
+        self.asts.is_empty()
+    }
+
+    /// Returns the AST of a single file, without its dependencies
+    pub fn ast(&self, path: &str) -> Option<&Arc<Contract>> {
+        self.asts.get(path)
+    }
+
+    /// Assembles the AST of an entry file from the ASTs of the file and its dependencies.
+    ///
+    /// The definitions are ordered as if the dependencies were flattened into the entry file's
+    /// source, with each file included once. Signatures using structs from other files are
+    /// resolved, see [Contract::resolve_signatures], and types that none of the files define are
+    /// reported. Errors if a file wasn't parsed by this session.
+    pub fn contract<'a>(&self, file: &FileSource) -> Result<Contract, CompilerError<'a>> {
+        let mut contract = Contract::default();
+        self.include(file, &mut contract, &mut HashSet::new()).ok_or_else(|| {
+            tracing::error!(target: "core", "FILE NOT PARSED BY SESSION \"{}\"", file.path);
+            CompilerError::FileUnpackError(UnpackError::MissingFile(file.path.clone()))
+        })?;
+        contract.index_symbols();
+        contract.resolve_signatures().map_err(CompilerError::ParserError)?;
+        Ok(contract)
+    }
+
+    /// Appends the AST of a file and then those of its dependencies, depth-first
+    fn include(
+        &self,
+        file: &FileSource,
+        contract: &mut Contract,
+        included: &mut HashSet<String>,
+    ) -> Option<()> {
+        if !included.insert(file.path.clone()) {
+            return Some(())
+        }
+        contract.extend(self.asts.get(&file.path)?);
+        for dep in file.dependencies.iter().flatten() {
+            self.include(dep, contract, included)?;
+        }
+        Some(())
+    }
+
+    /// Lexes and parses a single file
+    fn parse<'a>(file: &Arc<FileSource>) -> Result<Contract, CompilerError<'a>> {
+        let source = file.source.as_deref().unwrap_or_default();
+
+        // JSON ABI files only contain declarations
+        if FileSource::is_abi(&file.path) {
+            let abi = Abi::from_json(source).map_err(|e| {
+                tracing::error!(target: "core", "INVALID ABI FILE \"{}\": {}", file.path, e);
+                CompilerError::ParserError(ParserError {
+                    kind: ParserErrorKind::InvalidAbi(e.to_string()),
+                    hint: Some(
+                        "Expected a JSON ABI array, or a JSON artifact with an `abi`.".to_string(),
+                    ),
+                    spans: AstSpan(vec![Span::new(0..source.len(), Some(Arc::clone(file)))]),
+                })
+            })?;
+            tracing::info!(target: "core", "DECLARED ABI FILE [{}]", file.path);
+            return Ok(Contract::from(&abi))
+        }
+
+        // As are Solidity interface files
+        if FileSource::is_sol_interface(&file.path) {
+            let abi = parse_sol_interface(source).map_err(|e| {
+                tracing::error!(target: "core", "INVALID SOLIDITY INTERFACE \"{}\": {}", file.path, e);
+                CompilerError::ParserError(ParserError {
+                    kind: ParserErrorKind::InvalidSolInterface(e),
+                    hint: Some(
+                        "Expected interface declarations without implementations.".to_string(),
+                    ),
+                    spans: AstSpan(vec![Span::new(0..source.len(), Some(Arc::clone(file)))]),
+                })
+            })?;
+            tracing::info!(target: "core", "DECLARED SOLIDITY INTERFACE [{}]", file.path);
+            return Ok(Contract::from(&abi))
+        }
+        let full_source = FullFileSource {
+            source,
+            file: Some(Arc::clone(file)),
+            spans: vec![(Arc::clone(file), Span::new(0..source.len(), None))],
+        };
+
+        // Perform Lexical Analysis
+        let lexer: Lexer = Lexer::new(full_source);
+        let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+        tracing::info!(target: "core", "LEXICAL ANALYSIS COMPLETE FOR \"{}\"", file.path);
+        tracing::info!(target: "core", "└─ TOKEN COUNT: {}", tokens.len());
+
+        // Parse into an AST
+        let mut parser = Parser::new(tokens, Some(file.path.clone()));
+        let contract = parser.parse().map_err(CompilerError::ParserError)?;
+        tracing::info!(target: "core", "PARSED FILE [{}]", file.path);
+        Ok(contract)
+    }
+}
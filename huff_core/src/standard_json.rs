@@ -0,0 +1,395 @@
+//! ## Standard JSON
+//!
+//! Compiles a [solc Standard JSON](https://docs.soliditylang.org/en/latest/using-the-compiler.html#compiler-input-and-output-json-description)
+//! input, so that tooling built around solc can drive huffc.
+//!
+//! Sources are compiled from their `content` in the input, never read from the filesystem, and
+//! includes must name other input sources. Every source with a `MAIN` macro is compiled into a
+//! contract named after its file.
+
+use crate::{session::Session, Compiler};
+use huff_lexer::Lexer;
+use huff_utils::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
+
+/// A Standard JSON input
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct StandardJsonInput {
+    /// The source language, which must be `Huff`
+    pub language: String,
+    /// The sources by path
+    pub sources: BTreeMap<String, StandardJsonSource>,
+    /// The compilation settings
+    #[serde(default)]
+    pub settings: StandardJsonSettings,
+}
+
+/// A source of a Standard JSON input
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct StandardJsonSource {
+    /// The source code
+    pub content: String,
+}
+
+/// The settings of a Standard JSON input
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StandardJsonSettings {
+    /// The target EVM version. huffc always targets the latest EVM version, so any other is
+    /// reported as a warning.
+    pub evm_version: Option<String>,
+    /// The optimizer settings
+    #[serde(default)]
+    pub optimizer: StandardJsonOptimizer,
+    /// Include path remappings, ie. `lib/=dependencies/lib/`
+    #[serde(default)]
+    pub remappings: Vec<String>,
+    /// Constant overrides by name, ie. `"OWNER": "0x01"`
+    #[serde(default)]
+    pub constants: BTreeMap<String, String>,
+    /// The outputs to generate, by source path and then contract name, where `*` matches any
+    #[serde(default)]
+    pub output_selection: BTreeMap<String, BTreeMap<String, Vec<String>>>,
+}
+
+/// The optimizer settings of a Standard JSON input
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct StandardJsonOptimizer {
+    /// Whether to optimize compilation
+    #[serde(default)]
+    pub enabled: bool,
+}
+
+/// A Standard JSON output
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct StandardJsonOutput {
+    /// The errors and warnings of the compilation
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<StandardJsonError>,
+    /// The ids of the sources by path
+    #[serde(default)]
+    pub sources: BTreeMap<String, StandardJsonSourceId>,
+    /// The selected outputs of each contract, by source path and then contract name
+    #[serde(default)]
+    pub contracts: BTreeMap<String, BTreeMap<String, Value>>,
+}
+
+/// The id of a compiled source, as indexed by source maps
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StandardJsonSourceId {
+    /// The source id
+    pub id: usize,
+}
+
+/// An error or warning of a Standard JSON output
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StandardJsonError {
+    /// The solc error type, ie. `ParserError`
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// The compiler component, which is always `general`
+    pub component: String,
+    /// Either `error` or `warning`
+    pub severity: String,
+    /// The error message
+    pub message: String,
+    /// The error message formatted with its source location
+    pub formatted_message: String,
+    /// The location of the error in its source
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_location: Option<StandardJsonSourceLocation>,
+}
+
+/// The location of a Standard JSON error in its source
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StandardJsonSourceLocation {
+    /// The path of the source
+    pub file: String,
+    /// The start offset in the source
+    pub start: usize,
+    /// The end offset in the source
+    pub end: usize,
+}
+
+impl StandardJsonError {
+    /// Creates an error without a source location
+    pub fn new(kind: &str, severity: &str, message: &str) -> Self {
+        Self {
+            kind: kind.to_string(),
+            component: "general".to_string(),
+            severity: severity.to_string(),
+            message: message.to_string(),
+            formatted_message: format!("{}: {}", kind, message),
+            source_location: None,
+        }
+    }
+
+    /// Converts a compiler error into errors, mapping file paths back to source paths
+    fn from_compiler_error(e: &CompilerError, paths: &HashMap<String, String>) -> Vec<Self> {
+        let (kind, spans) = match e {
+            CompilerError::FailedCompiles(errors) => {
+                return errors.iter().flat_map(|e| Self::from_compiler_error(e, paths)).collect()
+            }
+            CompilerError::LexicalError(le) => ("ParserError", vec![le.span.clone()]),
+            CompilerError::ParserError(pe) => ("ParserError", pe.spans.0.clone()),
+            CompilerError::CodegenError(ce) => ("CompilerError", ce.span.0.clone()),
+            CompilerError::PathBufRead(_) | CompilerError::FileUnpackError(_) => {
+                ("IOError", vec![])
+            }
+        };
+        let formatted_message = e.to_string().trim().to_string();
+        let message = formatted_message.lines().next().unwrap_or_default();
+        let source_location = spans.iter().find_map(|s| {
+            let file = paths.get(&s.file.as_ref()?.path)?;
+            Some(StandardJsonSourceLocation { file: file.clone(), start: s.start, end: s.end })
+        });
+        vec![Self {
+            message: message.trim_start_matches("Error: ").trim().to_string(),
+            formatted_message,
+            source_location,
+            ..Self::new(kind, "error", "")
+        }]
+    }
+}
+
+impl StandardJsonOutput {
+    /// An output with a single error
+    fn error(kind: &str, message: &str) -> Self {
+        Self { errors: vec![StandardJsonError::new(kind, "error", message)], ..Default::default() }
+    }
+}
+
+/// Compiles a Standard JSON input into a Standard JSON output.
+///
+/// Invalid inputs and compilation failures are reported in the output's `errors`.
+pub fn compile_standard_json(input: &str) -> String {
+    let output = match serde_json::from_str::<StandardJsonInput>(input) {
+        Ok(input) => compile(&input),
+        Err(e) => StandardJsonOutput::error("JSONError", &e.to_string()),
+    };
+    serde_json::to_string_pretty(&output).unwrap_or_default()
+}
+
+/// Compiles a deserialized Standard JSON input
+pub fn compile(input: &StandardJsonInput) -> StandardJsonOutput {
+    if input.language != "Huff" {
+        return StandardJsonOutput::error(
+            "JSONError",
+            &format!("Unsupported language \"{}\", expected \"Huff\"", input.language),
+        )
+    }
+    let settings = &input.settings;
+
+    // Constant overrides must be hex literals, as with `huffc -c`
+    let mut constants = BTreeMap::new();
+    for (name, value) in &settings.constants {
+        match value.strip_prefix("0x").filter(|v| !v.is_empty() && v.len() <= 64) {
+            Some(v) if v.chars().all(|c| c.is_ascii_hexdigit()) => {
+                constants.insert(name.as_str(), str_to_bytes32(v));
+            }
+            _ => {
+                return StandardJsonOutput::error(
+                    "JSONError",
+                    &format!("Invalid constant override \"{}\": \"{}\"", name, value),
+                )
+            }
+        }
+    }
+
+    let mut output = StandardJsonOutput::default();
+    if let Some(version) = &settings.evm_version {
+        output.errors.push(StandardJsonError::new(
+            "Warning",
+            "warning",
+            &format!("evmVersion \"{}\" is ignored, huffc targets the latest EVM version", version),
+        ));
+    }
+
+    // The compiler's paths are relative, ie. `./src/Token.huff` for `src/Token.huff`
+    let paths: HashMap<String, String> =
+        input.sources.keys().map(|key| (file_path(key), key.clone())).collect();
+    let remapper = Remapper {
+        remappings: settings.remappings.iter().filter_map(|r| Remapper::split(r)).collect(),
+        base_dir: String::default(),
+    };
+
+    // Resolve the includes of every source against the other sources
+    let mut files = vec![];
+    for (id, key) in input.sources.keys().enumerate() {
+        output.sources.insert(key.clone(), StandardJsonSourceId { id });
+        match resolve(&file_path(key), &input.sources, &remapper, &mut vec![]) {
+            Ok(file) => files.push(file),
+            Err(e) => {
+                output.errors.push(*e);
+                return output
+            }
+        }
+    }
+
+    let session = match Session::new(&files) {
+        Ok(s) => s,
+        Err(e) => {
+            output.errors.extend(StandardJsonError::from_compiler_error(&e, &paths));
+            return output
+        }
+    };
+
+    let mut compiler = Compiler::new(Arc::new(vec![]), None, None, Some(constants), false, false);
+    compiler.optimize = settings.optimizer.enabled;
+
+    for file in files {
+        // Sources without a MAIN macro only declare definitions for other sources
+        match session.contract(&file) {
+            Ok(contract) if contract.find_macro_by_name("MAIN").is_none() => continue,
+            Ok(_) => {}
+            Err(e) => {
+                output.errors.extend(StandardJsonError::from_compiler_error(&e, &paths));
+                continue
+            }
+        }
+        let key = paths[&file.path].clone();
+        match compiler.gen_session_artifact(&session, file) {
+            Ok(artifact) => {
+                let selection = select(&settings.output_selection, &key, &artifact.contract_name());
+                let contract = contract_output(&artifact, &selection, input.sources.keys());
+                output.contracts.entry(key).or_default().insert(artifact.contract_name(), contract);
+            }
+            Err(e) => output.errors.extend(StandardJsonError::from_compiler_error(&e, &paths)),
+        }
+    }
+    output
+}
+
+/// The compiler's path of a source
+fn file_path(key: &str) -> String {
+    match key.starts_with("./") || key.starts_with('/') {
+        true => key.to_string(),
+        false => format!("./{}", key),
+    }
+}
+
+/// Builds the FileSource of a source and its includes, recursively
+fn resolve(
+    path: &str,
+    sources: &BTreeMap<String, StandardJsonSource>,
+    remapper: &Remapper,
+    including: &mut Vec<String>,
+) -> Result<Arc<FileSource>, Box<StandardJsonError>> {
+    let key = path.trim_start_matches("./");
+    let source = match sources.iter().find(|(k, _)| k.trim_start_matches("./") == key) {
+        Some((_, s)) => s.content.clone(),
+        None => {
+            return Err(Box::new(StandardJsonError::new(
+                "IOError",
+                "error",
+                &format!("Source \"{}\" not found in the input sources", key),
+            )))
+        }
+    };
+
+    // A file already being resolved is an include cycle
+    if including.iter().any(|p| p == path) {
+        return Err(Box::new(StandardJsonError::new(
+            "ParserError",
+            "error",
+            &format!("Include cycle: {} -> {}", including.join(" -> "), path),
+        )))
+    }
+    including.push(path.to_string());
+    let dependencies = Lexer::lex_imports(&source)
+        .iter()
+        .map(|import| {
+            let import = remapper
+                .remap(import)
+                .map(|p| file_path(&p))
+                .or_else(|| FileSource::localize_file(path, import))
+                .unwrap_or_default();
+            resolve(&import, sources, remapper, including)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    including.pop();
+
+    Ok(Arc::new(FileSource {
+        id: uuid::Uuid::new_v4(),
+        path: path.to_string(),
+        source: Some(source),
+        access: None,
+        dependencies: Some(dependencies),
+    }))
+}
+
+/// The outputs selected for a contract
+fn select(
+    selection: &BTreeMap<String, BTreeMap<String, Vec<String>>>,
+    key: &str,
+    name: &str,
+) -> Vec<String> {
+    [selection.get(key), selection.get("*")]
+        .into_iter()
+        .flatten()
+        .flat_map(|contracts| [contracts.get(name), contracts.get("*")])
+        .flatten()
+        .flatten()
+        .cloned()
+        .collect()
+}
+
+/// The selected outputs of a compiled contract
+fn contract_output<'a>(
+    artifact: &Artifact,
+    selection: &[String],
+    keys: impl Iterator<Item = &'a String>,
+) -> Value {
+    // `evm.bytecode` selects `evm.bytecode.object`, and `*` selects everything
+    let selected = |output: &str| {
+        selection.iter().any(|s| s == "*" || output == s || output.starts_with(&format!("{}.", s)))
+    };
+
+    // Source maps index the input sources in order
+    let files = keys.map(|k| file_path(k)).collect::<Vec<_>>();
+    let bytecode = |code: &str, source_map: &[SourceMapEntry], prefix: &str| {
+        let mut bytecode = Map::new();
+        if selected(&format!("{}.object", prefix)) {
+            bytecode.insert("object".to_string(), json!(code));
+        }
+        if selected(&format!("{}.sourceMap", prefix)) {
+            let code = str_to_vec(code).unwrap_or_default();
+            bytecode.insert(
+                "sourceMap".to_string(),
+                json!(compress_source_map(&code, source_map, &files)),
+            );
+        }
+        if selected(&format!("{}.linkReferences", prefix)) {
+            bytecode.insert("linkReferences".to_string(), json!({}));
+        }
+        bytecode
+    };
+
+    let mut evm = Map::new();
+    let deploy = bytecode(&artifact.bytecode, &artifact.source_map, "evm.bytecode");
+    if !deploy.is_empty() {
+        evm.insert("bytecode".to_string(), Value::Object(deploy));
+    }
+    let runtime = bytecode(&artifact.runtime, &artifact.runtime_source_map, "evm.deployedBytecode");
+    if !runtime.is_empty() {
+        evm.insert("deployedBytecode".to_string(), Value::Object(runtime));
+    }
+    if selected("evm.methodIdentifiers") {
+        evm.insert("methodIdentifiers".to_string(), json!(artifact.selectors.method_identifiers));
+    }
+
+    let mut contract = Map::new();
+    if selected("abi") {
+        contract.insert("abi".to_string(), json!(artifact.abi.clone().unwrap_or_default()));
+    }
+    if !evm.is_empty() {
+        contract.insert("evm".to_string(), Value::Object(evm));
+    }
+    Value::Object(contract)
+}
@@ -0,0 +1,168 @@
+mod common;
+
+use std::sync::Arc;
+
+use common::file;
+use huff_core::{session::Session, Compiler};
+use huff_lexer::*;
+use huff_parser::*;
+use huff_utils::prelude::*;
+
+const IERC20: &str = r#"[
+    {
+        "type": "function",
+        "name": "transfer",
+        "inputs": [
+            { "name": "to", "type": "address" },
+            { "name": "amount", "type": "uint256" }
+        ],
+        "outputs": [{ "name": "", "type": "bool" }],
+        "stateMutability": "nonpayable"
+    },
+    {
+        "type": "function",
+        "name": "balanceOf",
+        "inputs": [{ "name": "owner", "type": "address" }],
+        "outputs": [{ "name": "", "type": "uint256" }],
+        "stateMutability": "view"
+    },
+    {
+        "type": "function",
+        "name": "permitBatch",
+        "inputs": [
+            {
+                "name": "permits",
+                "type": "tuple[]",
+                "internalType": "struct Permit[]",
+                "components": [
+                    { "name": "spender", "type": "address" },
+                    {
+                        "name": "details",
+                        "type": "tuple",
+                        "internalType": "struct Details",
+                        "components": [
+                            { "name": "amount", "type": "uint160" },
+                            { "name": "expiration", "type": "uint48" }
+                        ]
+                    }
+                ]
+            },
+            {
+                "name": "signature",
+                "type": "tuple",
+                "components": [
+                    { "name": "", "type": "bytes32" },
+                    { "name": "", "type": "bytes32" }
+                ]
+            }
+        ],
+        "outputs": [],
+        "stateMutability": "payable"
+    },
+    {
+        "type": "event",
+        "name": "Transfer",
+        "inputs": [
+            { "name": "from", "type": "address", "indexed": true },
+            { "name": "to", "type": "address", "indexed": true },
+            { "name": "value", "type": "uint256", "indexed": false }
+        ],
+        "anonymous": false
+    },
+    {
+        "type": "error",
+        "name": "InsufficientBalance",
+        "inputs": [{ "name": "available", "type": "uint256" }]
+    }
+]"#;
+
+#[test]
+fn test_generates_huff_declarations() {
+    let abi = Abi::from_json(IERC20).unwrap();
+    assert_eq!(
+        gen_huff_interface(&abi),
+        "#define struct Details(uint160 amount, uint48 expiration)\n\
+        #define struct Permit(address spender, Details details)\n\
+        \n\
+        #define function balanceOf(address owner) view returns (uint256)\n\
+        #define function permitBatch(Permit[] permits, (bytes32,bytes32) signature) payable returns ()\n\
+        #define function transfer(address to, uint256 amount) nonpayable returns (bool)\n\
+        \n\
+        #define event Transfer(address indexed from, address indexed to, uint256 value)\n\
+        \n\
+        #define error InsufficientBalance(uint256 available)\n"
+    );
+}
+
+#[test]
+fn test_generated_declarations_roundtrip() {
+    let abi = Abi::from_json(IERC20).unwrap();
+    let source = gen_huff_interface(&abi);
+
+    let flattened_source = FullFileSource { source: &source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+    let mut contract = parser.parse().unwrap();
+    contract.resolve_signatures().unwrap();
+
+    // The parsed selectors match the ABI's
+    assert_eq!(
+        contract.functions.iter().map(|f| f.signature).collect::<Vec<[u8; 4]>>(),
+        abi.functions.values().map(|f| f.selector()).collect::<Vec<[u8; 4]>>()
+    );
+    let generated: Abi = contract.into();
+    assert_eq!(generated.to_json().unwrap(), abi.to_json().unwrap());
+}
+
+#[test]
+fn test_includes_abi_file() {
+    let interface = file("./IERC20.abi.json", IERC20, vec![]);
+    let main = file(
+        "./ERC20.huff",
+        r#"
+        #include "./IERC20.abi.json"
+        #define macro MAIN() = takes (0) returns (0) {
+            __FUNC_SIG(transfer)
+            __EVENT_HASH(Transfer)
+            __ERROR(InsufficientBalance)
+        }
+        "#,
+        vec![interface],
+    );
+
+    let session = Session::new(&[Arc::clone(&main)]).unwrap();
+    let contract = session.contract(&main).unwrap();
+    assert_eq!(contract.functions.len(), 3);
+    assert_eq!(contract.structs.len(), 2);
+
+    let compiler = Compiler::new(Arc::new(vec![]), None, None, None, false, false);
+    let artifact = compiler.gen_session_artifact(&session, main).unwrap();
+    assert_eq!(
+        artifact.runtime,
+        "63a9059cbb\
+        7fddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef\
+        7f9266535100000000000000000000000000000000000000000000000000000000"
+    );
+    assert_eq!(artifact.abi.unwrap().functions.len(), 3);
+}
+
+#[test]
+fn test_invalid_abi_file() {
+    let interface = file("./IERC20.abi.json", r#"[{ "type": "function" }]"#, vec![]);
+    let main = file("./ERC20.huff", r#"#include "./IERC20.abi.json""#, vec![interface]);
+
+    match Session::new(&[main]) {
+        Err(CompilerError::ParserError(e)) => match e.kind {
+            ParserErrorKind::InvalidAbi(_) => {}
+            _ => panic!("moose"),
+        },
+        _ => panic!("moose"),
+    }
+}
+
+#[test]
+fn test_transform_abi_path() {
+    let paths = Compiler::transform_paths(&vec!["./IERC20.abi.json".to_string()]).unwrap();
+    assert_eq!(paths, vec![std::path::PathBuf::from("./IERC20.abi.json")]);
+}
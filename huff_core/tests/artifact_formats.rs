@@ -0,0 +1,112 @@
+mod common;
+
+use common::file;
+use huff_utils::prelude::*;
+
+const SOURCE: &str = r#"#define function transfer(address,uint256) nonpayable returns (bool)
+
+#define macro INNER() = takes (0) returns (0) {
+    0x01 0x02 add
+}
+
+#define macro MAIN() = takes (0) returns (0) {
+    INNER()
+    __FUNC_SIG(transfer)
+}
+"#;
+
+fn artifact(path: &str) -> Artifact {
+    common::artifact(file(path, SOURCE, vec![]))
+}
+
+#[test]
+fn test_parses_artifact_formats() {
+    assert_eq!(ArtifactFormat::try_from("huff"), Ok(ArtifactFormat::Huff));
+    assert_eq!(ArtifactFormat::try_from("foundry"), Ok(ArtifactFormat::Foundry));
+    assert_eq!(ArtifactFormat::try_from("hardhat"), Ok(ArtifactFormat::Hardhat));
+    assert_eq!(
+        ArtifactFormat::try_from("truffle"),
+        Err("Unknown artifact format \"truffle\", expected foundry, hardhat or huff".to_string())
+    );
+}
+
+#[test]
+fn test_artifact_paths() {
+    let artifact = artifact("./contracts/Token.huff");
+    assert_eq!(artifact.contract_name(), "Token");
+    assert_eq!(ArtifactFormat::Huff.path("./out", &artifact), "./out/CONTRACTS/TOKEN.HUFF.json");
+    assert_eq!(ArtifactFormat::Foundry.path("./out", &artifact), "./out/Token.huff/Token.json");
+    assert_eq!(
+        ArtifactFormat::Hardhat.path("./artifacts", &artifact),
+        "./artifacts/contracts/Token.huff/Token.json"
+    );
+}
+
+#[test]
+fn test_nested_artifact_paths() {
+    let artifact = artifact("./src/tokens/erc20/Token.huff");
+    assert_eq!(ArtifactFormat::Foundry.path("./out", &artifact), "./out/Token.huff/Token.json");
+    assert_eq!(
+        ArtifactFormat::Hardhat.path("./artifacts", &artifact),
+        "./artifacts/src/tokens/erc20/Token.huff/Token.json"
+    );
+}
+
+#[test]
+fn test_foundry_artifact() {
+    let artifact = artifact("./contracts/Token.huff");
+    let json: serde_json::Value =
+        serde_json::from_str(&ArtifactFormat::Foundry.emit(&artifact).unwrap()).unwrap();
+
+    assert_eq!(json["abi"], serde_json::to_value(artifact.abi.clone().unwrap()).unwrap());
+    assert_eq!(json["bytecode"]["object"], format!("0x{}", artifact.bytecode));
+    assert_eq!(json["bytecode"]["linkReferences"], serde_json::json!({}));
+    assert_eq!(json["deployedBytecode"]["object"], "0x600160020163a9059cbb");
+    assert_eq!(json["deployedBytecode"]["sourceMap"], "124:2:0:-;129;132:3;202:19");
+    assert_eq!(json["deployedBytecode"]["immutableReferences"], serde_json::json!({}));
+    assert_eq!(
+        json["methodIdentifiers"],
+        serde_json::json!({ "transfer(address,uint256)": "a9059cbb" })
+    );
+}
+
+#[test]
+fn test_hardhat_artifact() {
+    let artifact = artifact("./contracts/Token.huff");
+    let json: serde_json::Value =
+        serde_json::from_str(&ArtifactFormat::Hardhat.emit(&artifact).unwrap()).unwrap();
+
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "_format": "hh-sol-artifact-1",
+            "contractName": "Token",
+            "sourceName": "contracts/Token.huff",
+            "abi": serde_json::to_value(artifact.abi.clone().unwrap()).unwrap(),
+            "bytecode": format!("0x{}", artifact.bytecode),
+            "deployedBytecode": "0x600160020163a9059cbb",
+            "linkReferences": {},
+            "deployedLinkReferences": {},
+        })
+    );
+}
+
+#[test]
+fn test_compress_source_map() {
+    // PUSH1 0x01 PUSH1 0x02 ADD STOP
+    let code = [0x60, 0x01, 0x60, 0x02, 0x01, 0x00];
+    let entry = |offset, length, file: &str, start, end| SourceMapEntry {
+        offset,
+        length,
+        name: "MAIN".to_string(),
+        data: false,
+        file: Some(file.to_string()),
+        line: 1,
+        start,
+        end,
+    };
+    let source_map = vec![entry(0, 5, "main.huff", 10, 40), entry(2, 2, "inner.huff", 5, 9)];
+    let files = vec!["main.huff".to_string(), "inner.huff".to_string()];
+
+    assert_eq!(compress_source_map(&code, &source_map, &files), "10:30:0:-;5:4:1;10:30:0;-1:-1:-1");
+}
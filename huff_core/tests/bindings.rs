@@ -0,0 +1,160 @@
+mod common;
+
+use std::sync::Arc;
+
+use common::file;
+use huff_core::Compiler;
+use huff_utils::prelude::*;
+
+const OWNED: &str = r#"
+#define struct Call(address target, bytes callData)
+
+#define function owner() view returns (address)
+#define function execute(Call call) payable returns (bytes result)
+#define function execute(Call[] calls) payable returns (bytes[])
+#define event OwnerUpdated(address indexed user, address indexed newOwner)
+
+#define macro MAIN() = takes (0) returns (0) {
+    __FUNC_SIG(owner)
+}
+"#;
+
+fn artifact(path: &str, source: &str) -> Arc<Artifact> {
+    Arc::new(common::artifact(file(path, source, vec![])))
+}
+
+#[test]
+fn test_parses_bindings_languages() {
+    assert_eq!(BindingsLanguage::try_from("rust"), Ok(BindingsLanguage::Rust));
+    assert_eq!(BindingsLanguage::try_from("ts"), Ok(BindingsLanguage::TypeScript));
+    assert_eq!(
+        BindingsLanguage::try_from("go"),
+        Err("Unknown bindings language \"go\", expected rust or ts".to_string())
+    );
+}
+
+#[test]
+fn test_rust_bindings() {
+    let artifact = artifact("./src/Owned.huff", OWNED);
+    let bindings = gen_bindings(&[artifact], BindingsLanguage::Rust).unwrap();
+    assert_eq!(
+        bindings.iter().map(|(f, _)| f.as_str()).collect::<Vec<_>>(),
+        vec!["owned.rs", "mod.rs"]
+    );
+    assert_eq!(bindings[1].1, "//! Bindings generated by huffc, do not edit.\n\npub mod owned;\n");
+    assert_eq!(bindings[0].1, include_str!("bindings/owned.rs"));
+}
+
+#[test]
+fn test_ts_bindings() {
+    let artifact = artifact(
+        "./src/ERC20Token.huff",
+        r#"
+        #define function constructor(address owner, uint256 supply) nonpayable returns ()
+        #define macro MAIN() = takes (0) returns (0) {}
+        "#,
+    );
+    let abi = artifact.abi.clone().unwrap().to_json().unwrap();
+    let bindings = gen_bindings(&[artifact], BindingsLanguage::TypeScript).unwrap();
+
+    assert_eq!(
+        bindings[1],
+        (
+            "index.ts".to_string(),
+            "// Bindings generated by huffc, do not edit.\n\nexport * from \"./ERC20Token\";\n"
+                .to_string()
+        )
+    );
+    assert_eq!(bindings[0].0, "ERC20Token.ts");
+    assert_eq!(
+        bindings[0].1,
+        format!(
+            "// Bindings for the `ERC20Token` contract, generated by huffc from `src/ERC20Token.huff`.\n\
+            // Do not edit, regenerate them with `huffc bindings --lang ts`.\n\
+            \n\
+            import {{ ContractFactory, type ContractRunner }} from \"ethers\";\n\
+            \n\
+            /** The ABI of the `ERC20Token` contract */\n\
+            export const erc20TokenAbi = {} as const;\n\
+            \n\
+            /** The bytecode of the `ERC20Token` contract, including any constructor arguments passed to huffc */\n\
+            export const erc20TokenBytecode = \"0x60008060093d393df3\" as const;\n\
+            \n\
+            /** The runtime bytecode of the `ERC20Token` contract */\n\
+            export const erc20TokenDeployedBytecode = \"0x\" as const;\n\
+            \n\
+            /** Deploys the `ERC20Token` contract, and waits for its deployment */\n\
+            export async function deployERC20Token(runner: ContractRunner, owner: string, supply: bigint) {{\n  \
+            const factory = new ContractFactory(erc20TokenAbi, erc20TokenBytecode, runner);\n  \
+            const contract = await factory.deploy(owner, supply);\n  \
+            return contract.waitForDeployment();\n\
+            }}\n",
+            abi
+        )
+    );
+}
+
+#[test]
+fn test_bindings_ignore_current_dir_components() {
+    let bindings = |path| gen_bindings(&[artifact(path, OWNED)], BindingsLanguage::Rust).unwrap();
+    assert_eq!(bindings("./Owned.huff"), bindings("Owned.huff"));
+    assert_eq!(bindings("./src/./Owned.huff"), bindings("src/Owned.huff"));
+}
+
+#[test]
+fn test_rejects_duplicate_contract_names() {
+    let artifacts = vec![artifact("./src/Owned.huff", OWNED), artifact("./lib/Owned.huff", OWNED)];
+    assert_eq!(
+        gen_bindings(&artifacts, BindingsLanguage::Rust),
+        Err("Contracts \"./src/Owned.huff\" and \"./lib/Owned.huff\" both generate bindings \"owned.rs\"".to_string())
+    );
+}
+
+#[test]
+fn test_compiles_without_exporting() {
+    let dir = std::env::temp_dir().join(format!("huff-bindings-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("Owned.huff");
+    std::fs::write(&path, OWNED).unwrap();
+    let out = dir.join("artifacts");
+
+    let compiler = Compiler::new(
+        Arc::new(vec![path.to_str().unwrap().to_string()]),
+        Some(out.to_str().unwrap().to_string()),
+        None,
+        None,
+        false,
+        false,
+    );
+    let artifacts = compiler.compile().unwrap();
+    assert_eq!(artifacts.len(), 1);
+    assert_eq!(artifacts[0].contract_name(), "Owned");
+    assert!(!out.exists());
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_checks_bindings() {
+    let dir = std::env::temp_dir().join(format!("huff-bindings-{}", uuid::Uuid::new_v4()));
+    let dir = dir.to_str().unwrap();
+    let bindings =
+        gen_bindings(&[artifact("./src/Owned.huff", OWNED)], BindingsLanguage::Rust).unwrap();
+
+    // Nothing was written yet
+    assert_eq!(check_bindings(dir, &bindings, BindingsLanguage::Rust), vec!["owned.rs", "mod.rs"]);
+
+    export_bindings(dir, &bindings).unwrap();
+    assert_eq!(check_bindings(dir, &bindings, BindingsLanguage::Rust), Vec::<String>::new());
+
+    // Edited and leftover bindings are stale, other files are ignored
+    std::fs::write(format!("{}/owned.rs", dir), "// edited").unwrap();
+    std::fs::write(format!("{}/removed.rs", dir), "").unwrap();
+    std::fs::write(format!("{}/README.md", dir), "").unwrap();
+    assert_eq!(
+        check_bindings(dir, &bindings, BindingsLanguage::Rust),
+        vec!["owned.rs", "removed.rs"]
+    );
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
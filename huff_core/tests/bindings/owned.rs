@@ -0,0 +1,110 @@
+//! Bindings for the `Owned` contract, generated by huffc from `src/Owned.huff`.
+//!
+//! Do not edit, regenerate them with `huffc bindings --lang rust`.
+#![allow(clippy::all)]
+
+/// The ABI of the `Owned` contract
+pub static OWNED_ABI: ::ethers::contract::Lazy<::ethers::core::abi::Abi> =
+    ::ethers::contract::Lazy::new(|| {
+        ::ethers::core::utils::__serde_json::from_str(r#"[{"type":"function","name":"execute","inputs":[{"name":"call","type":"tuple","internalType":"struct Call","components":[{"name":"target","type":"address"},{"name":"callData","type":"bytes"}]}],"outputs":[{"name":"result","type":"bytes"}],"stateMutability":"payable"},{"type":"function","name":"execute","inputs":[{"name":"calls","type":"tuple[]","internalType":"struct Call[]","components":[{"name":"target","type":"address"},{"name":"callData","type":"bytes"}]}],"outputs":[{"name":"","type":"bytes[]"}],"stateMutability":"payable"},{"type":"function","name":"owner","inputs":[],"outputs":[{"name":"","type":"address"}],"stateMutability":"view"},{"type":"event","name":"OwnerUpdated","inputs":[{"name":"user","type":"address","indexed":true},{"name":"newOwner","type":"address","indexed":true}],"anonymous":false}]"#).expect("invalid abi")
+    });
+
+/// The `Owned` contract's bytecode, including any constructor arguments passed to huffc
+pub static OWNED_BYTECODE: ::ethers::contract::Lazy<::ethers::core::types::Bytes> =
+    ::ethers::contract::Lazy::new(|| "0x60058060093d393df3638da5cb5b".parse().expect("invalid bytecode"));
+
+/// The `Owned` contract's runtime bytecode
+pub static OWNED_DEPLOYED_BYTECODE: ::ethers::contract::Lazy<::ethers::core::types::Bytes> =
+    ::ethers::contract::Lazy::new(|| "0x638da5cb5b".parse().expect("invalid bytecode"));
+
+/// The `Owned` contract
+pub struct Owned<M>(::ethers::contract::Contract<M>);
+
+impl<M> Clone for Owned<M> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<M> ::std::ops::Deref for Owned<M> {
+    type Target = ::ethers::contract::Contract<M>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<M> From<::ethers::contract::Contract<M>> for Owned<M> {
+    fn from(contract: ::ethers::contract::Contract<M>) -> Self {
+        Self(contract)
+    }
+}
+
+impl<M: ::ethers::providers::Middleware> Owned<M> {
+    /// Creates a binding of the contract deployed at `address`
+    pub fn new<T: Into<::ethers::core::types::Address>>(address: T, client: ::std::sync::Arc<M>) -> Self {
+        Self(::ethers::contract::Contract::new(address.into(), OWNED_ABI.clone(), client))
+    }
+
+    /// Deploys the contract, ABI encoding the constructor arguments after its bytecode
+    pub fn deploy<T: ::ethers::core::abi::Tokenize>(
+        client: ::std::sync::Arc<M>,
+        constructor_args: T,
+    ) -> Result<::ethers::contract::ContractDeployer<M, Self>, ::ethers::contract::ContractError<M>> {
+        let factory = ::ethers::contract::ContractFactory::new(
+            OWNED_ABI.clone(),
+            OWNED_BYTECODE.clone(),
+            client,
+        );
+        Ok(::ethers::contract::ContractDeployer::new(factory.deploy(constructor_args)?))
+    }
+
+    /// Calls `execute((address,bytes))`, with selector `0xef4d7b75`
+    pub fn execute(
+        &self,
+        call: Call,
+    ) -> ::ethers::contract::builders::ContractCall<M, ::ethers::core::types::Bytes> {
+        self.0
+            .method_hash([239, 77, 123, 117], (call,))
+            .expect("method not found (this should never happen)")
+    }
+
+    /// Calls `execute((address,bytes)[])`, with selector `0xbaae8abf`
+    pub fn execute1(
+        &self,
+        calls: ::std::vec::Vec<Call>,
+    ) -> ::ethers::contract::builders::ContractCall<M, ::std::vec::Vec<::ethers::core::types::Bytes>> {
+        self.0
+            .method_hash([186, 174, 138, 191], (calls,))
+            .expect("method not found (this should never happen)")
+    }
+
+    /// Calls `owner()`, with selector `0x8da5cb5b`
+    pub fn owner(&self) -> ::ethers::contract::builders::ContractCall<M, ::ethers::core::types::Address> {
+        self.0
+            .method_hash([141, 165, 203, 91], ())
+            .expect("method not found (this should never happen)")
+    }
+
+    /// Returns a filter of `OwnerUpdated(address,address)` events
+    pub fn owner_updated_filter(&self) -> ::ethers::contract::builders::Event<::std::sync::Arc<M>, M, OwnerUpdatedFilter> {
+        self.0.event()
+    }
+}
+
+/// The `OwnerUpdated(address,address)` event
+#[derive(Clone, Debug, PartialEq, Eq, ::ethers::contract::EthEvent)]
+#[ethevent(name = "OwnerUpdated", abi = "OwnerUpdated(address,address)")]
+pub struct OwnerUpdatedFilter {
+    #[ethevent(indexed)]
+    pub user: ::ethers::core::types::Address,
+    #[ethevent(indexed)]
+    pub new_owner: ::ethers::core::types::Address,
+}
+
+/// The `Call` struct
+#[derive(Clone, Debug, PartialEq, Eq, ::ethers::contract::EthAbiType, ::ethers::contract::EthAbiCodec)]
+pub struct Call {
+    pub target: ::ethers::core::types::Address,
+    pub call_data: ::ethers::core::types::Bytes,
+}
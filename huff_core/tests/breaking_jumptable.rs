@@ -138,10 +138,10 @@ fn test_breaking_jump_table() {
     let mut parser = Parser::new(tokens, None);
 
     // Parse the AST
-    let mut contract = parser.parse().unwrap();
+    let contract = parser.parse().unwrap();
 
-    // Derive storage pointers
-    contract.derive_storage_pointers();
+    // Resolve the constants
+    let env = ConstantEnv::new(&contract, None).unwrap();
 
     // Instantiate Codegen
     let cg = Codegen::new();
@@ -150,6 +150,6 @@ fn test_breaking_jump_table() {
     assert!(cg.artifact.is_none());
 
     // Have the Codegen create the main macro bytecode
-    let mbytes = Codegen::generate_main_bytecode(&contract).unwrap();
+    let mbytes = Codegen::generate_main_bytecode(&contract, &env).unwrap();
     assert_eq!(mbytes, String::from("60fe6100cc600039600080fd5b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b805b80000c000e00100012001400160018001a001c001e00200022002400260028002a002c002e00300032003400360038003a003c003e00400042004400460048004a004c004e00500052005400560058005a005c005e00600062006400660068006a006c006e00700072007400760078007a007c007e00800082008400860088008a008c008e00900092009400960098009a009c009e00a000a200a400a600a800aa00ac00ae00b000b200b400b600b800ba00bc00be00c000c200c400c600c800ca00ac00ae00b000b200b400b600b800ba00bc00be00c000c200c400c600c800ca00ac00ae00b000b200b400b600b800ba00bc00be00c000c200c400c600c8"));
 }
@@ -28,10 +28,10 @@ fn test_codesize_builtin() {
     let mut parser = Parser::new(tokens, None);
 
     // Parse the AST
-    let mut contract = parser.parse().unwrap();
+    let contract = parser.parse().unwrap();
 
-    // Derive storage pointers
-    contract.derive_storage_pointers();
+    // Resolve the constants
+    let env = ConstantEnv::new(&contract, None).unwrap();
 
     // Instantiate Codegen
     let cg = Codegen::new();
@@ -40,7 +40,7 @@ fn test_codesize_builtin() {
     assert!(cg.artifact.is_none());
 
     // Have the Codegen create the constructor bytecode
-    let cbytes = Codegen::generate_constructor_bytecode(&contract).unwrap();
+    let cbytes = Codegen::generate_constructor_bytecode(&contract, &env).unwrap();
     assert_eq!(cbytes, String::from("6004"));
 }
 
@@ -105,10 +105,10 @@ fn test_tablesize_builtin() {
     let mut parser = Parser::new(tokens, None);
 
     // Parse the AST
-    let mut contract = parser.parse().unwrap();
+    let contract = parser.parse().unwrap();
 
-    // Derive storage pointers
-    contract.derive_storage_pointers();
+    // Resolve the constants
+    let env = ConstantEnv::new(&contract, None).unwrap();
 
     // Instantiate Codegen
     let cg = Codegen::new();
@@ -117,7 +117,7 @@ fn test_tablesize_builtin() {
     assert!(cg.artifact.is_none());
 
     // Have the Codegen create the constructor bytecode
-    let mbytes = Codegen::generate_main_bytecode(&contract).unwrap();
+    let mbytes = Codegen::generate_main_bytecode(&contract, &env).unwrap();
     assert_eq!(mbytes, String::from("6008608061002c60003960205b60006000f35b60006000f35b60006000f35b60006000f3000c00120018001e000000000000000000000000000000000000000000000000000000000000000c00000000000000000000000000000000000000000000000000000000000000120000000000000000000000000000000000000000000000000000000000000018000000000000000000000000000000000000000000000000000000000000001eDEADBEEFDEADBEEFDEADBEEFDEADBEEFDEADBEEFDEADBEEFDEADBEEFDEADBEEF"));
 }
 
@@ -169,10 +169,10 @@ fn test_tablestart_builtin() {
     let mut parser = Parser::new(tokens, None);
 
     // Parse the AST
-    let mut contract = parser.parse().unwrap();
+    let contract = parser.parse().unwrap();
 
-    // Derive storage pointers
-    contract.derive_storage_pointers();
+    // Resolve the constants
+    let env = ConstantEnv::new(&contract, None).unwrap();
 
     // Instantiate Codegen
     let cg = Codegen::new();
@@ -181,7 +181,7 @@ fn test_tablestart_builtin() {
     assert!(cg.artifact.is_none());
 
     // Have the Codegen create the constructor bytecode
-    let cbytes = Codegen::generate_constructor_bytecode(&contract).unwrap();
+    let cbytes = Codegen::generate_constructor_bytecode(&contract, &env).unwrap();
     assert_eq!(cbytes, String::from("61001e6100265b60006000f35b60006000f35b60006000f35b60006000f30006000c001200180000000000000000000000000000000000000000000000000000000000000006000000000000000000000000000000000000000000000000000000000000000c00000000000000000000000000000000000000000000000000000000000000120000000000000000000000000000000000000000000000000000000000000018"));
 }
 
@@ -232,10 +232,10 @@ fn test_jump_table_exhaustive_usage() {
     let mut parser = Parser::new(tokens, None);
 
     // Parse the AST
-    let mut contract = parser.parse().unwrap();
+    let contract = parser.parse().unwrap();
 
-    // Derive storage pointers
-    contract.derive_storage_pointers();
+    // Resolve the constants
+    let env = ConstantEnv::new(&contract, None).unwrap();
 
     // Instantiate Codegen
     let cg = Codegen::new();
@@ -244,7 +244,7 @@ fn test_jump_table_exhaustive_usage() {
     assert!(cg.artifact.is_none());
 
     // Have the Codegen create the constructor bytecode
-    let mbytes = Codegen::generate_main_bytecode(&contract).unwrap();
+    let mbytes = Codegen::generate_main_bytecode(&contract, &env).unwrap();
     assert_eq!(mbytes, String::from("608061004060003960003560e01c8063a9059cbb14610019575b60208703516202ffe016806020015b60206020015b60206020015b60206020015b60206020010000000000000000000000000000000000000000000000000000000000000028000000000000000000000000000000000000000000000000000000000000002e0000000000000000000000000000000000000000000000000000000000000034000000000000000000000000000000000000000000000000000000000000003a"));
 }
 
@@ -292,10 +292,10 @@ fn test_jump_table_packed_exhaustive_usage() {
     let mut parser = Parser::new(tokens, None);
 
     // Parse the AST
-    let mut contract = parser.parse().unwrap();
+    let contract = parser.parse().unwrap();
 
-    // Derive storage pointers
-    contract.derive_storage_pointers();
+    // Resolve the constants
+    let env = ConstantEnv::new(&contract, None).unwrap();
 
     // Instantiate Codegen
     let cg = Codegen::new();
@@ -304,7 +304,7 @@ fn test_jump_table_packed_exhaustive_usage() {
     assert!(cg.artifact.is_none());
 
     // Have the Codegen create the main macro bytecode
-    let mbytes = Codegen::generate_main_bytecode(&contract).unwrap();
+    let mbytes = Codegen::generate_main_bytecode(&contract, &env).unwrap();
     assert_eq!(mbytes, String::from("600861004060003960003560e01c8063a9059cbb14610019575b60208703516202ffe016806020015b60206020015b60206020015b60206020015b60206020010028002e0034003a"));
 }
 
@@ -359,10 +359,10 @@ fn test_label_clashing() {
     let mut parser = Parser::new(tokens, None);
 
     // Parse the AST
-    let mut contract = parser.parse().unwrap();
+    let contract = parser.parse().unwrap();
 
-    // Derive storage pointers
-    contract.derive_storage_pointers();
+    // Resolve the constants
+    let env = ConstantEnv::new(&contract, None).unwrap();
 
     // Instantiate Codegen
     let cg = Codegen::new();
@@ -371,7 +371,7 @@ fn test_label_clashing() {
     assert!(cg.artifact.is_none());
 
     // Have the Codegen create the main macro bytecode
-    let mbytes = Codegen::generate_main_bytecode(&contract).unwrap();
+    let mbytes = Codegen::generate_main_bytecode(&contract, &env).unwrap();
     assert_eq!(mbytes, String::from("6008610048600039608061005060003960003560e01c8063a9059cbb14610021575b60208703516202ffe016806020015b60206020015b60206020015b60206020015b602060200100300036003c004200000000000000000000000000000000000000000000000000000000000000300000000000000000000000000000000000000000000000000000000000000036000000000000000000000000000000000000000000000000000000000000003c0000000000000000000000000000000000000000000000000000000000000042"));
 }
 
@@ -403,10 +403,10 @@ fn test_func_sig_builtin() {
     let mut parser = Parser::new(tokens, None);
 
     // Parse the AST
-    let mut contract = parser.parse().unwrap();
+    let contract = parser.parse().unwrap();
 
-    // Derive storage pointers
-    contract.derive_storage_pointers();
+    // Resolve the constants
+    let env = ConstantEnv::new(&contract, None).unwrap();
 
     // Instantiate Codegen
     let cg = Codegen::new();
@@ -415,7 +415,7 @@ fn test_func_sig_builtin() {
     assert!(cg.artifact.is_none());
 
     // Have the Codegen create the constructor bytecode
-    let cbytes = Codegen::generate_main_bytecode(&contract).unwrap();
+    let cbytes = Codegen::generate_main_bytecode(&contract, &env).unwrap();
     // `transfer(address,uint256) signature = 0xa9059cbb
     assert_eq!(&cbytes[16..24], "a9059cbb");
     assert_eq!(&cbytes[38..46], "a9059cbb");
@@ -448,10 +448,10 @@ fn test_event_hash_builtin() {
     let mut parser = Parser::new(tokens, None);
 
     // Parse the AST
-    let mut contract = parser.parse().unwrap();
+    let contract = parser.parse().unwrap();
 
-    // Derive storage pointers
-    contract.derive_storage_pointers();
+    // Resolve the constants
+    let env = ConstantEnv::new(&contract, None).unwrap();
 
     // Instantiate Codegen
     let cg = Codegen::new();
@@ -460,7 +460,7 @@ fn test_event_hash_builtin() {
     assert!(cg.artifact.is_none());
 
     // Have the Codegen create the constructor bytecode
-    let cbytes = Codegen::generate_main_bytecode(&contract).unwrap();
+    let cbytes = Codegen::generate_main_bytecode(&contract, &env).unwrap();
     // `transfer(address,address,uint256) signature =
     // 0xbeabacc8ffedac16e9a60acdb2ca743d80c2ebb44977a93fa8e483c74d2b35a8
     assert_eq!(&cbytes[2..66], "beabacc8ffedac16e9a60acdb2ca743d80c2ebb44977a93fa8e483c74d2b35a8");
@@ -523,10 +523,10 @@ fn test_error_selector_builtin() {
     let mut parser = Parser::new(tokens, None);
 
     // Parse the AST
-    let mut contract = parser.parse().unwrap();
+    let contract = parser.parse().unwrap();
 
-    // Derive storage pointers
-    contract.derive_storage_pointers();
+    // Resolve the constants
+    let env = ConstantEnv::new(&contract, None).unwrap();
 
     // Instantiate Codegen
     let cg = Codegen::new();
@@ -535,7 +535,7 @@ fn test_error_selector_builtin() {
     assert!(cg.artifact.is_none());
 
     // Have Codegen create the runtime bytecode
-    let r_bytes = Codegen::generate_main_bytecode(&contract).unwrap();
+    let r_bytes = Codegen::generate_main_bytecode(&contract, &env).unwrap();
     assert_eq!(&r_bytes[2..66], "be20788c00000000000000000000000000000000000000000000000000000000");
     assert_eq!(
         &r_bytes[98..162],
@@ -566,10 +566,10 @@ fn test_rightpad_builtin() {
     let mut parser = Parser::new(tokens, None);
 
     // Parse the AST
-    let mut contract = parser.parse().unwrap();
+    let contract = parser.parse().unwrap();
 
-    // Derive storage pointers
-    contract.derive_storage_pointers();
+    // Resolve the constants
+    let env = ConstantEnv::new(&contract, None).unwrap();
 
     // Instantiate Codegen
     let cg = Codegen::new();
@@ -578,7 +578,7 @@ fn test_rightpad_builtin() {
     assert!(cg.artifact.is_none());
 
     // Have Codegen create the runtime bytecode
-    let r_bytes = Codegen::generate_main_bytecode(&contract).unwrap();
+    let r_bytes = Codegen::generate_main_bytecode(&contract, &env).unwrap();
     assert_eq!(&r_bytes[2..66], "a57b000000000000000000000000000000000000000000000000000000000000");
     assert_eq!(
         &r_bytes[68..132],
@@ -38,14 +38,22 @@ fn test_storage_pointers_not_derived() {
     let mut parser = Parser::new(tokens, Some("".to_string()));
     let contract = parser.parse().unwrap();
 
+    // An environment built without deriving the free pointers
+    let env = ConstantEnv {
+        constants: contract.constants.iter().map(|c| (c.name.clone(), c.clone())).collect(),
+        ..Default::default()
+    };
+
     // Create main and constructor bytecode
-    match Codegen::generate_main_bytecode(&contract) {
+    match Codegen::generate_main_bytecode(&contract, &env) {
         Ok(_) => panic!("moose"),
         Err(e) => {
             assert_eq!(
                 e,
                 CodegenError {
-                    kind: CodegenErrorKind::StoragePointersNotDerived,
+                    kind: CodegenErrorKind::StoragePointerNotDerived(
+                        "TOTAL_SUPPLY_LOCATION".to_string()
+                    ),
                     span: AstSpan(vec![
                         Span { start: 5, end: 12, file: None },
                         Span { start: 13, end: 21, file: None },
@@ -53,13 +61,52 @@ fn test_storage_pointers_not_derived() {
                         Span { start: 44, end: 45, file: None },
                         Span { start: 46, end: 68, file: None }
                     ]),
-                    token: None
+                    token: None,
+                    backtrace: Box::new(vec![ExpansionFrame {
+                        macro_name: "MINT".to_string(),
+                        invoked_from: "MAIN".to_string(),
+                        span: AstSpan(vec![
+                            Span { start: 747, end: 751, file: None },
+                            Span { start: 751, end: 752, file: None },
+                            Span { start: 752, end: 753, file: None }
+                        ])
+                    }])
                 }
             )
         }
     }
 }
 
+#[test]
+fn test_storage_pointer_passed_as_argument_not_derived() {
+    let source = r#"
+    #define constant BALANCE_LOCATION = FREE_STORAGE_POINTER()
+
+    #define macro LOAD(slot) = takes(0) returns (1) {
+        <slot> sload
+    }
+
+    #define macro MAIN() = takes(0) returns (0) {
+        LOAD(BALANCE_LOCATION)
+    }
+  "#;
+
+    let full_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(full_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+    let contract = parser.parse().unwrap();
+
+    // Pointers only passed as arguments aren't reached deriving storage pointers
+    let env = ConstantEnv::new(&contract, None).unwrap();
+    let err = Codegen::generate_main_bytecode(&contract, &env).unwrap_err();
+    assert_eq!(
+        err.kind,
+        CodegenErrorKind::StoragePointerNotDerived("BALANCE_LOCATION".to_string())
+    );
+    assert_eq!(err.span, contract.constants[0].span);
+}
+
 #[test]
 fn test_invalid_constant_definition() {
     let source = r#"
@@ -95,11 +142,11 @@ fn test_invalid_constant_definition() {
     let lexer = Lexer::new(full_source);
     let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
     let mut parser = Parser::new(tokens, Some("".to_string()));
-    let mut contract = parser.parse().unwrap();
-    contract.derive_storage_pointers();
+    let contract = parser.parse().unwrap();
+    let env = ConstantEnv::new(&contract, None).unwrap();
 
     // Create main and constructor bytecode
-    match Codegen::generate_main_bytecode(&contract) {
+    match Codegen::generate_main_bytecode(&contract, &env) {
         Ok(_) => panic!("moose"),
         Err(e) => {
             assert_eq!(
@@ -109,7 +156,16 @@ fn test_invalid_constant_definition() {
                         "UNKNOWN_CONSTANT_DEFINITION".to_string()
                     ),
                     span: AstSpan(vec![Span { start: const_start, end: const_end, file: None }]),
-                    token: None
+                    token: None,
+                    backtrace: Box::new(vec![ExpansionFrame {
+                        macro_name: "MINT".to_string(),
+                        invoked_from: "MAIN".to_string(),
+                        span: AstSpan(vec![
+                            Span { start: 792, end: 796, file: None },
+                            Span { start: 796, end: 797, file: None },
+                            Span { start: 797, end: 798, file: None }
+                        ])
+                    }])
                 }
             )
         }
@@ -138,11 +194,11 @@ fn test_missing_constructor() {
     let lexer = Lexer::new(full_source);
     let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
     let mut parser = Parser::new(tokens, Some("".to_string()));
-    let mut contract = parser.parse().unwrap();
-    contract.derive_storage_pointers();
+    let contract = parser.parse().unwrap();
+    let env = ConstantEnv::new(&contract, None).unwrap();
 
     // Create constructor bytecode
-    match Codegen::generate_constructor_bytecode(&contract) {
+    match Codegen::generate_constructor_bytecode(&contract, &env) {
         Ok(_) => panic!("moose"),
         Err(e) => {
             assert_eq!(
@@ -150,7 +206,8 @@ fn test_missing_constructor() {
                 CodegenError {
                     kind: CodegenErrorKind::MissingMacroDefinition("CONSTRUCTOR".to_string()),
                     span: AstSpan(vec![Span { start: 0, end: 0, file: None }]),
-                    token: None
+                    token: None,
+                    backtrace: Box::default()
                 }
             )
         }
@@ -171,11 +228,11 @@ fn test_missing_main() {
     let lexer = Lexer::new(full_source);
     let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
     let mut parser = Parser::new(tokens, Some("".to_string()));
-    let mut contract = parser.parse().unwrap();
-    contract.derive_storage_pointers();
+    let contract = parser.parse().unwrap();
+    let env = ConstantEnv::new(&contract, None).unwrap();
 
     // Createconstructor bytecode
-    match Codegen::generate_main_bytecode(&contract) {
+    match Codegen::generate_main_bytecode(&contract, &env) {
         Ok(_) => panic!("moose"),
         Err(e) => {
             assert_eq!(
@@ -183,7 +240,8 @@ fn test_missing_main() {
                 CodegenError {
                     kind: CodegenErrorKind::MissingMacroDefinition("MAIN".to_string()),
                     span: AstSpan(vec![Span { start: 0, end: 0, file: None }]),
-                    token: None
+                    token: None,
+                    backtrace: Box::default()
                 }
             )
         }
@@ -212,11 +270,11 @@ fn test_unknown_macro_definition() {
     let lexer = Lexer::new(full_source);
     let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
     let mut parser = Parser::new(tokens, Some("".to_string()));
-    let mut contract = parser.parse().unwrap();
-    contract.derive_storage_pointers();
+    let contract = parser.parse().unwrap();
+    let env = ConstantEnv::new(&contract, None).unwrap();
 
     // Create main and constructor bytecode
-    match Codegen::generate_main_bytecode(&contract) {
+    match Codegen::generate_main_bytecode(&contract, &env) {
         Ok(_) => panic!("moose"),
         Err(e) => {
             assert_eq!(
@@ -228,7 +286,8 @@ fn test_unknown_macro_definition() {
                         Span { start: 351, end: 352, file: None },
                         Span { start: 352, end: 353, file: None }
                     ]),
-                    token: None
+                    token: None,
+                    backtrace: Box::default()
                 }
             )
         }
@@ -259,11 +318,11 @@ fn test_unmatched_jump_label() {
     let lexer = Lexer::new(full_source);
     let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
     let mut parser = Parser::new(tokens, Some("".to_string()));
-    let mut contract = parser.parse().unwrap();
-    contract.derive_storage_pointers();
+    let contract = parser.parse().unwrap();
+    let env = ConstantEnv::new(&contract, None).unwrap();
 
     // Create main and constructor bytecode
-    match Codegen::generate_main_bytecode(&contract) {
+    match Codegen::generate_main_bytecode(&contract, &env) {
         Ok(_) => panic!("moose"),
         Err(e) => {
             assert_eq!(
@@ -276,9 +335,59 @@ fn test_unmatched_jump_label() {
                         Span { start: 377, end: 380, file: None },
                         Span { start: 380, end: 381, file: None }
                     ]),
-                    token: None
+                    token: None,
+                    backtrace: Box::default()
                 }
             )
         }
     }
 }
+
+#[test]
+fn test_nested_error_backtrace() {
+    let source = r#"
+#define macro INNER() = takes(0) returns (0) {
+    [MISSING]
+}
+
+#define macro OUTER() = takes(0) returns (0) {
+    INNER()
+}
+
+#define macro MAIN() = takes(0) returns (0) {
+    OUTER()
+}
+"#;
+
+    let file = std::sync::Arc::new(FileSource {
+        path: "./src/Nested.huff".to_string(),
+        source: Some(source.to_string()),
+        ..Default::default()
+    });
+    let full_source = FullFileSource {
+        source,
+        file: Some(file.clone()),
+        spans: vec![(file, Span { start: 0, end: source.len() + 1, file: None })],
+    };
+    let lexer = Lexer::new(full_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, Some("".to_string()));
+    let contract = parser.parse().unwrap();
+    let env = ConstantEnv::new(&contract, None).unwrap();
+
+    match Codegen::generate_main_bytecode(&contract, &env) {
+        Ok(_) => panic!("moose"),
+        Err(e) => {
+            assert_eq!(
+                e.backtrace
+                    .iter()
+                    .map(|f| (f.macro_name.as_str(), f.invoked_from.as_str()))
+                    .collect::<Vec<_>>(),
+                vec![("INNER", "OUTER"), ("OUTER", "MAIN")]
+            );
+            assert!(CompilerError::CodegenError(e).to_string().ends_with(
+                "  in expansion of INNER, invoked from OUTER at ./src/Nested.huff:7\n  in expansion of OUTER, invoked from MAIN at ./src/Nested.huff:11\n"
+            ));
+        }
+    }
+}
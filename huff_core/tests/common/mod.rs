@@ -0,0 +1,38 @@
+//! Helpers shared by the integration tests. Each test file only uses some of them.
+#![allow(dead_code)]
+
+use std::sync::Arc;
+
+use huff_core::{session::Session, Compiler};
+use huff_lexer::Lexer;
+use huff_parser::Parser;
+use huff_utils::prelude::*;
+
+/// Parses a contract from source, and resolves the signatures of its definitions
+pub fn parse(source: &str) -> Contract {
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+    let mut contract = parser.parse().unwrap();
+    contract.resolve_signatures().unwrap();
+    contract
+}
+
+/// An in-memory file, with its resolved dependencies
+pub fn file(path: &str, source: &str, dependencies: Vec<Arc<FileSource>>) -> Arc<FileSource> {
+    Arc::new(FileSource {
+        source: Some(source.to_string()),
+        id: uuid::Uuid::new_v4(),
+        path: path.to_string(),
+        access: None,
+        dependencies: Some(dependencies),
+    })
+}
+
+/// Compiles the artifact of a file in its own session
+pub fn artifact(main: Arc<FileSource>) -> Artifact {
+    let session = Session::new(&[Arc::clone(&main)]).unwrap();
+    let compiler = Compiler::new(Arc::new(vec![]), None, None, None, false, false);
+    compiler.gen_session_artifact(&session, main).unwrap()
+}
@@ -45,9 +45,9 @@ fn compiles_constructor_bytecode() {
     let mut parser = Parser::new(tokens, None);
 
     // Grab the first macro
-    let mut contract = parser.parse().unwrap();
-    // Derive storage pointers
-    contract.derive_storage_pointers();
+    let contract = parser.parse().unwrap();
+    // Resolve the constants
+    let env = ConstantEnv::new(&contract, None).unwrap();
 
     // Instantiate Codegen
     let cg = Codegen::new();
@@ -56,7 +56,7 @@ fn compiles_constructor_bytecode() {
     assert!(cg.artifact.is_none());
 
     // Have the Codegen create the constructor bytecode
-    let cbytes = Codegen::generate_constructor_bytecode(&contract).unwrap();
+    let cbytes = Codegen::generate_constructor_bytecode(&contract, &env).unwrap();
     println!("Constructor Bytecode Result: {:?}", cbytes);
     assert_eq!(cbytes, String::from("33600055"));
 }
@@ -70,9 +70,9 @@ fn compiles_runtime_bytecode() {
     let mut parser = Parser::new(tokens, None);
 
     // Grab the first macro
-    let mut contract = parser.parse().unwrap();
-    // Derive storage pointers
-    contract.derive_storage_pointers();
+    let contract = parser.parse().unwrap();
+    // Resolve the constants
+    let env = ConstantEnv::new(&contract, None).unwrap();
 
     // Instantiate Codegen
     let mut cg = Codegen::new();
@@ -81,7 +81,7 @@ fn compiles_runtime_bytecode() {
     assert!(cg.artifact.is_none());
 
     // Have the Codegen create the constructor bytecode
-    let cbytes = Codegen::generate_constructor_bytecode(&contract).unwrap();
+    let cbytes = Codegen::generate_constructor_bytecode(&contract, &env).unwrap();
     assert_eq!(cbytes, String::from("33600055"));
 
     // assert!(false);
@@ -0,0 +1,43 @@
+use huff_core::create2::*;
+use huff_utils::prelude::*;
+
+#[test]
+fn test_search_salt() {
+    let deployer = [0u8; 20];
+    let mut init_code_hash = [0u8; 32];
+    hash_raw_bytes(&mut init_code_hash, &[0x00]);
+
+    let prefix = [0x0, 0x0];
+    let found = search_salt(&deployer, &init_code_hash, &prefix, None, |_, _| {});
+
+    assert_eq!(found.matched, 2);
+    assert_eq!(found.address, create2_address(&deployer, &found.salt, &init_code_hash));
+    assert_eq!(found.address[0], 0x00);
+}
+
+#[test]
+fn test_search_salt_max_attempts() {
+    let deployer = [0u8; 20];
+    let init_code_hash = [0u8; 32];
+
+    // A full zero address won't be found in a handful of attempts
+    let prefix = [0u8; 40];
+    let found = search_salt(&deployer, &init_code_hash, &prefix, Some(16), |tried, _| {
+        assert_eq!(tried, 16)
+    });
+
+    assert!(found.matched < 40);
+    assert_eq!(found.address, create2_address(&deployer, &found.salt, &init_code_hash));
+    assert_eq!(found.matched, matched_nibbles(&found.address, &prefix));
+}
+
+#[test]
+fn test_matched_nibbles() {
+    let mut address = [0xffu8; 20];
+    address[0] = 0x00;
+    address[1] = 0x0a;
+
+    assert_eq!(matched_nibbles(&address, &[0x0, 0x0, 0x0, 0xa]), 4);
+    assert_eq!(matched_nibbles(&address, &[0x0, 0x0, 0x0, 0xb]), 3);
+    assert_eq!(matched_nibbles(&address, &[0x1]), 0);
+}
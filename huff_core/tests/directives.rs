@@ -0,0 +1,179 @@
+mod common;
+
+use common::parse;
+use huff_codegen::Codegen;
+use huff_utils::prelude::*;
+use std::collections::BTreeMap;
+
+#[test]
+fn test_repeat_macro() {
+    let contract = parse(
+        r#"
+    #define constant COUNT = 0x02
+
+    #define macro STORE(i) = takes (1) returns (1) {
+        dup1 <i> sstore
+    }
+
+    #define macro POP() = takes (1) returns (0) {
+        pop
+    }
+
+    #define macro MAIN() = takes (0) returns (0) {
+        0x01
+        __REPEAT(0x03, STORE)
+        __REPEAT(COUNT, POP)
+    }
+    "#,
+    );
+    let env = ConstantEnv::new(&contract, None).unwrap();
+
+    assert_eq!(
+        Codegen::generate_main_bytecode(&contract, &env).unwrap(),
+        "60018060005580600155806002555050"
+    );
+}
+
+#[test]
+fn test_repeat_macro_with_labels() {
+    let contract = parse(
+        r#"
+    #define macro SKIP() = takes (0) returns (0) {
+        skip jump
+        skip:
+    }
+
+    #define macro MAIN() = takes (0) returns (0) {
+        __REPEAT(0x02, SKIP)
+    }
+    "#,
+    );
+    let env = ConstantEnv::new(&contract, None).unwrap();
+
+    assert_eq!(Codegen::generate_main_bytecode(&contract, &env).unwrap(), "610004565b610009565b");
+}
+
+#[test]
+fn test_repeat_errors() {
+    let source = r#"
+    #define macro PAIR(a, b) = takes (0) returns (0) {
+        <a> <b>
+    }
+
+    #define macro MAIN() = takes (0) returns (0) {
+        __REPEAT(0x02, PAIR)
+    }
+    "#;
+    let contract = parse(source);
+    let env = ConstantEnv::new(&contract, None).unwrap();
+    match Codegen::generate_main_bytecode(&contract, &env) {
+        Ok(_) => panic!("moose"),
+        Err(e) => {
+            assert_eq!(e.kind, CodegenErrorKind::MacroArityMismatch("PAIR".to_string(), 2, 1))
+        }
+    }
+
+    let source = r#"
+    #define macro MAIN() = takes (0) returns (0) {
+        __REPEAT(0x02, MISSING)
+    }
+    "#;
+    let contract = parse(source);
+    let env = ConstantEnv::new(&contract, None).unwrap();
+    match Codegen::generate_main_bytecode(&contract, &env) {
+        Ok(_) => panic!("moose"),
+        Err(e) => {
+            assert_eq!(e.kind, CodegenErrorKind::InvalidMacroInvocation("MISSING".to_string()))
+        }
+    }
+}
+
+#[test]
+fn test_repeat_size_limit() {
+    let source = r#"
+    #define macro POP() = takes (1) returns (0) {
+        pop
+    }
+
+    #define macro STORE(i) = takes (1) returns (1) {
+        dup1 <i> sstore
+    }
+
+    #define macro MAIN() = takes (0) returns (0) {
+        __REPEAT(REPEATS)
+    }
+    "#;
+    let repeat = |args: &str| {
+        let contract = parse(&source.replace("REPEATS", args));
+        let env = ConstantEnv::new(&contract, None).unwrap();
+        Codegen::generate_main_bytecode(&contract, &env)
+    };
+
+    // Repeating a single byte up to the limit fills the contract size limit exactly
+    assert_eq!(repeat("0x6000, POP").unwrap(), "50".repeat(0x6000));
+
+    // Counts past the limit are rejected before unrolling
+    match repeat("0xffffffff, POP") {
+        Ok(_) => panic!("moose"),
+        Err(e) => assert_eq!(e.kind, CodegenErrorKind::RepeatTooLarge("POP".to_string())),
+    }
+
+    // Each repetition of STORE is at least 4 bytes, so 0x2000 of them exceed the limit
+    match repeat("0x2000, STORE") {
+        Ok(_) => panic!("moose"),
+        Err(e) => assert_eq!(e.kind, CodegenErrorKind::RepeatTooLarge("STORE".to_string())),
+    }
+}
+
+const CONDITIONAL: &str = r#"
+    #define constant DEBUG = 0x00
+
+    #define macro MAIN() = takes (0) returns (0) {
+        0x01
+        #if DEBUG
+            0x00 0x00 log0
+        #else
+            pop
+        #endif
+        done:
+            #if DEBUG
+                0x02
+            #endif
+            stop
+    }
+"#;
+
+#[test]
+fn test_conditional_compilation() {
+    let contract = parse(CONDITIONAL);
+    let env = ConstantEnv::new(&contract, None).unwrap();
+    assert_eq!(Codegen::generate_main_bytecode(&contract, &env).unwrap(), "6001505b00");
+}
+
+#[test]
+fn test_conditional_compilation_with_override() {
+    let contract = parse(CONDITIONAL);
+    let overrides = BTreeMap::from([("DEBUG", str_to_bytes32("01"))]);
+    let env = ConstantEnv::new(&contract, Some(&overrides)).unwrap();
+    assert_eq!(Codegen::generate_main_bytecode(&contract, &env).unwrap(), "600160006000a05b600200");
+}
+
+#[test]
+fn test_conditional_missing_constant() {
+    let contract = parse(
+        r#"
+    #define macro MAIN() = takes (0) returns (0) {
+        #if MISSING
+            stop
+        #endif
+    }
+    "#,
+    );
+    let env = ConstantEnv::new(&contract, None).unwrap();
+    match Codegen::generate_main_bytecode(&contract, &env) {
+        Ok(_) => panic!("moose"),
+        Err(e) => {
+            assert_eq!(e.kind, CodegenErrorKind::MissingConstantDefinition("MISSING".to_string()))
+        }
+    }
+}
@@ -28,12 +28,12 @@ fn test_erc20_compile() {
     let lexer = Lexer::new(full_source);
     let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
     let mut parser = Parser::new(tokens, Some("../huff-examples/erc20/contracts".to_string()));
-    let mut contract = parser.parse().unwrap();
-    contract.derive_storage_pointers();
+    let contract = parser.parse().unwrap();
+    let env = ConstantEnv::new(&contract, None).unwrap();
 
     // Create main and constructor bytecode
-    let main_bytecode = Codegen::generate_main_bytecode(&contract).unwrap();
-    let constructor_bytecode = Codegen::generate_constructor_bytecode(&contract).unwrap();
+    let main_bytecode = Codegen::generate_main_bytecode(&contract, &env).unwrap();
+    let constructor_bytecode = Codegen::generate_constructor_bytecode(&contract, &env).unwrap();
 
     // Churn
     let mut cg = Codegen::new();
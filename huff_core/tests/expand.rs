@@ -0,0 +1,135 @@
+mod common;
+
+use common::parse;
+use huff_codegen::Codegen;
+use huff_utils::prelude::*;
+
+#[test]
+fn test_expand_macro() {
+    let contract = parse(
+        r#"
+    #define function transfer(address,uint256) nonpayable returns ()
+    #define constant SLOT = FREE_STORAGE_POINTER()
+
+    #define macro REQUIRE(err) = takes (1) returns (0) {
+        ok jumpi
+        <err> 0x00 mstore
+        0x04 0x1c revert
+        ok:
+    }
+
+    #define macro CHECK(slot, dest) = takes (0) returns (0) {
+        <slot> sload
+        REQUIRE(0x01)
+        <dest> jump
+    }
+
+    #define macro MAIN() = takes (0) returns (0) {
+        __FUNC_SIG(transfer) pop
+        CHECK(SLOT, done)
+        CHECK(SLOT, done)
+        done:
+            stop
+    }
+    "#,
+    );
+    // SLOT is only passed as an argument, which isn't traversed deriving storage pointers
+    let env = ConstantEnv::with_allocation(
+        &contract,
+        None,
+        Allocation { storage_order: StorageOrder::Definition, ..Default::default() },
+    )
+    .unwrap();
+
+    assert_eq!(
+        Codegen::expand_macro("MAIN", &contract, &env).unwrap(),
+        r#"#define macro MAIN() = takes (0) returns (0) {
+    0xa9059cbb // __FUNC_SIG(transfer)
+    pop
+    // CHECK(SLOT, done) {
+        0x00 // <slot>
+        sload
+        // REQUIRE(0x01) {
+            ok_2
+            jumpi
+            0x01 // <err>
+            0x00
+            mstore
+            0x04
+            0x1c
+            revert
+            ok_2:
+        // }
+        done // <dest>
+        jump
+    // }
+    // CHECK(SLOT, done) {
+        0x00 // <slot>
+        sload
+        // REQUIRE(0x01) {
+            ok_4
+            jumpi
+            0x01 // <err>
+            0x00
+            mstore
+            0x04
+            0x1c
+            revert
+            ok_4:
+        // }
+        done // <dest>
+        jump
+    // }
+    done:
+        stop
+}
+"#
+    );
+}
+
+#[test]
+fn test_expand_macro_with_parameters() {
+    let contract = parse(
+        r#"
+    #define macro INNER(value) = takes (0) returns (0) {
+        <value> pop
+    }
+
+    #define macro OUTER(value) = takes (0) returns (0) {
+        INNER(<value>)
+        tablestart:
+    }
+    "#,
+    );
+    let env = ConstantEnv::new(&contract, None).unwrap();
+
+    assert_eq!(
+        Codegen::expand_macro("OUTER", &contract, &env).unwrap(),
+        r#"#define macro OUTER(value) = takes (0) returns (0) {
+    // INNER(<value>) {
+        <value>
+        pop
+    // }
+    tablestart:
+}
+"#
+    );
+}
+
+#[test]
+fn test_expand_missing_macro() {
+    let contract = parse("#define macro MAIN() = takes (0) returns (0) { MISSING() }");
+    let env = ConstantEnv::new(&contract, None).unwrap();
+
+    let err = Codegen::expand_macro("MAIN", &contract, &env).unwrap_err();
+    assert_eq!(err.kind, CodegenErrorKind::InvalidMacroInvocation("MISSING".to_string()));
+}
+
+#[test]
+fn test_expand_reports_bytecode_errors() {
+    let contract = parse("#define macro MAIN() = takes (0) returns (0) { __tablestart(MISSING) }");
+    let env = ConstantEnv::new(&contract, None).unwrap();
+
+    let err = Codegen::expand_macro("MAIN", &contract, &env).unwrap_err();
+    assert_eq!(err.kind, CodegenErrorKind::InvalidMacroInvocation("MISSING".to_string()));
+}
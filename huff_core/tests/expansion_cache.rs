@@ -0,0 +1,153 @@
+mod common;
+
+use common::parse;
+use huff_codegen::Codegen;
+use huff_utils::prelude::*;
+use std::collections::{BTreeMap, HashMap};
+
+#[test]
+fn test_symbols_are_indexed() {
+    let contract = parse(
+        r#"
+    #define macro A() = takes (0) returns (0) {}
+    #define macro B() = takes (0) returns (0) {}
+    #define table T { 0x00 }
+    "#,
+    );
+
+    assert_eq!(
+        contract.symbols,
+        SymbolIndex {
+            macros: HashMap::from([("A".to_string(), 0), ("B".to_string(), 1)]),
+            tables: HashMap::from([("T".to_string(), 0)]),
+        }
+    );
+    assert_eq!(contract.find_macro_by_name("B").unwrap().name, "B");
+    assert!(contract.find_macro_by_name("C").is_none());
+    assert!(contract.find_table_by_name("T").is_some());
+}
+
+#[test]
+fn test_cached_expansion_is_relocated() {
+    let source = r#"
+    #define macro STORE(slot) = takes (1) returns (0) {
+        <slot> sstore
+    }
+
+    #define macro MAIN() = takes (0) returns (0) {
+        0x01 STORE(0x00)
+        0x02 STORE(0x00)
+        done jump
+        done:
+        0x03 STORE(0x00)
+    }
+    "#;
+    let contract = parse(source);
+    let env = ConstantEnv::new(&contract, None).unwrap();
+
+    let (bytecode, source_map) =
+        Codegen::generate_main_bytecode_with_source_map(&contract, &env).unwrap();
+    assert_eq!(bytecode, "6001600055600260005561000e565b6003600055");
+
+    let stores = source_map
+        .iter()
+        .filter(|e| e.name == "STORE")
+        .map(|e| (e.offset, e.length))
+        .collect::<Vec<_>>();
+    assert_eq!(stores, vec![(2, 2), (4, 1), (7, 2), (9, 1), (17, 2), (19, 1)]);
+
+    let expansions = env.expansions.lock().unwrap();
+    assert_eq!(expansions.invocations.len(), 1);
+    assert!(expansions
+        .invocations
+        .contains_key(&("STORE".to_string(), vec![MacroArg::Literal(str_to_bytes32("00"))])));
+}
+
+#[test]
+fn test_expansions_with_labels_are_not_cached() {
+    let source = r#"
+    #define macro SKIP() = takes (0) returns (0) {
+        skip jump
+        skip:
+    }
+
+    #define macro MAIN() = takes (0) returns (0) {
+        SKIP() SKIP()
+    }
+    "#;
+    let contract = parse(source);
+    let env = ConstantEnv::new(&contract, None).unwrap();
+
+    assert_eq!(Codegen::generate_main_bytecode(&contract, &env).unwrap(), "610004565b610009565b");
+    assert!(env.expansions.lock().unwrap().invocations.is_empty());
+}
+
+#[test]
+fn test_codesize_is_cached() {
+    let source = r#"
+    #define macro BODY() = takes (0) returns (0) {
+        0x01 0x02 add
+    }
+
+    #define macro MAIN() = takes (0) returns (0) {
+        __codesize(BODY) __codesize(BODY)
+    }
+    "#;
+    let contract = parse(source);
+    let env = ConstantEnv::new(&contract, None).unwrap();
+
+    assert_eq!(Codegen::generate_main_bytecode(&contract, &env).unwrap(), "60056005");
+    assert_eq!(
+        env.expansions.lock().unwrap().codesizes.get(&("BODY".to_string(), true)).map(|c| c.0),
+        Some(5)
+    );
+}
+
+#[test]
+fn test_cached_expansion_recursion() {
+    // The expansion of `INNER` is cached before `OUTER` invokes it recursively
+    let source = r#"
+    #define macro INNER() = takes (0) returns (0) {
+        0x01 LEAF()
+    }
+
+    #define macro LEAF() = takes (0) returns (0) {
+        0x02
+    }
+
+    #define macro OUTER() = takes (0) returns (0) {
+        INNER()
+    }
+
+    #define macro MAIN() = takes (0) returns (0) {
+        INNER() OUTER()
+    }
+    "#;
+    let contract = parse(source);
+    let env = ConstantEnv::new(&contract, None).unwrap();
+
+    assert_eq!(Codegen::generate_main_bytecode(&contract, &env).unwrap(), "6001600260016002");
+}
+
+#[test]
+fn test_environments_have_separate_caches() {
+    let source = r#"
+    #define constant VALUE = 0x01
+
+    #define macro PUSH() = takes (0) returns (1) {
+        [VALUE]
+    }
+
+    #define macro MAIN() = takes (0) returns (0) {
+        PUSH()
+    }
+    "#;
+    let contract = parse(source);
+    let env = ConstantEnv::new(&contract, None).unwrap();
+    let overrides = BTreeMap::from([("VALUE", str_to_bytes32("02"))]);
+    let overridden = ConstantEnv::new(&contract, Some(&overrides)).unwrap();
+
+    assert_eq!(Codegen::generate_main_bytecode(&contract, &env).unwrap(), "6001");
+    assert_eq!(Codegen::generate_main_bytecode(&contract, &overridden).unwrap(), "6002");
+    assert_eq!(Codegen::generate_main_bytecode(&contract, &env).unwrap(), "6001");
+}
@@ -19,8 +19,7 @@ fn test_abi_uint_events() {
     let lexer = Lexer::new(flattened_source);
     let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
     let mut parser = Parser::new(tokens, None);
-    let mut contract = parser.parse().unwrap();
-    contract.derive_storage_pointers();
+    let contract = parser.parse().unwrap();
 
     // let main_bytecode = Codegen::generate_main_bytecode(&contract).unwrap();
     // let constructor_bytecode = Codegen::generate_constructor_bytecode(&contract).unwrap();
@@ -33,7 +32,7 @@ fn test_abi_uint_events() {
     assert_eq!(
         abi.events,
         BTreeMap::from([(
-            "UintEvents".to_string(),
+            "UintEvents(uint256,uint8,uint16,uint32,uint64,uint128,uint256)".to_string(),
             huff_utils::abi::Event {
                 name: "UintEvents".to_string(),
                 inputs: vec![
@@ -94,14 +93,13 @@ fn test_abi_int_events() {
     let lexer = Lexer::new(flattened_source);
     let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
     let mut parser = Parser::new(tokens, None);
-    let mut contract = parser.parse().unwrap();
-    contract.derive_storage_pointers();
+    let contract = parser.parse().unwrap();
 
     let abi: Abi = contract.into();
     assert_eq!(
         abi.events,
         BTreeMap::from([(
-            "IntEvents".to_string(),
+            "IntEvents(int256,int8,int16,int32,int64,int128,int256)".to_string(),
             huff_utils::abi::Event {
                 name: "IntEvents".to_string(),
                 inputs: vec![
@@ -162,14 +160,13 @@ fn test_abi_simple_events() {
     let lexer = Lexer::new(flattened_source);
     let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
     let mut parser = Parser::new(tokens, None);
-    let mut contract = parser.parse().unwrap();
-    contract.derive_storage_pointers();
+    let contract = parser.parse().unwrap();
 
     let abi: Abi = contract.into();
     assert_eq!(
         abi.events,
         BTreeMap::from([(
-            "SimpleEvent".to_string(),
+            "SimpleEvent(address,address,uint256,string,uint256)".to_string(),
             huff_utils::abi::Event {
                 name: "SimpleEvent".to_string(),
                 inputs: vec![
@@ -205,7 +202,6 @@ fn test_abi_simple_events() {
     );
 }
 
-#[ignore]
 #[test]
 fn test_abi_tuple_array_events() {
     let source: &str = r#"
@@ -221,40 +217,41 @@ fn test_abi_tuple_array_events() {
     let lexer = Lexer::new(flattened_source);
     let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
     let mut parser = Parser::new(tokens, None);
-    let mut contract = parser.parse().unwrap();
-    contract.derive_storage_pointers();
+    let contract = parser.parse().unwrap();
 
     let abi: Abi = contract.into();
     assert_eq!(
         abi.events,
         BTreeMap::from([(
-            "SimpleEvent".to_string(),
+            "TupleArrays(address[],(address,uint256),(uint256,bool)[])".to_string(),
             huff_utils::abi::Event {
-                name: "SimpleEvent".to_string(),
+                name: "TupleArrays".to_string(),
                 inputs: vec![
                     EventParam {
                         name: "".to_string(),
-                        kind: FunctionParamType::Address,
-                        indexed: false
-                    },
-                    EventParam {
-                        name: "".to_string(),
-                        kind: FunctionParamType::Address,
+                        kind: FunctionParamType::Array(
+                            Box::new(FunctionParamType::Address),
+                            vec![0]
+                        ),
                         indexed: false
                     },
                     EventParam {
                         name: "".to_string(),
-                        kind: FunctionParamType::Uint(256),
+                        kind: FunctionParamType::Tuple(vec![
+                            FunctionParamType::Address,
+                            FunctionParamType::Uint(256)
+                        ]),
                         indexed: false
                     },
                     EventParam {
                         name: "".to_string(),
-                        kind: FunctionParamType::String,
-                        indexed: false
-                    },
-                    EventParam {
-                        name: "".to_string(),
-                        kind: FunctionParamType::Uint(256),
+                        kind: FunctionParamType::Array(
+                            Box::new(FunctionParamType::Tuple(vec![
+                                FunctionParamType::Uint(256),
+                                FunctionParamType::Bool
+                            ])),
+                            vec![0]
+                        ),
                         indexed: false
                     }
                 ],
@@ -264,7 +261,6 @@ fn test_abi_tuple_array_events() {
     );
 }
 
-#[ignore]
 #[test]
 fn test_abi_nested_tuple_array_events() {
     let source: &str = r#"
@@ -280,41 +276,44 @@ fn test_abi_nested_tuple_array_events() {
     let lexer = Lexer::new(flattened_source);
     let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
     let mut parser = Parser::new(tokens, None);
-    let mut contract = parser.parse().unwrap();
-    contract.derive_storage_pointers();
+    let contract = parser.parse().unwrap();
 
     let abi: Abi = contract.into();
     assert_eq!(
         abi.events,
         BTreeMap::from([(
-            "SimpleEvent".to_string(),
+            "NestedTupleArrays((address,(bool,bytes))[][],bytes,bytes32,string)".to_string(),
             huff_utils::abi::Event {
-                name: "SimpleEvent".to_string(),
+                name: "NestedTupleArrays".to_string(),
                 inputs: vec![
                     EventParam {
                         name: "".to_string(),
-                        kind: FunctionParamType::Address,
+                        kind: FunctionParamType::Array(
+                            Box::new(FunctionParamType::Tuple(vec![
+                                FunctionParamType::Address,
+                                FunctionParamType::Tuple(vec![
+                                    FunctionParamType::Bool,
+                                    FunctionParamType::Bytes
+                                ])
+                            ])),
+                            vec![0, 0]
+                        ),
                         indexed: false
                     },
                     EventParam {
                         name: "".to_string(),
-                        kind: FunctionParamType::Address,
+                        kind: FunctionParamType::Bytes,
                         indexed: false
                     },
                     EventParam {
                         name: "".to_string(),
-                        kind: FunctionParamType::Uint(256),
+                        kind: FunctionParamType::FixedBytes(32),
                         indexed: false
                     },
                     EventParam {
                         name: "".to_string(),
                         kind: FunctionParamType::String,
                         indexed: false
-                    },
-                    EventParam {
-                        name: "".to_string(),
-                        kind: FunctionParamType::Uint(256),
-                        indexed: false
                     }
                 ],
                 anonymous: false
@@ -1,7 +1,7 @@
 use huff_codegen::Codegen;
 use huff_lexer::Lexer;
 use huff_parser::Parser;
-use huff_utils::prelude::{FileSource, FullFileSource, Token};
+use huff_utils::prelude::{ConstantEnv, FileSource, FullFileSource, Token};
 use std::sync::Arc;
 
 #[test]
@@ -98,10 +98,10 @@ fn test_function() {
     let mut parser = Parser::new(tokens, None);
 
     // Parse the AST
-    let mut contract = parser.parse().unwrap();
+    let contract = parser.parse().unwrap();
 
-    // Derive storage pointers
-    contract.derive_storage_pointers();
+    // Resolve the constants
+    let env = ConstantEnv::new(&contract, None).unwrap();
 
     // Instantiate Codegen
     let cg = Codegen::new();
@@ -110,7 +110,7 @@ fn test_function() {
     assert!(cg.artifact.is_none());
 
     // Have the Codegen create the runtime bytecode
-    let rbytes = Codegen::generate_main_bytecode(&contract).unwrap();
+    let rbytes = Codegen::generate_main_bytecode(&contract, &env).unwrap();
     // Churn
     let mut cg = Codegen::new();
     let artifact =
@@ -192,10 +192,10 @@ fn test_nested_function() {
     let mut parser = Parser::new(tokens, None);
 
     // Parse the AST
-    let mut contract = parser.parse().unwrap();
+    let contract = parser.parse().unwrap();
 
-    // Derive storage pointers
-    contract.derive_storage_pointers();
+    // Resolve the constants
+    let env = ConstantEnv::new(&contract, None).unwrap();
 
     // Instantiate Codegen
     let cg = Codegen::new();
@@ -204,7 +204,7 @@ fn test_nested_function() {
     assert!(cg.artifact.is_none());
 
     // Have the Codegen create the runtime bytecode
-    let rbytes = Codegen::generate_main_bytecode(&contract).unwrap();
+    let rbytes = Codegen::generate_main_bytecode(&contract, &env).unwrap();
     // Churn
     let mut cg = Codegen::new();
     let artifact =
@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{collections::BTreeMap, sync::Arc};
 
 use huff_core::Compiler;
 use huff_utils::prelude::*;
@@ -89,9 +89,49 @@ fn test_missing_constructor_with_inputs() {
                 CompilerError::CodegenError(CodegenError {
                     kind: CodegenErrorKind::MissingMacroDefinition("CONSTRUCTOR".to_string()),
                     span: AstSpan(vec![Span { start: 0, end: 0, file: Some(arc_source) }]),
-                    token: None
+                    token: None,
+                    backtrace: Box::default()
                 })
             )
         }
     }
 }
+
+#[test]
+fn test_artifact_variants() {
+    let source = r#"
+    #define constant OWNER = FREE_STORAGE_POINTER()
+    #define constant FEE = 0x01
+
+    #define macro MAIN() = takes(0) returns (0) {
+        [FEE] [OWNER] sstore
+    }
+    "#;
+
+    // Full source
+    let full_source = FileSource {
+        source: Some(source.to_string()),
+        id: uuid::Uuid::new_v4(),
+        path: "".to_string(),
+        access: None,
+        dependencies: None,
+    };
+
+    // Instantiate a new compiler
+    let compiler = Compiler::new(Arc::new(vec![]), None, None, None, false, false);
+
+    // Generate one artifact per set of constant overrides
+    let variants = vec![
+        BTreeMap::new(),
+        BTreeMap::from([("FEE", str_to_bytes32("02"))]),
+        BTreeMap::from([("FEE", str_to_bytes32("03")), ("OWNER", str_to_bytes32("05"))]),
+    ];
+    let artifacts = compiler.gen_artifact_variants(Arc::new(full_source), &variants).unwrap();
+    assert_eq!(
+        artifacts.iter().map(|a| a.runtime.as_str()).collect::<Vec<_>>(),
+        vec!["6001600055", "6002600055", "6003600555"]
+    );
+
+    // The storage layout is derived from the definitions, regardless of the overrides
+    assert!(artifacts.iter().all(|a| a.storage_layout == artifacts[0].storage_layout));
+}
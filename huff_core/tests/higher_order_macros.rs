@@ -0,0 +1,118 @@
+mod common;
+
+use huff_codegen::Codegen;
+use huff_utils::prelude::*;
+
+const MACROS: &str = r#"
+    #define macro DOUBLE() = takes (1) returns (1) {
+        dup1 add
+    }
+
+    #define macro ADD_N(n) = takes (1) returns (1) {
+        <n> add
+    }
+
+    #define macro TWICE(body) = takes (1) returns (1) {
+        <body>()
+        <body>()
+    }
+
+    #define macro APPLY(f, n) = takes (1) returns (1) {
+        <f>(<n>)
+    }
+
+    #define macro WRAP(g) = takes (1) returns (1) {
+        APPLY(<g>, 0x02)
+    }
+
+    #define macro LOOP(body) = takes (0) returns (0) {
+        loop:
+            <body>(<body>)
+            loop jump
+    }
+"#;
+
+fn parse(main: &str) -> Contract {
+    common::parse(&format!(
+        "{}\n    #define macro MAIN() = takes (0) returns (0) {{ {} }}\n",
+        MACROS, main
+    ))
+}
+
+#[test]
+fn test_macro_passed_as_argument() {
+    let contract = parse("0x01 TWICE(DOUBLE)");
+    let env = ConstantEnv::new(&contract, None).unwrap();
+    assert_eq!(Codegen::generate_main_bytecode(&contract, &env).unwrap(), "600180018001");
+}
+
+#[test]
+fn test_macro_argument_with_args_bubbles_up() {
+    let contract = parse("0x01 WRAP(ADD_N)");
+    let env = ConstantEnv::new(&contract, None).unwrap();
+    assert_eq!(Codegen::generate_main_bytecode(&contract, &env).unwrap(), "6001600201");
+}
+
+#[test]
+fn test_expand_macro_argument() {
+    let contract = parse("0x01 TWICE(DOUBLE)");
+    let env = ConstantEnv::new(&contract, None).unwrap();
+    assert_eq!(
+        Codegen::expand_macro("MAIN", &contract, &env).unwrap(),
+        r#"#define macro MAIN() = takes (0) returns (0) {
+    0x01
+    // TWICE(DOUBLE) {
+        // DOUBLE() {
+            dup1
+            add
+        // }
+        // DOUBLE() {
+            dup1
+            add
+        // }
+    // }
+}
+"#
+    );
+}
+
+#[test]
+fn test_macro_argument_errors() {
+    for (main, kind) in [
+        ("0x01 TWICE(ADD_N)", CodegenErrorKind::MacroArityMismatch("ADD_N".to_string(), 1, 0)),
+        ("0x01 TWICE()", CodegenErrorKind::MacroArityMismatch("TWICE".to_string(), 1, 0)),
+        ("0x01 ADD_N(0x01, 0x02)", CodegenErrorKind::MacroArityMismatch("ADD_N".to_string(), 1, 2)),
+        ("0x01 TWICE(0x01)", CodegenErrorKind::InvalidMacroArgument("0x01".to_string())),
+        ("0x01 TWICE(MISSING)", CodegenErrorKind::InvalidMacroArgument("MISSING".to_string())),
+        (
+            "LOOP(LOOP)",
+            CodegenErrorKind::RecursiveMacroInvocation(vec![
+                "MAIN".to_string(),
+                "LOOP".to_string(),
+                "LOOP".to_string(),
+            ]),
+        ),
+    ] {
+        let contract = parse(main);
+        let env = ConstantEnv::new(&contract, None).unwrap();
+        match Codegen::generate_main_bytecode(&contract, &env) {
+            Ok(_) => panic!("moose"),
+            Err(e) => assert_eq!(e.kind, kind),
+        }
+    }
+}
+
+#[test]
+fn test_missing_macro_argument() {
+    let contract = parse("0x01 <body>()");
+    let env = ConstantEnv::new(&contract, None).unwrap();
+    match Codegen::generate_main_bytecode(&contract, &env) {
+        Ok(_) => panic!("moose"),
+        Err(e) => {
+            assert_eq!(e.kind, CodegenErrorKind::MissingMacroArgument("body".to_string()));
+            let start =
+                MACROS.len() + "\n    #define macro MAIN() = takes (0) returns (0) { 0x01 ".len();
+            assert_eq!(e.span.0[0], Span { start: start + 1, end: start + 5, file: None });
+        }
+    }
+}
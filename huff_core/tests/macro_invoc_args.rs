@@ -22,11 +22,11 @@ fn test_opcode_macro_args() {
     let lexer = Lexer::new(flattened_source);
     let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
     let mut parser = Parser::new(tokens, None);
-    let mut contract = parser.parse().unwrap();
-    contract.derive_storage_pointers();
+    let contract = parser.parse().unwrap();
+    let env = ConstantEnv::new(&contract, None).unwrap();
 
     // Create main and constructor bytecode
-    let main_bytecode = Codegen::generate_main_bytecode(&contract).unwrap();
+    let main_bytecode = Codegen::generate_main_bytecode(&contract, &env).unwrap();
 
     // Full expected bytecode output (generated from huffc) (placed here as a reference)
     let expected_bytecode = "60088060093d393df360ff3d5260203df3";
@@ -59,11 +59,11 @@ fn test_all_opcodes_in_macro_args() {
         let lexer = Lexer::new(flattened_source);
         let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
         let mut parser = Parser::new(tokens, None);
-        let mut contract = parser.parse().unwrap();
-        contract.derive_storage_pointers();
+        let contract = parser.parse().unwrap();
+        let env = ConstantEnv::new(&contract, None).unwrap();
 
         // Create main and constructor bytecode
-        let main_bytecode = Codegen::generate_main_bytecode(&contract).unwrap();
+        let main_bytecode = Codegen::generate_main_bytecode(&contract, &env).unwrap();
 
         // Full expected bytecode output (generated from huffc) (placed here as a reference)
         let expected_bytecode = format!("60088060093d393df360ff{}", Opcode::from_str(o).unwrap());
@@ -95,11 +95,11 @@ fn test_constant_macro_arg() {
     let lexer = Lexer::new(flattened_source);
     let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
     let mut parser = Parser::new(tokens, None);
-    let mut contract = parser.parse().unwrap();
-    contract.derive_storage_pointers();
+    let contract = parser.parse().unwrap();
+    let env = ConstantEnv::new(&contract, None).unwrap();
 
     // Create main and constructor bytecode
-    let main_bytecode = Codegen::generate_main_bytecode(&contract).unwrap();
+    let main_bytecode = Codegen::generate_main_bytecode(&contract, &env).unwrap();
 
     // Full expected bytecode output (generated from huffc) (placed here as a reference)
     let expected_bytecode = "60088060093d393df360ff6002";
@@ -133,11 +133,11 @@ fn test_bubbled_label_call_macro_arg() {
     let lexer = Lexer::new(flattened_source);
     let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
     let mut parser = Parser::new(tokens, None);
-    let mut contract = parser.parse().unwrap();
-    contract.derive_storage_pointers();
+    let contract = parser.parse().unwrap();
+    let env = ConstantEnv::new(&contract, None).unwrap();
 
     // Create main and constructor bytecode
-    let main_bytecode = Codegen::generate_main_bytecode(&contract).unwrap();
+    let main_bytecode = Codegen::generate_main_bytecode(&contract, &env).unwrap();
 
     // Full expected bytecode output (generated from huffc) (placed here as a reference)
     let expected_bytecode = "60088060093d393df360ff5b610000";
@@ -170,11 +170,11 @@ fn test_bubbled_literal_macro_arg() {
     let lexer = Lexer::new(flattened_source);
     let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
     let mut parser = Parser::new(tokens, None);
-    let mut contract = parser.parse().unwrap();
-    contract.derive_storage_pointers();
+    let contract = parser.parse().unwrap();
+    let env = ConstantEnv::new(&contract, None).unwrap();
 
     // Create main and constructor bytecode
-    let main_bytecode = Codegen::generate_main_bytecode(&contract).unwrap();
+    let main_bytecode = Codegen::generate_main_bytecode(&contract, &env).unwrap();
 
     // Full expected bytecode output (generated from huffc) (placed here as a reference)
     let expected_bytecode = "60088060093d393df360ff610420";
@@ -207,11 +207,11 @@ fn test_bubbled_opcode_macro_arg() {
     let lexer = Lexer::new(flattened_source);
     let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
     let mut parser = Parser::new(tokens, None);
-    let mut contract = parser.parse().unwrap();
-    contract.derive_storage_pointers();
+    let contract = parser.parse().unwrap();
+    let env = ConstantEnv::new(&contract, None).unwrap();
 
     // Create main and constructor bytecode
-    let main_bytecode = Codegen::generate_main_bytecode(&contract).unwrap();
+    let main_bytecode = Codegen::generate_main_bytecode(&contract, &env).unwrap();
 
     // Full expected bytecode output (generated from huffc) (placed here as a reference)
     let expected_bytecode = "60088060093d393df360ff3d";
@@ -246,11 +246,11 @@ fn test_bubbled_constant_macro_arg() {
     let lexer = Lexer::new(flattened_source);
     let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
     let mut parser = Parser::new(tokens, None);
-    let mut contract = parser.parse().unwrap();
-    contract.derive_storage_pointers();
+    let contract = parser.parse().unwrap();
+    let env = ConstantEnv::new(&contract, None).unwrap();
 
     // Create main and constructor bytecode
-    let main_bytecode = Codegen::generate_main_bytecode(&contract).unwrap();
+    let main_bytecode = Codegen::generate_main_bytecode(&contract, &env).unwrap();
 
     // Full expected bytecode output (generated from huffc) (placed here as a reference)
     let expected_bytecode = "60088060093d393df360ff6002";
@@ -0,0 +1,140 @@
+mod common;
+
+use std::sync::Arc;
+
+use common::parse;
+use huff_codegen::*;
+use huff_utils::prelude::*;
+
+const SAFE_TRANSFER_FROM: &str = r#"
+    #define function safeTransferFrom(address,address,uint256) nonpayable returns ()
+    #define function safeTransferFrom(address,address,uint256,bytes) nonpayable returns ()
+
+    #define event Approval(address indexed,address indexed,uint256)
+    #define event Approval(address indexed,uint256)
+"#;
+
+#[test]
+fn test_abi_keeps_overloads() {
+    let abi: Abi = parse(SAFE_TRANSFER_FROM).into();
+
+    assert_eq!(
+        abi.functions.keys().collect::<Vec<_>>(),
+        vec![
+            "safeTransferFrom(address,address,uint256)",
+            "safeTransferFrom(address,address,uint256,bytes)"
+        ]
+    );
+    assert_eq!(
+        abi.functions["safeTransferFrom(address,address,uint256)"].selector(),
+        [0x42, 0x84, 0x2e, 0x0e]
+    );
+    assert_eq!(
+        abi.functions["safeTransferFrom(address,address,uint256,bytes)"].selector(),
+        [0xb8, 0x8d, 0x4f, 0xde]
+    );
+    assert_eq!(abi.events.len(), 2);
+}
+
+#[test]
+fn test_func_sig_resolves_overloads_by_signature() {
+    let source = format!(
+        r#"{}
+        #define macro MAIN() = takes (0) returns (0) {{
+            __FUNC_SIG("safeTransferFrom(address,address,uint256)")
+            __FUNC_SIG("safeTransferFrom(address, address, uint256, bytes)")
+        }}
+        "#,
+        SAFE_TRANSFER_FROM
+    );
+    let contract = parse(&source);
+    let env = ConstantEnv::new(&contract, None).unwrap();
+
+    let main_bytecode = Codegen::generate_main_bytecode(&contract, &env).unwrap();
+    assert_eq!(main_bytecode, "6342842e0e63b88d4fde");
+}
+
+#[test]
+fn test_func_sig_ambiguous_overload() {
+    let source = format!(
+        r#"{}
+        #define macro MAIN() = takes (0) returns (0) {{
+            __FUNC_SIG(safeTransferFrom)
+        }}
+        "#,
+        SAFE_TRANSFER_FROM
+    );
+    let contract = parse(&source);
+    let env = ConstantEnv::new(&contract, None).unwrap();
+
+    match Codegen::generate_main_bytecode(&contract, &env) {
+        Err(e) => assert_eq!(
+            e.kind,
+            CodegenErrorKind::AmbiguousOverload(vec![
+                "safeTransferFrom(address,address,uint256)".to_string(),
+                "safeTransferFrom(address,address,uint256,bytes)".to_string()
+            ])
+        ),
+        Ok(_) => panic!("moose"),
+    }
+}
+
+#[test]
+fn test_event_hash_resolves_overloads_by_signature() {
+    let source = format!(
+        r#"{}
+        #define macro MAIN() = takes (0) returns (0) {{
+            __EVENT_HASH("Approval(address,address,uint256)")
+        }}
+        "#,
+        SAFE_TRANSFER_FROM
+    );
+    let contract = parse(&source);
+    let env = ConstantEnv::new(&contract, None).unwrap();
+
+    let main_bytecode = Codegen::generate_main_bytecode(&contract, &env).unwrap();
+    assert_eq!(main_bytecode, "7f8c5be1e5ebec7d5bd14f71427d1e84f3dd0314c0f7b2291e5b200ac8c7c3b925");
+}
+
+#[test]
+fn test_redefinitions_are_not_overloads() {
+    let source = r#"
+        #define function transfer(address,uint256) nonpayable returns ()
+        #define function transfer(address,uint256) nonpayable returns ()
+
+        #define macro MAIN() = takes (0) returns (0) {
+            __FUNC_SIG(transfer)
+        }
+    "#;
+    let contract = parse(source);
+    let env = ConstantEnv::new(&contract, None).unwrap();
+
+    let main_bytecode = Codegen::generate_main_bytecode(&contract, &env).unwrap();
+    assert_eq!(main_bytecode, "63a9059cbb");
+}
+
+#[test]
+fn test_interface_renders_overloads() {
+    let artifact = Artifact {
+        file: Arc::new(FileSource {
+            path: "./contracts/ERC721.huff".to_string(),
+            ..Default::default()
+        }),
+        abi: Some(parse(SAFE_TRANSFER_FROM).into()),
+        ..Default::default()
+    };
+
+    let interfaces = gen_sol_interfaces(&vec![Arc::new(artifact)], None, None, None);
+    assert_eq!(
+        interfaces[0].2,
+        "// SPDX-License-Identifier: UNLICENSED\n\
+        pragma solidity ^0.8.4;\n\
+        \n\
+        interface IERC721 {\n\
+        \tevent Approval(address indexed, address indexed, uint256);\n\
+        \tevent Approval(address indexed, uint256);\n\
+        \tfunction safeTransferFrom(address, address, uint256) external;\n\
+        \tfunction safeTransferFrom(address, address, uint256, bytes calldata) external;\n\
+        }\n"
+    );
+}
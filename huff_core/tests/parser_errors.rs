@@ -95,7 +95,7 @@ fn test_invalid_definition() {
                 ParserError {
                     kind: ParserErrorKind::InvalidDefinition(TokenKind::Ident("invalid".to_string())),
                     hint: Some(
-                        "Definition must be one of: `function`, `event`, `constant`, `error`, `macro`, `fn`, or `test`."
+                        "Definition must be one of: `function`, `event`, `constant`, `error`, `struct`, `macro`, `fn`, or `test`."
                             .to_string()
                     ),
                     spans: AstSpan(vec![Span {
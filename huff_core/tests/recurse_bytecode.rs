@@ -49,8 +49,8 @@ fn recurse_macro_bytecode() {
     let lexer = Lexer::new(flattened_source);
     let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
     let mut parser = Parser::new(tokens, None);
-    let mut contract = parser.parse().unwrap();
-    contract.derive_storage_pointers();
+    let contract = parser.parse().unwrap();
+    let env = ConstantEnv::new(&contract, None).unwrap();
 
     // let contract = Contract {
     //     macros: vec![
@@ -151,8 +151,8 @@ fn recurse_macro_bytecode() {
     // assert_eq!(contract, ast);
 
     // Create main and constructor bytecode
-    let main_bytecode = Codegen::generate_main_bytecode(&contract).unwrap();
-    let constructor_bytecode = Codegen::generate_constructor_bytecode(&contract).unwrap();
+    let main_bytecode = Codegen::generate_main_bytecode(&contract, &env).unwrap();
+    let constructor_bytecode = Codegen::generate_constructor_bytecode(&contract, &env).unwrap();
 
     // Full expected bytecode output (generated from huffc) (placed here as a reference)
     let expected_bytecode = "61003f8061000d6000396000f360003560E01c8063a9059cbb1461001c57806340c10f191461002e575b60043533602435600160005260206000f35b60043560006024358060005401600055";
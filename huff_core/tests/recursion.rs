@@ -0,0 +1,206 @@
+mod common;
+
+use common::parse;
+use huff_codegen::Codegen;
+use huff_utils::prelude::*;
+
+/// The span of an identifier's first occurrence after the given pattern
+fn span_of(source: &str, after: &str, ident: &str) -> Span {
+    let start = source.find(after).unwrap() + after.find(ident).unwrap();
+    Span { start, end: start + ident.len(), file: None }
+}
+
+#[test]
+fn test_direct_recursion() {
+    let source = r#"
+    #define macro LOOP() = takes (0) returns (0) {
+        0x01 LOOP()
+    }
+
+    #define macro MAIN() = takes (0) returns (0) {
+        LOOP()
+    }
+    "#;
+
+    let contract = parse(source);
+    let env = ConstantEnv::new(&contract, None).unwrap();
+    match Codegen::generate_main_bytecode(&contract, &env) {
+        Ok(_) => panic!("moose"),
+        Err(e) => {
+            assert_eq!(
+                e.kind,
+                CodegenErrorKind::RecursiveMacroInvocation(vec![
+                    "MAIN".to_string(),
+                    "LOOP".to_string(),
+                    "LOOP".to_string(),
+                ])
+            );
+            assert!(e.span.0.contains(&span_of(source, "{\n        LOOP()", "LOOP")));
+            assert!(e.span.0.contains(&span_of(source, "0x01 LOOP()", "LOOP")));
+        }
+    }
+}
+
+#[test]
+fn test_cyclic_recursion() {
+    let source = r#"
+    #define macro PING() = takes (0) returns (0) {
+        PONG()
+    }
+
+    #define macro PONG() = takes (0) returns (0) {
+        PING()
+    }
+
+    #define macro MAIN() = takes (0) returns (0) {
+        0x00 PING()
+    }
+    "#;
+
+    let contract = parse(source);
+    let env = ConstantEnv::new(&contract, None).unwrap();
+    match Codegen::generate_main_bytecode(&contract, &env) {
+        Ok(_) => panic!("moose"),
+        Err(e) => {
+            assert_eq!(
+                e.kind,
+                CodegenErrorKind::RecursiveMacroInvocation(vec![
+                    "MAIN".to_string(),
+                    "PING".to_string(),
+                    "PONG".to_string(),
+                    "PING".to_string(),
+                ])
+            );
+            assert!(e.span.0.contains(&span_of(source, "0x00 PING()", "PING")));
+            assert!(e.span.0.contains(&span_of(source, "PONG()\n", "PONG")));
+            assert!(e.span.0.contains(&span_of(source, "PING()\n", "PING")));
+            assert!(CompilerError::CodegenError(e)
+                .to_string()
+                .contains("Recursive Invocation of Macro \"PING\": MAIN -> PING -> PONG -> PING"));
+        }
+    }
+}
+
+#[test]
+fn test_root_recursion() {
+    let source = r#"
+    #define macro MAIN() = takes (0) returns (0) {
+        MAIN()
+    }
+    "#;
+
+    let contract = parse(source);
+    let env = ConstantEnv::new(&contract, None).unwrap();
+    match Codegen::generate_main_bytecode(&contract, &env) {
+        Ok(_) => panic!("moose"),
+        Err(e) => assert_eq!(
+            e.kind,
+            CodegenErrorKind::RecursiveMacroInvocation(vec![
+                "MAIN".to_string(),
+                "MAIN".to_string(),
+            ])
+        ),
+    }
+}
+
+#[test]
+fn test_codesize_recursion() {
+    let source = r#"
+    #define macro SIZED() = takes (0) returns (0) {
+        __codesize(SIZED)
+    }
+
+    #define macro MAIN() = takes (0) returns (0) {
+        SIZED()
+    }
+    "#;
+
+    let contract = parse(source);
+    let env = ConstantEnv::new(&contract, None).unwrap();
+    match Codegen::generate_main_bytecode(&contract, &env) {
+        Ok(_) => panic!("moose"),
+        Err(e) => assert_eq!(
+            e.kind,
+            CodegenErrorKind::RecursiveMacroInvocation(vec![
+                "MAIN".to_string(),
+                "SIZED".to_string(),
+                "SIZED".to_string(),
+            ])
+        ),
+    }
+}
+
+#[test]
+fn test_macro_argument_recursion() {
+    let source = r#"
+    #define macro CALL(body) = takes (0) returns (0) {
+        <body>()
+    }
+
+    #define macro SELF() = takes (0) returns (0) {
+        CALL(SELF)
+    }
+
+    #define macro MAIN() = takes (0) returns (0) {
+        SELF()
+    }
+    "#;
+
+    let contract = parse(source);
+    let env = ConstantEnv::new(&contract, None).unwrap();
+    match Codegen::generate_main_bytecode(&contract, &env) {
+        Ok(_) => panic!("moose"),
+        Err(e) => assert_eq!(
+            e.kind,
+            CodegenErrorKind::RecursiveMacroInvocation(vec![
+                "MAIN".to_string(),
+                "SELF".to_string(),
+                "CALL".to_string(),
+                "SELF".to_string(),
+            ])
+        ),
+    }
+}
+
+#[test]
+fn test_repeated_invocations_are_not_recursive() {
+    let source = r#"
+    #define macro ONE() = takes (0) returns (1) {
+        0x01
+    }
+
+    #define macro TWO() = takes (0) returns (2) {
+        ONE() ONE()
+    }
+
+    #define macro MAIN() = takes (0) returns (0) {
+        TWO() ONE() __codesize(TWO)
+    }
+    "#;
+
+    let contract = parse(source);
+    let env = ConstantEnv::new(&contract, None).unwrap();
+    assert_eq!(Codegen::generate_main_bytecode(&contract, &env).unwrap(), "6001600160016004");
+}
+
+#[test]
+fn test_expand_recursion() {
+    let source = r#"
+    #define macro LOOP() = takes (0) returns (0) {
+        0x01 LOOP()
+    }
+    "#;
+
+    let contract = parse(source);
+    let env = ConstantEnv::new(&contract, None).unwrap();
+    match Codegen::expand_macro("LOOP", &contract, &env) {
+        Ok(_) => panic!("moose"),
+        Err(e) => assert_eq!(
+            e.kind,
+            CodegenErrorKind::RecursiveMacroInvocation(vec![
+                "LOOP".to_string(),
+                "LOOP".to_string(),
+            ])
+        ),
+    }
+}
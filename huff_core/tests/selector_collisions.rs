@@ -0,0 +1,111 @@
+mod common;
+
+use common::parse;
+use huff_codegen::*;
+use huff_utils::prelude::*;
+
+#[test]
+fn test_function_selector_collision() {
+    // Both signatures hash to 0x42966c68
+    let source = r#"
+    #define function burn(uint256) nonpayable returns ()
+    #define function collate_propagate_storage(bytes16) nonpayable returns ()
+    "#;
+    let contract = parse(source);
+
+    match Codegen::check_selector_collisions(&contract) {
+        Ok(_) => panic!("moose"),
+        Err(e) => {
+            assert_eq!(
+                e,
+                CodegenError {
+                    kind: CodegenErrorKind::SelectorCollision(
+                        vec!["burn".to_string(), "collate_propagate_storage".to_string()],
+                        [0x42, 0x96, 0x6c, 0x68]
+                    ),
+                    span: AstSpan(
+                        contract.functions[0]
+                            .span
+                            .0
+                            .iter()
+                            .chain(contract.functions[1].span.0.iter())
+                            .cloned()
+                            .collect()
+                    ),
+                    token: None,
+                    backtrace: Box::default()
+                }
+            )
+        }
+    }
+}
+
+#[test]
+fn test_error_selector_collision() {
+    let source = r#"
+    #define error burn(uint256)
+    #define error collate_propagate_storage(bytes16)
+    "#;
+    let contract = parse(source);
+
+    match Codegen::check_selector_collisions(&contract) {
+        Ok(_) => panic!("moose"),
+        Err(e) => {
+            assert_eq!(
+                e.kind,
+                CodegenErrorKind::SelectorCollision(
+                    vec!["burn".to_string(), "collate_propagate_storage".to_string()],
+                    [0x42, 0x96, 0x6c, 0x68]
+                )
+            )
+        }
+    }
+}
+
+#[test]
+fn test_no_selector_collision() {
+    let source = r#"
+    #define function burn(uint256) nonpayable returns ()
+    #define function transfer(address,uint256) nonpayable returns ()
+    #define error burn(uint256)
+    "#;
+    let contract = parse(source);
+
+    assert!(Codegen::check_selector_collisions(&contract).is_ok());
+}
+
+#[test]
+fn test_external_abi_selector_collision() {
+    let implementation = parse(
+        r#"
+    #define function burn(uint256) nonpayable returns ()
+    "#,
+    );
+    let abi: Abi = implementation.into();
+    assert_eq!(abi.functions.get("burn(uint256)").unwrap().selector(), [0x42, 0x96, 0x6c, 0x68]);
+
+    let proxy = parse(
+        r#"
+    #define function upgradeTo(address) nonpayable returns ()
+    #define function collate_propagate_storage(bytes16) nonpayable returns ()
+    "#,
+    );
+
+    match Codegen::check_abi_selector_collisions(&proxy, &abi) {
+        Ok(_) => panic!("moose"),
+        Err(e) => {
+            assert_eq!(
+                e,
+                CodegenError {
+                    kind: CodegenErrorKind::SelectorCollision(
+                        vec!["collate_propagate_storage".to_string(), "burn".to_string()],
+                        [0x42, 0x96, 0x6c, 0x68]
+                    ),
+                    span: proxy.functions[1].span.clone(),
+                    token: None,
+                    backtrace: Box::default()
+                }
+            )
+        }
+    }
+}
@@ -0,0 +1,67 @@
+mod common;
+
+use std::collections::BTreeMap;
+
+use common::{artifact, file, parse};
+use huff_utils::prelude::*;
+
+const SOURCE: &str = r#"
+    #define struct Permit(address spender, uint256 amount)
+
+    #define function transfer(address,uint256) nonpayable returns (bool)
+    #define function permit(Permit) nonpayable returns ()
+    #define event Transfer(address indexed, address indexed, uint256)
+    #define error Unauthorized(address)
+
+    #define macro MAIN() = takes (0) returns (0) {
+        __FUNC_SIG(transfer)
+    }
+"#;
+
+fn selectors() -> Selectors {
+    Selectors {
+        method_identifiers: BTreeMap::from([
+            ("permit((address,uint256))".to_string(), "921f4bbc".to_string()),
+            ("transfer(address,uint256)".to_string(), "a9059cbb".to_string()),
+        ]),
+        event_topics: BTreeMap::from([(
+            "Transfer(address,address,uint256)".to_string(),
+            "ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef".to_string(),
+        )]),
+        error_selectors: BTreeMap::from([(
+            "Unauthorized(address)".to_string(),
+            "8e4a23d6".to_string(),
+        )]),
+    }
+}
+
+#[test]
+fn test_contract_selectors() {
+    assert_eq!(parse(SOURCE).selectors(), selectors());
+}
+
+#[test]
+fn test_artifact_selectors() {
+    let artifact = artifact(file("./Token.huff", SOURCE, vec![]));
+    assert_eq!(artifact.selectors, selectors());
+
+    // The tables are top level sections of the serialized artifact
+    let json: serde_json::Value = serde_json::to_value(&artifact).unwrap();
+    assert_eq!(json["methodIdentifiers"]["transfer(address,uint256)"], "a9059cbb");
+    assert_eq!(json["errorSelectors"]["Unauthorized(address)"], "8e4a23d6");
+    let imported: Artifact = serde_json::from_value(json).unwrap();
+    assert_eq!(imported.selectors, selectors());
+}
+
+#[test]
+fn test_formats_selectors() {
+    assert_eq!(
+        selectors().to_string(),
+        "Kind      Selector                                                            Signature\n\
+        function  0x921f4bbc                                                          permit((address,uint256))\n\
+        function  0xa9059cbb                                                          transfer(address,uint256)\n\
+        event     0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef  Transfer(address,address,uint256)\n\
+        error     0x8e4a23d6                                                          Unauthorized(address)\n"
+    );
+    assert_eq!(Selectors::default().to_string(), "Kind      Selector  Signature\n");
+}
@@ -0,0 +1,119 @@
+mod common;
+
+use std::sync::Arc;
+
+use common::file;
+use huff_core::{session::Session, Compiler};
+use huff_utils::prelude::*;
+
+fn lib() -> Arc<FileSource> {
+    let source = r#"
+    #define constant OWNER = FREE_STORAGE_POINTER()
+
+    #define macro STORE() = takes (1) returns (0) {
+        [OWNER] sstore
+    }
+    "#;
+    file("./lib.huff", source, vec![])
+}
+
+#[test]
+fn test_shared_dependency_parsed_once() {
+    let lib = lib();
+    let a = file(
+        "./a.huff",
+        r#"
+        #include "./lib.huff"
+        #define macro MAIN() = takes (0) returns (0) { 0x01 STORE() }
+        "#,
+        vec![Arc::clone(&lib)],
+    );
+    let b = file(
+        "./b.huff",
+        r#"
+        #include "./lib.huff"
+        #define macro MAIN() = takes (0) returns (0) { 0x02 STORE() }
+        "#,
+        vec![lib],
+    );
+
+    let session = Session::new(&[Arc::clone(&a), Arc::clone(&b)]).unwrap();
+    assert_eq!(session.len(), 3);
+
+    // The dependency's AST is merged into both entry files
+    let contract = session.contract(&a).unwrap();
+    let macros = contract.macros.iter().map(|m| m.name.as_str()).collect::<Vec<&str>>();
+    assert_eq!(macros, vec!["MAIN", "STORE"]);
+    assert_eq!(contract.constants.len(), 1);
+    assert_eq!(contract.imports.len(), 1);
+
+    // Artifacts are the same as when each file is compiled on its own
+    let compiler = Compiler::new(Arc::new(vec![]), None, None, None, false, false);
+    for entry in [a, b] {
+        let shared = compiler.gen_session_artifact(&session, Arc::clone(&entry)).unwrap();
+        let single = compiler.gen_artifact(entry).unwrap();
+        assert_eq!(shared.runtime, single.runtime);
+        assert_eq!(shared.storage_layout, single.storage_layout);
+    }
+}
+
+#[test]
+fn test_diamond_dependency_included_once() {
+    let lib = lib();
+    let left = file(
+        "./left.huff",
+        r#"#define macro LEFT() = takes (0) returns (0) { 0x01 STORE() }"#,
+        vec![Arc::clone(&lib)],
+    );
+    let right = file(
+        "./right.huff",
+        r#"#define macro RIGHT() = takes (0) returns (0) { 0x02 STORE() }"#,
+        vec![lib],
+    );
+    let main = file(
+        "./main.huff",
+        r#"#define macro MAIN() = takes (0) returns (0) { LEFT() RIGHT() }"#,
+        vec![left, right],
+    );
+
+    let session = Session::new(&[Arc::clone(&main)]).unwrap();
+    assert_eq!(session.len(), 4);
+
+    let contract = session.contract(&main).unwrap();
+    let macros = contract.macros.iter().map(|m| m.name.as_str()).collect::<Vec<&str>>();
+    assert_eq!(macros, vec!["MAIN", "LEFT", "STORE", "RIGHT"]);
+
+    // The shared pointer is only assigned a single slot
+    let compiler = Compiler::new(Arc::new(vec![]), None, None, None, false, false);
+    let artifact = compiler.gen_session_artifact(&session, main).unwrap();
+    assert_eq!(artifact.runtime, "60016000556002600055");
+    assert_eq!(artifact.storage_layout.len(), 1);
+}
+
+#[test]
+fn test_dependency_parser_error() {
+    let broken = file("./broken.huff", "#define macro BROKEN() = takes (0) {", vec![]);
+    let main = file(
+        "./main.huff",
+        r#"#define macro MAIN() = takes (0) returns (0) { 0x01 }"#,
+        vec![broken],
+    );
+
+    match Session::new(&[main]) {
+        Err(CompilerError::ParserError(_)) => {}
+        _ => panic!("moose"),
+    }
+}
+
+#[test]
+fn test_unknown_file() {
+    let session = Session::new(&[lib()]).unwrap();
+    let other = file("./other.huff", "", vec![]);
+    match session.contract(&other) {
+        Err(CompilerError::FileUnpackError(UnpackError::MissingFile(path))) => {
+            assert_eq!(path, "./other.huff")
+        }
+        _ => panic!("moose"),
+    }
+    assert!(session.ast("./lib.huff").is_some());
+}
@@ -0,0 +1,193 @@
+mod common;
+
+use std::sync::Arc;
+
+use common::file;
+use huff_core::{session::Session, Compiler};
+use huff_utils::prelude::*;
+
+const IVAULT: &str = r#"
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.15;
+
+import "./IERC20.sol";
+
+type Shares is uint128;
+
+/// @notice A vault of tokens
+interface IVault is IERC165 {
+    enum Status { Open, Closed }
+
+    /* Declared after its use in `Position` */
+    struct Position {
+        address owner;
+        Shares shares;
+        Lock[] locks;
+    }
+
+    struct Lock {
+        uint64 until;
+        Status status;
+    }
+
+    event Deposit(address indexed owner, IVault.Position position) anonymous;
+
+    error Locked(uint64 until);
+
+    function deposit(address payable to, Position calldata position) external payable returns (Shares);
+    function positionOf(address owner) external view returns (Position memory, bytes32[2] memory);
+    function asset() external pure returns (IVault);
+
+    receive() external payable;
+}
+"#;
+
+#[test]
+fn test_parses_sol_interface() {
+    let abi = parse_sol_interface(IVAULT).unwrap();
+    let position = FunctionParamType::Struct(
+        "Position".to_string(),
+        vec![
+            ("owner".to_string(), FunctionParamType::Address),
+            ("shares".to_string(), FunctionParamType::Uint(128)),
+            (
+                "locks".to_string(),
+                FunctionParamType::Array(
+                    Box::new(FunctionParamType::Struct(
+                        "Lock".to_string(),
+                        vec![
+                            ("until".to_string(), FunctionParamType::Uint(64)),
+                            ("status".to_string(), FunctionParamType::Uint(8)),
+                        ],
+                    )),
+                    vec![0],
+                ),
+            ),
+        ],
+    );
+
+    assert_eq!(
+        abi.functions.keys().cloned().collect::<Vec<String>>(),
+        vec![
+            "asset()".to_string(),
+            "deposit(address,(address,uint128,(uint64,uint8)[]))".to_string(),
+            "positionOf(address)".to_string()
+        ]
+    );
+    let deposit = &abi.functions["deposit(address,(address,uint128,(uint64,uint8)[]))"];
+    assert_eq!(deposit.state_mutability, FunctionType::Payable);
+    assert_eq!(deposit.inputs[1].kind, position);
+    assert_eq!(deposit.outputs[0].kind, FunctionParamType::Uint(128));
+
+    let position_of = &abi.functions["positionOf(address)"];
+    assert_eq!(position_of.state_mutability, FunctionType::View);
+    assert_eq!(
+        position_of.outputs.iter().map(|o| o.kind.clone()).collect::<Vec<FunctionParamType>>(),
+        vec![
+            position,
+            FunctionParamType::Array(Box::new(FunctionParamType::FixedBytes(32)), vec![2])
+        ]
+    );
+    assert_eq!(abi.functions["asset()"].outputs[0].kind, FunctionParamType::Address);
+
+    let deposit = &abi.events["Deposit(address,(address,uint128,(uint64,uint8)[]))"];
+    assert!(deposit.anonymous);
+    assert!(deposit.inputs[0].indexed);
+    assert!(!deposit.inputs[1].indexed);
+    assert_eq!(abi.errors.keys().cloned().collect::<Vec<String>>(), vec!["Locked(uint64)"]);
+    assert!(abi.receive);
+    assert!(!abi.fallback);
+}
+
+#[test]
+fn test_generates_huff_declarations() {
+    let abi = parse_sol_interface(
+        r#"
+        interface IToken {
+            struct Permit { address spender; uint160 amount; }
+            function permit(Permit calldata permit, bytes calldata signature) external;
+            function totalSupply() external view returns (uint256 supply);
+            event Approval(address indexed owner, address indexed spender, uint256 value);
+        }
+        "#,
+    )
+    .unwrap();
+    assert_eq!(
+        gen_huff_interface(&abi),
+        "#define struct Permit(address spender, uint160 amount)\n\
+        \n\
+        #define function permit(Permit permit, bytes signature) nonpayable returns ()\n\
+        #define function totalSupply() view returns (uint256 supply)\n\
+        \n\
+        #define event Approval(address indexed owner, address indexed spender, uint256 value)\n"
+    );
+}
+
+#[test]
+fn test_includes_sol_interface() {
+    let interface = file("./IVault.sol", IVAULT, vec![]);
+    let main = file(
+        "./Vault.huff",
+        r#"
+        #include "./IVault.sol"
+        #define macro MAIN() = takes (0) returns (0) {
+            __FUNC_SIG(asset)
+            __ERROR(Locked)
+        }
+        "#,
+        vec![interface],
+    );
+
+    let session = Session::new(&[Arc::clone(&main)]).unwrap();
+    let contract = session.contract(&main).unwrap();
+    assert_eq!(contract.functions.len(), 3);
+    assert_eq!(contract.events.len(), 1);
+    assert_eq!(contract.structs.len(), 2);
+
+    let compiler = Compiler::new(Arc::new(vec![]), None, None, None, false, false);
+    let artifact = compiler.gen_session_artifact(&session, main).unwrap();
+    assert_eq!(
+        artifact.runtime,
+        "6338d52e0f7feada265800000000000000000000000000000000000000000000000000000000"
+    );
+}
+
+#[test]
+fn test_rejects_implementations() {
+    let errors = vec![
+        ("contract Vault {}", "Unsupported declaration \"contract\", expected an interface (line 1)"),
+        (
+            "interface IVault {\n    function asset() external view returns (address) {}\n}",
+            "Interface functions can't have implementations (line 2)",
+        ),
+        (
+            "interface IVault {\n    function balances(mapping(address => uint256) storage b) external;\n}",
+            "Unsupported parameter type \"mapping\" (line 2)",
+        ),
+        ("interface IVault {\n    function asset() external returns (IERC20);\n}", "Unknown type \"IERC20\" (line 2)"),
+        ("interface IVault {\n    struct Node { Node[] children; }\n    event Tree(Node root);\n}", "Recursive struct \"Node\" (line 2)"),
+    ];
+    for (source, error) in errors {
+        assert_eq!(parse_sol_interface(source).unwrap_err(), error);
+    }
+}
+
+#[test]
+fn test_invalid_sol_interface() {
+    let interface = file("./IVault.sol", "contract Vault {}", vec![]);
+    let main = file("./Vault.huff", r#"#include "./IVault.sol""#, vec![interface]);
+
+    match Session::new(&[main]) {
+        Err(CompilerError::ParserError(e)) => match e.kind {
+            ParserErrorKind::InvalidSolInterface(_) => {}
+            _ => panic!("moose"),
+        },
+        _ => panic!("moose"),
+    }
+}
+
+#[test]
+fn test_transform_sol_path() {
+    let paths = Compiler::transform_paths(&vec!["./IVault.sol".to_string()]).unwrap();
+    assert_eq!(paths, vec![std::path::PathBuf::from("./IVault.sol")]);
+}
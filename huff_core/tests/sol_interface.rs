@@ -0,0 +1,134 @@
+mod common;
+
+use std::sync::Arc;
+
+use common::file;
+use huff_utils::prelude::*;
+
+const VAULT: &str = r#"
+/* Interface */
+
+/// @notice Deposits assets into the vault
+/// @param to The receiver of the shares
+#define function deposit(address to, uint256[] amounts) payable returns (uint256 shares)
+#define function sharesOf(address) view returns (uint256)
+#define function name() pure returns (string)
+
+/**
+ * @notice Emitted on deposits
+ */
+#define event Deposit(address indexed to, uint256 shares)
+
+/** @notice Thrown when the vault is locked */
+#define error Locked(uint64 until)
+
+#define macro MAIN() = takes (0) returns (0) {
+    __FUNC_SIG(deposit)
+}
+"#;
+
+fn artifact(main: Arc<FileSource>) -> Arc<Artifact> {
+    Arc::new(common::artifact(main))
+}
+
+#[test]
+fn test_generates_compilable_interface() {
+    let interfaces = gen_sol_interfaces(
+        &vec![artifact(file("./src/Vault.huff", VAULT, vec![]))],
+        None,
+        Some("MIT".to_string()),
+        Some("^0.8.15".to_string()),
+    );
+    assert_eq!(interfaces[0].1, "IVault");
+    assert_eq!(
+        interfaces[0].2,
+        "// SPDX-License-Identifier: MIT\n\
+        pragma solidity ^0.8.15;\n\
+        \n\
+        interface IVault {\n\
+        \t/// @notice Emitted on deposits\n\
+        \tevent Deposit(address indexed to, uint256 shares);\n\
+        \t/// @notice Thrown when the vault is locked\n\
+        \terror Locked(uint64 until);\n\
+        \t/// @notice Deposits assets into the vault\n\
+        \t/// @param to The receiver of the shares\n\
+        \tfunction deposit(address to, uint256[] calldata amounts) external payable returns (uint256 shares);\n\
+        \tfunction name() external pure returns (string memory);\n\
+        \tfunction sharesOf(address) external view returns (uint256);\n\
+        }\n"
+    );
+}
+
+#[test]
+fn test_carries_docs_from_included_files() {
+    let interface = file(
+        "./src/IToken.huff",
+        "/// @notice The balance of an account\n#define function balanceOf(address) view returns (uint256)",
+        vec![],
+    );
+    let main = file(
+        "./src/Token.huff",
+        r#"
+        #include "./IToken.huff"
+        #define macro MAIN() = takes (0) returns (0) { __FUNC_SIG(balanceOf) }
+        "#,
+        vec![interface],
+    );
+
+    let interfaces = gen_sol_interfaces(&vec![artifact(main)], None, None, None);
+    assert_eq!(
+        interfaces[0].2,
+        "// SPDX-License-Identifier: UNLICENSED\n\
+        pragma solidity ^0.8.4;\n\
+        \n\
+        interface IToken {\n\
+        \t/// @notice The balance of an account\n\
+        \tfunction balanceOf(address) external view returns (uint256);\n\
+        }\n"
+    );
+}
+
+#[test]
+fn test_carries_docs_of_overloads_by_signature() {
+    let source = r#"
+    #define struct Call(address target, bytes callData)
+
+    /// @notice Executes a call
+    #define function execute(Call call) payable returns ()
+    /// @notice Executes a batch of calls
+    #define function execute(Call[] calls) payable returns ()
+    /// @notice Transfers to an account
+    #define function transfer(address to, uint amount) nonpayable returns ()
+    /// @notice Transfers from a token id
+    #define function transfer(uint256 id, address to) nonpayable returns ()
+
+    #define macro MAIN() = takes (0) returns (0) {}
+    "#;
+
+    let interfaces = gen_sol_interfaces(
+        &vec![artifact(file("./src/Batch.huff", source, vec![]))],
+        None,
+        None,
+        None,
+    );
+    assert_eq!(
+        interfaces[0].2,
+        "// SPDX-License-Identifier: UNLICENSED\n\
+        pragma solidity ^0.8.4;\n\
+        \n\
+        interface IBatch {\n\
+        \tstruct Call {\n\
+        \t\taddress target;\n\
+        \t\tbytes callData;\n\
+        \t}\n\
+        \t/// @notice Executes a call\n\
+        \tfunction execute(Call calldata call) external payable;\n\
+        \t/// @notice Executes a batch of calls\n\
+        \tfunction execute(Call[] calldata calls) external payable;\n\
+        \t/// @notice Transfers to an account\n\
+        \tfunction transfer(address to, uint256 amount) external;\n\
+        \t/// @notice Transfers from a token id\n\
+        \tfunction transfer(uint256 id, address to) external;\n\
+        }\n"
+    );
+}
@@ -0,0 +1,70 @@
+use huff_codegen::Codegen;
+use huff_lexer::Lexer;
+use huff_parser::Parser;
+use huff_utils::prelude::*;
+
+#[test]
+fn test_main_source_map() {
+    let source = r#"
+    #define macro INNER() = takes (0) returns (0) {
+        0x01 0x02 add
+    }
+
+    #define jumptable__packed TABLE {
+        lbl
+    }
+
+    #define macro MAIN() = takes (0) returns (0) {
+        INNER()
+        __tablestart(TABLE)
+        lbl jump
+        lbl:
+            0x00 0x00 return
+    }
+    "#;
+
+    // Lex + Parse
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+    let contract = parser.parse().unwrap();
+    let env = ConstantEnv::new(&contract, None).unwrap();
+
+    let (bytecode, source_map) =
+        Codegen::generate_main_bytecode_with_source_map(&contract, &env).unwrap();
+    assert_eq!(bytecode, "600160020161001261000c565b60006000f3000c");
+
+    // Inlined macro bytes map to the innermost macro
+    assert_eq!(
+        find_source(&source_map, 0x04),
+        Some(&SourceMapEntry {
+            offset: 0x04,
+            length: 1,
+            name: "INNER".to_string(),
+            data: false,
+            file: None,
+            line: 0,
+            start: 0,
+            end: 0
+        })
+    );
+    assert_eq!(find_source(&source_map, 0x05).map(|e| e.name.as_str()), Some("MAIN"));
+    assert_eq!(find_source(&source_map, 0x11).map(|e| e.name.as_str()), Some("MAIN"));
+
+    // Table bytes are mapped as data
+    assert_eq!(
+        find_source(&source_map, 0x12),
+        Some(&SourceMapEntry {
+            offset: 0x12,
+            length: 2,
+            name: "TABLE".to_string(),
+            data: true,
+            file: None,
+            line: 0,
+            start: 0,
+            end: 0
+        })
+    );
+    assert_eq!(find_source(&source_map, 0x14), None);
+}
@@ -0,0 +1,188 @@
+use huff_core::standard_json::*;
+use serde_json::{json, Value};
+
+fn compile_json(input: Value) -> Value {
+    serde_json::from_str(&compile_standard_json(&input.to_string())).unwrap()
+}
+
+const TOKEN: &str = r#"
+    #include "./lib/Owned.huff"
+    #include "utils/Math.huff"
+
+    #define function owner() view returns (address)
+    #define constant OWNER = 0x00
+
+    #define macro MAIN() = takes (0) returns (0) {
+        ONLY_OWNER()
+        [OWNER] ADD_ONE()
+    }
+"#;
+
+const OWNED: &str = r#"
+    #define macro ONLY_OWNER() = takes (0) returns (0) {
+        caller pop
+    }
+"#;
+
+const MATH: &str = r#"
+    #define macro ADD_ONE() = takes (1) returns (1) {
+        0x01 add
+    }
+"#;
+
+#[test]
+fn test_compiles_in_memory_sources() {
+    let output = compile_json(json!({
+        "language": "Huff",
+        "sources": {
+            "src/Token.huff": { "content": TOKEN },
+            "src/lib/Owned.huff": { "content": OWNED },
+            "vendor/math/Math.huff": { "content": MATH },
+        },
+        "settings": {
+            "remappings": ["utils/=vendor/math/"],
+            "constants": { "OWNER": "0x02" },
+            "outputSelection": {
+                "*": { "*": ["abi", "evm.deployedBytecode", "evm.methodIdentifiers"] }
+            }
+        }
+    }));
+
+    assert_eq!(
+        output,
+        json!({
+            "sources": {
+                "src/Token.huff": { "id": 0 },
+                "src/lib/Owned.huff": { "id": 1 },
+                "vendor/math/Math.huff": { "id": 2 },
+            },
+            "contracts": {
+                "src/Token.huff": {
+                    "Token": {
+                        "abi": [{
+                            "type": "function",
+                            "name": "owner",
+                            "inputs": [],
+                            "outputs": [{ "name": "", "type": "address" }],
+                            "stateMutability": "view"
+                        }],
+                        "evm": {
+                            "deployedBytecode": {
+                                "object": "33506002600101",
+                                "sourceMap": "66:6:1:-;73:3;233:5:0;65:2:2;68:3",
+                                "linkReferences": {}
+                            },
+                            "methodIdentifiers": { "owner()": "8da5cb5b" }
+                        }
+                    }
+                }
+            }
+        })
+    );
+}
+
+#[test]
+fn test_selects_outputs() {
+    let output = compile_json(json!({
+        "language": "Huff",
+        "sources": { "Math.huff": { "content": "#define macro MAIN() = takes (0) returns (0) { 0x01 }" } },
+        "settings": {
+            "optimizer": { "enabled": true },
+            "outputSelection": { "Math.huff": { "Math": ["evm.bytecode.object"] } }
+        }
+    }));
+    assert_eq!(
+        output["contracts"]["Math.huff"]["Math"],
+        json!({ "evm": { "bytecode": { "object": "60028060093d393df36001" } } })
+    );
+
+    // Nothing is output unless selected
+    let output = compile_json(json!({
+        "language": "Huff",
+        "sources": { "Math.huff": { "content": "#define macro MAIN() = takes (0) returns (0) { 0x01 }" } }
+    }));
+    assert_eq!(output["contracts"]["Math.huff"]["Math"], json!({}));
+}
+
+#[test]
+fn test_reports_errors() {
+    let output = compile_json(json!({ "language": "Solidity", "sources": {} }));
+    assert_eq!(
+        output["errors"],
+        json!([{
+            "type": "JSONError",
+            "component": "general",
+            "severity": "error",
+            "message": "Unsupported language \"Solidity\", expected \"Huff\"",
+            "formattedMessage": "JSONError: Unsupported language \"Solidity\", expected \"Huff\""
+        }])
+    );
+
+    let output: Value = serde_json::from_str(&compile_standard_json("{")).unwrap();
+    assert_eq!(output["errors"][0]["type"], "JSONError");
+
+    let output = compile_json(json!({
+        "language": "Huff",
+        "sources": { "Token.huff": { "content": "#include \"./Missing.huff\"" } }
+    }));
+    assert_eq!(output["errors"][0]["type"], "IOError");
+    assert_eq!(
+        output["errors"][0]["message"],
+        "Source \"Missing.huff\" not found in the input sources"
+    );
+
+    let output = compile_json(json!({
+        "language": "Huff",
+        "sources": { "Token.huff": { "content": "#define constant OWNER = 0x00" } },
+        "settings": { "constants": { "OWNER": "owner" } }
+    }));
+    assert_eq!(output["errors"][0]["message"], "Invalid constant override \"OWNER\": \"owner\"");
+}
+
+#[test]
+fn test_reports_source_locations() {
+    let output = compile_json(json!({
+        "language": "Huff",
+        "sources": {
+            "Token.huff": { "content": "#define macro MAIN() = takes (0) returns (0) {\n    MISSING()\n}" }
+        },
+        "settings": { "evmVersion": "paris" }
+    }));
+
+    let errors = output["errors"].as_array().unwrap();
+    assert_eq!(errors[0]["severity"], "warning");
+    assert_eq!(
+        errors[0]["message"],
+        "evmVersion \"paris\" is ignored, huffc targets the latest EVM version"
+    );
+    assert_eq!(errors[1]["type"], "CompilerError");
+    assert_eq!(errors[1]["severity"], "error");
+    assert_eq!(
+        errors[1]["sourceLocation"],
+        json!({ "file": "Token.huff", "start": 51, "end": 58 })
+    );
+    assert_eq!(output["contracts"], json!({}));
+}
+
+#[test]
+fn test_reports_source_locations_in_included_files() {
+    let output = compile_json(json!({
+        "language": "Huff",
+        "sources": {
+            "src/Main.huff": {
+                "content": "#include \"./Lib.huff\"\n#define macro MAIN() = takes (0) returns (0) {\n    BROKEN()\n}"
+            },
+            "src/Lib.huff": {
+                "content": "#define macro BROKEN() = takes (0) returns (0) {\n    MISSING()\n}"
+            }
+        }
+    }));
+
+    let errors = output["errors"].as_array().unwrap();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0]["type"], "CompilerError");
+    assert_eq!(
+        errors[0]["sourceLocation"],
+        json!({ "file": "src/Lib.huff", "start": 53, "end": 60 })
+    );
+}
@@ -0,0 +1,187 @@
+mod common;
+
+use std::sync::Arc;
+
+use common::{artifact, file, parse};
+use huff_core::Compiler;
+use huff_utils::prelude::*;
+
+const ROUTER: &str = r#"
+    #define struct Call(address target, bytes callData)
+    #define struct Batch(Call[] calls, uint256 deadline)
+
+    #define function execute(Batch batch) payable returns ((bool,bytes)[] results)
+    #define event Executed(Batch indexed batch, (address,uint256)[2])
+    #define error CallFailed(Call call)
+"#;
+
+#[test]
+fn test_struct_abi_components() {
+    let abi: Abi = parse(ROUTER).into();
+    let json: serde_json::Value = serde_json::from_str(&abi.to_json().unwrap()).unwrap();
+
+    let call = serde_json::json!({
+        "name": "call",
+        "type": "tuple",
+        "internalType": "struct Call",
+        "components": [
+            { "name": "target", "type": "address" },
+            { "name": "callData", "type": "bytes" }
+        ]
+    });
+    let batch = |indexed: Option<bool>| {
+        let mut batch = serde_json::json!({
+            "name": "batch",
+            "type": "tuple",
+            "internalType": "struct Batch",
+            "components": [
+                {
+                    "name": "calls",
+                    "type": "tuple[]",
+                    "internalType": "struct Call[]",
+                    "components": [
+                        { "name": "target", "type": "address" },
+                        { "name": "callData", "type": "bytes" }
+                    ]
+                },
+                { "name": "deadline", "type": "uint256" }
+            ]
+        });
+        if let Some(indexed) = indexed {
+            batch["indexed"] = serde_json::json!(indexed);
+        }
+        batch
+    };
+    assert_eq!(
+        json,
+        serde_json::json!([
+            {
+                "type": "function",
+                "name": "execute",
+                "inputs": [batch(None)],
+                "outputs": [{
+                    "name": "results",
+                    "type": "tuple[]",
+                    "components": [
+                        { "name": "", "type": "bool" },
+                        { "name": "", "type": "bytes" }
+                    ]
+                }],
+                "stateMutability": "payable"
+            },
+            {
+                "type": "event",
+                "name": "Executed",
+                "inputs": [
+                    batch(Some(true)),
+                    {
+                        "name": "",
+                        "type": "tuple[2]",
+                        "components": [
+                            { "name": "", "type": "address" },
+                            { "name": "", "type": "uint256" }
+                        ],
+                        "indexed": false
+                    }
+                ],
+                "anonymous": false
+            },
+            {
+                "type": "error",
+                "name": "CallFailed",
+                "inputs": [call]
+            }
+        ])
+    );
+
+    // Selectors are computed from the expanded tuples
+    assert_eq!(
+        abi.functions.keys().collect::<Vec<_>>(),
+        vec!["execute(((address,bytes)[],uint256))"]
+    );
+    let contract = parse(ROUTER);
+    assert_eq!(contract.functions[0].signature, abi.functions.values().next().unwrap().selector());
+    assert_eq!(contract.errors[0].selector, abi.errors.values().next().unwrap().selector());
+
+    // Struct names survive a round trip through the JSON ABI
+    let roundtrip: Abi = serde_json::from_str(&abi.to_json().unwrap()).unwrap();
+    assert_eq!(roundtrip.to_json().unwrap(), abi.to_json().unwrap());
+}
+
+#[test]
+fn test_interface_declares_structs() {
+    let artifact = Artifact {
+        file: Arc::new(FileSource {
+            path: "./contracts/Router.huff".to_string(),
+            ..Default::default()
+        }),
+        abi: Some(parse(ROUTER).into()),
+        ..Default::default()
+    };
+
+    let interfaces = gen_sol_interfaces(&vec![Arc::new(artifact)], None, None, None);
+    assert_eq!(
+        interfaces[0].2,
+        "// SPDX-License-Identifier: UNLICENSED\n\
+        pragma solidity ^0.8.4;\n\
+        \n\
+        interface IRouter {\n\
+        \tstruct Call {\n\
+        \t\taddress target;\n\
+        \t\tbytes callData;\n\
+        \t}\n\
+        \tstruct Batch {\n\
+        \t\tCall[] calls;\n\
+        \t\tuint256 deadline;\n\
+        \t}\n\
+        \tstruct Tuple0 {\n\
+        \t\taddress field0;\n\
+        \t\tuint256 field1;\n\
+        \t}\n\
+        \tstruct Tuple1 {\n\
+        \t\tbool field0;\n\
+        \t\tbytes field1;\n\
+        \t}\n\
+        \tevent Executed(Batch indexed batch, Tuple0[2]);\n\
+        \terror CallFailed(Call call);\n\
+        \tfunction execute(Batch calldata batch) external payable returns (Tuple1[] memory results);\n\
+        }\n"
+    );
+}
+
+#[test]
+fn test_struct_from_included_file() {
+    let types = file("./types.huff", "#define struct Call(address target, bytes callData)", vec![]);
+    let main = file(
+        "./main.huff",
+        r#"
+        #include "./types.huff"
+        #define function aggregate(Call[]) payable returns ()
+        #define macro MAIN() = takes (0) returns (0) { __FUNC_SIG(aggregate) }
+        "#,
+        vec![types],
+    );
+
+    let artifact = artifact(main);
+    assert_eq!(artifact.runtime, "63252dba42");
+}
+
+#[test]
+fn test_unknown_struct_errors() {
+    let main = file(
+        "./main.huff",
+        r#"
+        #define function aggregate(Call[]) payable returns ()
+        #define macro MAIN() = takes (0) returns (0) { 0x00 }
+        "#,
+        vec![],
+    );
+
+    let compiler = Compiler::new(Arc::new(vec![]), None, None, None, false, false);
+    match compiler.gen_artifact(main) {
+        Err(CompilerError::ParserError(e)) => {
+            assert_eq!(e.kind, ParserErrorKind::UnknownType("Call[]".to_string()))
+        }
+        _ => panic!("moose"),
+    }
+}
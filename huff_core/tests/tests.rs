@@ -3,7 +3,7 @@ use huff_lexer::Lexer;
 use huff_parser::Parser;
 use huff_utils::{
     error::CodegenErrorKind,
-    prelude::{FullFileSource, Token},
+    prelude::{ConstantEnv, FullFileSource, Token},
 };
 
 #[test]
@@ -29,10 +29,10 @@ fn test_invocation_should_fail() {
     let mut parser = Parser::new(tokens, None);
 
     // Parse the AST
-    let mut contract = parser.parse().unwrap();
+    let contract = parser.parse().unwrap();
 
-    // Derive storage pointers
-    contract.derive_storage_pointers();
+    // Resolve the constants
+    let env = ConstantEnv::new(&contract, None).unwrap();
 
     // Instantiate Codegen
     let cg = Codegen::new();
@@ -42,7 +42,7 @@ fn test_invocation_should_fail() {
 
     // Have the Codegen create the runtime bytecode. Should throw an error because test
     // invocation is not allowed.
-    match Codegen::generate_main_bytecode(&contract) {
+    match Codegen::generate_main_bytecode(&contract, &env) {
         Ok(_) => panic!("Expected an error"),
         Err(e) => {
             assert_eq!(
@@ -0,0 +1,188 @@
+mod common;
+
+use std::sync::Arc;
+
+use common::file;
+use huff_codegen::Codegen;
+use huff_core::session::Session;
+use huff_utils::prelude::*;
+
+const MACROS: &str = r#"
+    #define constant OFFSET = 0x04
+
+    #define macro GOTO(label dest) = takes (0) returns (0) {
+        <dest> jump
+    }
+
+    #define macro PUSH_SIZE(literal size) = takes (0) returns (1) {
+        <size>
+    }
+
+    #define macro APPLY(opcode op) = takes (2) returns (1) {
+        <op>
+    }
+
+    #define macro FORWARD(constant size) = takes (0) returns (1) {
+        PUSH_SIZE(<size>)
+    }
+
+    #define macro FORWARD_UNTYPED(size) = takes (0) returns (1) {
+        PUSH_SIZE(<size>)
+    }
+
+    #define macro MISMATCH(opcode size) = takes (2) returns (1) {
+        PUSH_SIZE(<size>)
+    }
+
+    #define macro TWICE(macro body) = takes (1) returns (1) {
+        <body>()
+        <body>()
+    }
+
+    #define macro DOUBLE() = takes (1) returns (1) {
+        dup1 add
+    }
+
+    #define macro GOTO_UNTYPED(dest) = takes (0) returns (0) {
+        GOTO(<dest>)
+    }
+"#;
+
+fn parse(main: &str) -> Contract {
+    common::parse(&format!(
+        "{}\n    #define macro MAIN() = takes (0) returns (0) {{ {} }}\n",
+        MACROS, main
+    ))
+}
+
+fn kind_mismatch(contract: &Contract) -> CodegenErrorKind {
+    let env = ConstantEnv::new(contract, None).unwrap();
+    Codegen::generate_main_bytecode(contract, &env).unwrap_err().kind
+}
+
+#[test]
+fn test_typed_parameters() {
+    let contract = parse("GOTO(dest) dest: 0x01 0x02 APPLY(add) PUSH_SIZE(OFFSET) FORWARD(OFFSET)");
+    let env = ConstantEnv::new(&contract, None).unwrap();
+    assert_eq!(
+        Codegen::generate_main_bytecode(&contract, &env).unwrap(),
+        "610004565b600160020160046004"
+    );
+}
+
+#[test]
+fn test_label_parameter_named_like_opcode() {
+    // Without the `label` kind, `stop` and `OFFSET` would be passed as an opcode and a constant
+    let contract = parse("GOTO(stop) GOTO(OFFSET) stop: OFFSET:");
+    let env = ConstantEnv::new(&contract, None).unwrap();
+    assert_eq!(Codegen::generate_main_bytecode(&contract, &env).unwrap(), "61000856610009565b5b");
+}
+
+#[test]
+fn test_undefined_label_passed_to_label_parameter() {
+    let contract = parse("GOTO(nowhere)");
+    assert_eq!(
+        kind_mismatch(&contract),
+        CodegenErrorKind::MacroArgumentKindMismatch(MacroParamKind::Label, "dest".to_string())
+    );
+}
+
+#[test]
+fn test_label_passed_to_literal_parameter() {
+    let contract = parse("PUSH_SIZE(dest) dest:");
+    assert_eq!(
+        kind_mismatch(&contract),
+        CodegenErrorKind::MacroArgumentKindMismatch(MacroParamKind::Literal, "size".to_string())
+    );
+}
+
+#[test]
+fn test_macro_parameter() {
+    let contract = parse("0x01 TWICE(DOUBLE)");
+    let env = ConstantEnv::new(&contract, None).unwrap();
+    assert_eq!(Codegen::generate_main_bytecode(&contract, &env).unwrap(), "600180018001");
+
+    let contract = parse("0x01 TWICE(OFFSET)");
+    assert_eq!(
+        kind_mismatch(&contract),
+        CodegenErrorKind::MacroArgumentKindMismatch(MacroParamKind::Macro, "body".to_string())
+    );
+}
+
+#[test]
+fn test_untyped_arg_call_checks_the_bubbled_argument() {
+    let contract = parse("FORWARD_UNTYPED(0x03)");
+    let env = ConstantEnv::new(&contract, None).unwrap();
+    assert_eq!(Codegen::generate_main_bytecode(&contract, &env).unwrap(), "6003");
+
+    let contract = parse("FORWARD_UNTYPED(add)");
+    assert_eq!(
+        kind_mismatch(&contract),
+        CodegenErrorKind::MacroArgumentKindMismatch(MacroParamKind::Literal, "size".to_string())
+    );
+
+    let contract = parse("GOTO_UNTYPED(nowhere)");
+    assert_eq!(
+        kind_mismatch(&contract),
+        CodegenErrorKind::MacroArgumentKindMismatch(MacroParamKind::Label, "dest".to_string())
+    );
+}
+
+#[test]
+fn test_macro_defined_in_another_file() {
+    let lib = file(
+        "./lib.huff",
+        r#"
+        #define macro GOTO(label dest) = takes (0) returns (0) {
+            <dest> jump
+        }
+        "#,
+        vec![],
+    );
+    let main = file(
+        "./main.huff",
+        r#"
+        #include "./lib.huff"
+        #define macro MAIN() = takes (0) returns (0) { GOTO(0x01) }
+        "#,
+        vec![lib],
+    );
+
+    // The parser only checks invocations of macros defined in the same file
+    let session = Session::new(&[Arc::clone(&main)]).unwrap();
+    let contract = session.contract(&main).unwrap();
+    assert_eq!(
+        kind_mismatch(&contract),
+        CodegenErrorKind::MacroArgumentKindMismatch(MacroParamKind::Label, "dest".to_string())
+    );
+}
+
+#[test]
+fn test_arg_call_kind_mismatch() {
+    let contract = parse("0x01 0x02 MISMATCH(add)");
+    let env = ConstantEnv::new(&contract, None).unwrap();
+    let err = Codegen::generate_main_bytecode(&contract, &env).unwrap_err();
+    assert_eq!(
+        err.kind,
+        CodegenErrorKind::MacroArgumentKindMismatch(MacroParamKind::Literal, "size".to_string())
+    );
+    assert_eq!(err.backtrace.len(), 1);
+    assert_eq!(err.backtrace[0].macro_name, "MISMATCH");
+}
+
+#[test]
+fn test_expand_label_parameter() {
+    let contract = parse("GOTO(stop) stop:");
+    let env = ConstantEnv::new(&contract, None).unwrap();
+    assert_eq!(
+        Codegen::expand_macro("MAIN", &contract, &env).unwrap(),
+        r#"#define macro MAIN() = takes (0) returns (0) {
+    // GOTO(stop) {
+        stop // <dest>
+        jump
+    // }
+    stop:
+}
+"#
+    );
+}
@@ -58,6 +58,8 @@ pub struct Lexer<'a> {
     pub eof_returned: bool,
     /// Current context.
     pub context: Context,
+    /// Depth of the tuples opened in the current ABI arguments.
+    pub tuple_depth: usize,
 }
 
 impl<'a> Lexer<'a> {
@@ -72,6 +74,7 @@ impl<'a> Lexer<'a> {
             eof: false,
             eof_returned: false,
             context: Context::Global,
+            tuple_depth: 0,
         }
     }
 
@@ -268,8 +271,9 @@ impl<'a> Lexer<'a> {
     /// `TokenKind::Ident`.
     ///
     /// Rules:
-    /// - The `macro`, `fn`, `test`, `function`, `constant`, `event`, `jumptable`,
-    ///   `jumptable__packed`, and `table` keywords must be preceded by a `#define` keyword.
+    /// - The `macro`, `fn`, `test`, `function`, `constant`, `event`, `error`, `struct`,
+    ///   `jumptable`, `jumptable__packed`, and `table` keywords must be preceded by a `#define`
+    ///   keyword.
     /// - The `takes` keyword must be preceded by an assignment operator: `=`.
     /// - The `nonpayable`, `payable`, `view`, and `pure` keywords must be preceeded by one of these
     ///   keywords or a close paren.
@@ -283,6 +287,7 @@ impl<'a> Lexer<'a> {
             Some(TokenKind::Function) |
             Some(TokenKind::Constant) |
             Some(TokenKind::Error) |
+            Some(TokenKind::Struct) |
             Some(TokenKind::Event) |
             Some(TokenKind::JumpTable) |
             Some(TokenKind::JumpTablePacked) |
@@ -352,7 +357,13 @@ impl<'a> Iterator for Lexer<'a> {
                 '#' => {
                     let mut found_kind: Option<TokenKind> = None;
 
-                    let keys = [TokenKind::Define, TokenKind::Include];
+                    let keys = [
+                        TokenKind::Define,
+                        TokenKind::Include,
+                        TokenKind::If,
+                        TokenKind::Else,
+                        TokenKind::Endif,
+                    ];
                     for kind in keys.into_iter() {
                         let key = kind.to_string();
                         let token_length = key.len() - 1;
@@ -389,6 +400,7 @@ impl<'a> Iterator for Lexer<'a> {
                         TokenKind::Function,
                         TokenKind::Constant,
                         TokenKind::Error,
+                        TokenKind::Struct,
                         TokenKind::Takes,
                         TokenKind::Returns,
                         TokenKind::Event,
@@ -430,31 +442,39 @@ impl<'a> Iterator for Lexer<'a> {
                             TokenKind::Macro | TokenKind::Fn | TokenKind::Test => {
                                 self.context = Context::MacroDefinition
                             }
-                            TokenKind::Function | TokenKind::Event | TokenKind::Error => {
-                                self.context = Context::Abi
-                            }
+                            TokenKind::Function |
+                            TokenKind::Event |
+                            TokenKind::Error |
+                            TokenKind::Struct => self.context = Context::Abi,
                             TokenKind::Constant => self.context = Context::Constant,
                             TokenKind::CodeTable => self.context = Context::CodeTableBody,
                             _ => (),
                         }
                     }
 
-                    // Check for free storage pointer builtin
-                    let fsp = "FREE_STORAGE_POINTER";
-                    let token_length = fsp.len() - 1;
-                    let peeked = self.peek_n_chars(token_length);
-                    if fsp == peeked {
-                        self.nconsume(token_length);
-                        // Consume the parenthesis following the FREE_STORAGE_POINTER
-                        // Note: This will consume `FREE_STORAGE_POINTER)` or
-                        // `FREE_STORAGE_POINTER(` as well
-                        if let Some('(') = self.peek() {
-                            self.consume();
-                        }
-                        if let Some(')') = self.peek() {
-                            self.consume();
+                    // Check for free pointer builtins
+                    let pointers = [
+                        ("FREE_STORAGE_POINTER", TokenKind::FreeStoragePointer),
+                        ("FREE_TRANSIENT_POINTER", TokenKind::FreeTransientPointer),
+                        ("FREE_MEMORY_POINTER", TokenKind::FreeMemoryPointer),
+                    ];
+                    for (fp, kind) in pointers.into_iter() {
+                        let token_length = fp.len() - 1;
+                        let peeked = self.peek_n_chars(token_length);
+                        if fp == peeked {
+                            self.nconsume(token_length);
+                            // Consume the parenthesis following the pointer builtin
+                            // Note: This will consume `FREE_STORAGE_POINTER)` or
+                            // `FREE_STORAGE_POINTER(` as well
+                            if let Some('(') = self.peek() {
+                                self.consume();
+                            }
+                            if let Some(')') = self.peek() {
+                                self.consume();
+                            }
+                            found_kind = Some(kind);
+                            break
                         }
-                        found_kind = Some(TokenKind::FreeStoragePointer);
                     }
 
                     let potential_label: String =
@@ -500,7 +520,9 @@ impl<'a> Iterator for Lexer<'a> {
                     if self.context == Context::AbiArgs {
                         let curr_char = self.peek()?;
                         if !['(', ')'].contains(&curr_char) {
-                            self.dyn_consume(|c| c.is_alphanumeric() || *c == '[' || *c == ']');
+                            self.dyn_consume(|c| {
+                                c.is_alphanumeric() || *c == '_' || *c == '[' || *c == ']'
+                            });
                             // got a type at this point, we have to know which
                             let raw_type: String = self.slice();
 
@@ -604,6 +626,8 @@ impl<'a> Iterator for Lexer<'a> {
                 '(' => {
                     match self.context {
                         Context::Abi => self.context = Context::AbiArgs,
+                        // Tuple types nest within the arguments
+                        Context::AbiArgs => self.tuple_depth += 1,
                         Context::MacroBody => self.context = Context::MacroArgs,
                         _ => {}
                     }
@@ -611,6 +635,7 @@ impl<'a> Iterator for Lexer<'a> {
                 }
                 ')' => {
                     match self.context {
+                        Context::AbiArgs if self.tuple_depth > 0 => self.tuple_depth -= 1,
                         Context::AbiArgs => self.context = Context::Abi,
                         Context::MacroArgs => self.context = Context::MacroBody,
                         _ => {}
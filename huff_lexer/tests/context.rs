@@ -52,3 +52,45 @@ fn macro_context() {
         .collect::<Vec<Token>>();
     assert_eq!(tokens.get(tokens.len() - 3).unwrap().kind, TokenKind::Opcode(Opcode::Byte));
 }
+
+/// Nested tuples stay in the arguments' context until the arguments are closed
+#[test]
+fn tuple_context() {
+    let source = "#define function test((address,(bool,bytes32))[]) view returns (uint256)";
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer
+        .into_iter()
+        .map(|x| x.unwrap())
+        .filter(|x| !matches!(x.kind, TokenKind::Whitespace))
+        .map(|x| x.kind)
+        .collect::<Vec<TokenKind>>();
+
+    assert_eq!(
+        tokens,
+        vec![
+            TokenKind::Define,
+            TokenKind::Function,
+            TokenKind::Ident("test".to_string()),
+            TokenKind::OpenParen,
+            TokenKind::OpenParen,
+            TokenKind::PrimitiveType(PrimitiveEVMType::Address),
+            TokenKind::Comma,
+            TokenKind::OpenParen,
+            TokenKind::PrimitiveType(PrimitiveEVMType::Bool),
+            TokenKind::Comma,
+            TokenKind::PrimitiveType(PrimitiveEVMType::Bytes(32)),
+            TokenKind::CloseParen,
+            TokenKind::CloseParen,
+            TokenKind::OpenBracket,
+            TokenKind::CloseBracket,
+            TokenKind::CloseParen,
+            TokenKind::View,
+            TokenKind::Returns,
+            TokenKind::OpenParen,
+            TokenKind::PrimitiveType(PrimitiveEVMType::Uint(256)),
+            TokenKind::CloseParen,
+            TokenKind::Eof,
+        ]
+    );
+}
@@ -22,3 +22,26 @@ fn free_storage_pointer() {
     assert_eq!(lexer.current_span().end, source.len());
     assert!(lexer.eof);
 }
+
+#[test]
+fn free_transient_and_memory_pointers() {
+    let source = "FREE_TRANSIENT_POINTER() FREE_MEMORY_POINTER(0x20)";
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer
+        .into_iter()
+        .map(|x| x.unwrap())
+        .filter(|x| !matches!(x.kind, TokenKind::Whitespace))
+        .collect::<Vec<Token>>();
+
+    assert_eq!(
+        tokens,
+        vec![
+            Token::new(TokenKind::FreeTransientPointer, Span::new(0..24, None)),
+            Token::new(TokenKind::FreeMemoryPointer, Span::new(25..45, None)),
+            Token::new(TokenKind::Literal(str_to_bytes32("20")), Span::new(47..49, None)),
+            Token::new(TokenKind::CloseParen, Span::new(49..50, None)),
+            Token::new(TokenKind::Eof, Span::new(50..50, None)),
+        ]
+    );
+}
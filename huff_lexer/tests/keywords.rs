@@ -728,3 +728,23 @@ fn parses_include_with_extra_suffix() {
     assert_eq!(unwrapped, Token::new(TokenKind::Include, span.clone()));
     assert_eq!(lexer.current_span().deref(), &span);
 }
+
+#[test]
+fn parses_conditional_keywords() {
+    let source = "#define macro MAIN() = takes(0) returns(0) { #if DEBUG #else #endif }";
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer
+        .into_iter()
+        .map(|x| x.unwrap())
+        .filter(|x| !matches!(x.kind, TokenKind::Whitespace))
+        .collect::<Vec<Token>>();
+
+    assert_eq!(tokens[15], Token::new(TokenKind::If, Span::new(45..48, None)));
+    assert_eq!(
+        tokens[16],
+        Token::new(TokenKind::Ident("DEBUG".to_string()), Span::new(49..54, None))
+    );
+    assert_eq!(tokens[17], Token::new(TokenKind::Else, Span::new(55..60, None)));
+    assert_eq!(tokens[18], Token::new(TokenKind::Endif, Span::new(61..67, None)));
+}
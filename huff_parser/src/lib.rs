@@ -7,12 +7,14 @@
 use huff_utils::{
     ast::*,
     error::*,
+    evm::Opcode,
     files,
-    prelude::{bytes32_to_string, hash_bytes, str_to_bytes32, Span},
+    prelude::{bytes32_to_string, hash_bytes, hex_to_usize, str_to_bytes32, Span},
     token::{Token, TokenKind},
     types::*,
 };
 use regex::Regex;
+use std::str::FromStr;
 
 /// The Parser
 #[derive(Debug, Clone)]
@@ -94,13 +96,18 @@ impl Parser {
                     TokenKind::Constant => {
                         let c = self.parse_constant()?;
                         tracing::info!(target: "parser", "SUCCESSFULLY PARSED CONSTANT {}", c.name);
-                        contract.constants.lock().unwrap().push(c);
+                        contract.constants.push(c);
                     }
                     TokenKind::Error => {
                         let e = self.parse_custom_error()?;
                         tracing::info!(target: "parser", "SUCCESSFULLY PARSED ERROR {}", e.name);
                         contract.errors.push(e);
                     }
+                    TokenKind::Struct => {
+                        let s = self.parse_struct()?;
+                        tracing::info!(target: "parser", "SUCCESSFULLY PARSED STRUCT {}", s.name);
+                        contract.structs.push(s);
+                    }
                     TokenKind::Macro | TokenKind::Fn | TokenKind::Test => {
                         let m = self.parse_macro()?;
                         tracing::info!(target: "parser", "SUCCESSFULLY PARSED MACRO {}", m.name);
@@ -112,12 +119,12 @@ impl Parser {
                     _ => {
                         tracing::error!(
                             target: "parser",
-                            "Invalid definition. Must be a function, event, constant, error, or macro. Got: {}",
+                            "Invalid definition. Must be a function, event, constant, error, struct, or macro. Got: {}",
                             self.current_token.kind
                         );
                         return Err(ParserError {
                             kind: ParserErrorKind::InvalidDefinition(self.current_token.kind.clone()),
-                            hint: Some("Definition must be one of: `function`, `event`, `constant`, `error`, `macro`, `fn`, or `test`.".to_string()),
+                            hint: Some("Definition must be one of: `function`, `event`, `constant`, `error`, `struct`, `macro`, `fn`, or `test`.".to_string()),
                             spans: AstSpan(vec![self.current_token.span.clone()]),
                         })
                     }
@@ -136,9 +143,65 @@ impl Parser {
             }
         }
 
+        contract.index_symbols();
+        for m in contract.macros.iter() {
+            Parser::check_macro_arg_kinds(&contract, &m.statements)?;
+        }
+        // Structs may be defined in an included file, so signatures are resolved, and unknown types
+        // reported, once the files are assembled. See [Contract::resolve_signatures].
         Ok(contract)
     }
 
+    /// Checks the literal and opcode arguments of invocations of macros defined in the same file
+    /// against the kinds of the macros' typed parameters.
+    ///
+    /// Labels, constants and macros may be defined in other files, so identifiers passed to those
+    /// kinds are checked during codegen.
+    fn check_macro_arg_kinds(
+        contract: &Contract,
+        statements: &[Statement],
+    ) -> Result<(), ParserError> {
+        for s in statements {
+            match &s.ty {
+                StatementType::MacroInvocation(mi) => {
+                    let m = match contract.find_macro_by_name(&mi.macro_name) {
+                        Some(m) => m,
+                        None => continue,
+                    };
+                    for (param, arg) in m.parameters.iter().zip(mi.args.iter()) {
+                        let kind = match param.kind {
+                            Some(k) => k,
+                            None => continue,
+                        };
+                        let valid = match arg {
+                            MacroArg::Literal(_) => kind == MacroParamKind::Literal,
+                            MacroArg::Ident(iden) => {
+                                kind != MacroParamKind::Opcode || Opcode::from_str(iden).is_ok()
+                            }
+                            MacroArg::ArgCall(_) => true,
+                        };
+                        if !valid {
+                            let param_name = param.name.clone().unwrap_or_default();
+                            tracing::error!(target: "parser", "ARGUMENT PASSED TO \"{}\" IN \"{}\" IS NOT A {}", param_name, m.name, kind);
+                            return Err(ParserError {
+                                kind: ParserErrorKind::MacroArgumentKindMismatch(kind, param_name),
+                                hint: Some(format!("Expected a {} argument", kind)),
+                                spans: mi.span.clone(),
+                            })
+                        }
+                    }
+                }
+                StatementType::Label(l) => Parser::check_macro_arg_kinds(contract, &l.inner)?,
+                StatementType::Conditional(c) => {
+                    Parser::check_macro_arg_kinds(contract, &c.statements)?;
+                    Parser::check_macro_arg_kinds(contract, &c.else_statements)?;
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
     /// Parses Contract Imports
     pub fn parse_imports(&mut self) -> Result<FilePath, ParserError> {
         // First token should be keyword "#include"
@@ -341,7 +404,59 @@ impl Parser {
         let value: ConstVal = match self.current_token.kind.clone() {
             TokenKind::FreeStoragePointer => {
                 self.consume();
-                ConstVal::FreeStoragePointer(FreeStoragePointer {})
+                // Namespaced pointers, ie. `FREE_STORAGE_POINTER("namespace.id")`
+                let namespace = match self.current_token.kind.clone() {
+                    TokenKind::Str(ns) if !ns.is_empty() => {
+                        self.consume();
+                        self.match_kind(TokenKind::CloseParen)?;
+                        Some(ns)
+                    }
+                    TokenKind::Str(_) => {
+                        tracing::error!(target: "parser", "EMPTY FREE STORAGE POINTER NAMESPACE");
+                        return Err(ParserError {
+                            kind: ParserErrorKind::InvalidConstantValue(
+                                self.current_token.kind.clone(),
+                            ),
+                            hint: Some("Expected a non-empty storage namespace".to_string()),
+                            spans: AstSpan(vec![self.current_token.span.clone()]),
+                        })
+                    }
+                    _ => None,
+                };
+                ConstVal::FreeStoragePointer(FreeStoragePointer { namespace })
+            }
+            TokenKind::FreeTransientPointer => {
+                self.consume();
+                ConstVal::FreeTransientPointer(FreeTransientPointer)
+            }
+            TokenKind::FreeMemoryPointer => {
+                self.consume();
+                // The size of the memory region is required, ie. `FREE_MEMORY_POINTER(0x40)`
+                let size = match self.current_token.kind.clone() {
+                    TokenKind::Literal(l) => hex_to_usize(&bytes32_to_string(&l, false)).ok(),
+                    TokenKind::Num(n) => Some(n),
+                    _ => None,
+                };
+                match size {
+                    Some(size) => {
+                        self.consume();
+                        self.match_kind(TokenKind::CloseParen)?;
+                        ConstVal::FreeMemoryPointer(FreeMemoryPointer { size })
+                    }
+                    None => {
+                        tracing::error!(target: "parser", "INVALID FREE MEMORY POINTER SIZE: {}", self.current_token.kind);
+                        return Err(ParserError {
+                            kind: ParserErrorKind::InvalidConstantValue(
+                                self.current_token.kind.clone(),
+                            ),
+                            hint: Some(
+                                "Expected a memory region size, ie. `FREE_MEMORY_POINTER(0x20)`"
+                                    .to_string(),
+                            ),
+                            spans: AstSpan(vec![self.current_token.span.clone()]),
+                        })
+                    }
+                }
             }
             TokenKind::Literal(l) => {
                 self.consume();
@@ -403,6 +518,36 @@ impl Parser {
         Ok(ErrorDefinition { name, selector, parameters, span: AstSpan(new_spans) })
     }
 
+    /// Parses a struct definition, ie. `#define struct Position(address owner, uint256 amount)`
+    pub fn parse_struct(&mut self) -> Result<StructDefinition, ParserError> {
+        // Struct Identifier
+        self.match_kind(TokenKind::Struct)?;
+
+        // Parse the struct name
+        self.match_kind(TokenKind::Ident("x".to_string()))?;
+        let tok = self.peek_behind().unwrap().kind;
+        let name = match tok {
+            TokenKind::Ident(struct_name) => struct_name,
+            _ => {
+                tracing::error!(target: "parser", "TOKEN MISMATCH - EXPECTED IDENT, GOT: {}", tok);
+                return Err(ParserError {
+                    kind: ParserErrorKind::UnexpectedType(tok),
+                    hint: Some("Expected struct name.".to_string()),
+                    spans: AstSpan(self.spans.clone()),
+                })
+            }
+        };
+
+        // The fields are parsed like function arguments
+        let fields = self.parse_args(true, true, false, false)?;
+
+        // Clone spans and set to nothing
+        let new_spans = self.spans.clone();
+        self.spans = vec![];
+
+        Ok(StructDefinition { name, fields, span: AstSpan(new_spans) })
+    }
+
     /// Parses a decorator.
     ///
     /// Decorators are currently used to add additional flags to a test.
@@ -538,10 +683,21 @@ impl Parser {
     ///
     /// Only HEX, OPCODES, labels, builtins, and MACRO calls should be authorized.
     pub fn parse_body(&mut self) -> Result<Vec<Statement>, ParserError> {
-        let mut statements: Vec<Statement> = Vec::new();
         self.match_kind(TokenKind::OpenBrace)?;
         tracing::info!(target: "parser", "PARSING MACRO BODY");
-        while !self.check(TokenKind::CloseBrace) {
+        let statements = self.parse_statements()?;
+        // consume close brace
+        self.match_kind(TokenKind::CloseBrace)?;
+        Ok(statements)
+    }
+
+    /// Parse the statements of a macro body up to the end of the enclosing block.
+    pub fn parse_statements(&mut self) -> Result<Vec<Statement>, ParserError> {
+        let mut statements: Vec<Statement> = Vec::new();
+        while !self.check(TokenKind::CloseBrace) &&
+            !self.check(TokenKind::Else) &&
+            !self.check(TokenKind::Endif)
+        {
             match self.current_token.kind.clone() {
                 TokenKind::Literal(val) => {
                     let curr_spans = vec![self.current_token.span.clone()];
@@ -562,32 +718,23 @@ impl Parser {
                     });
                 }
                 TokenKind::Ident(ident_str) => {
-                    let mut curr_spans = vec![self.current_token.span.clone()];
+                    let ident_span = self.current_token.span.clone();
                     tracing::info!(target: "parser", "PARSING MACRO BODY: [IDENT: {}]", ident_str);
                     self.match_kind(TokenKind::Ident("MACRO_NAME".to_string()))?;
                     // Can be a macro call or label call
                     match self.current_token.kind.clone() {
                         TokenKind::OpenParen => {
-                            // Parse Macro Call
-                            let lit_args = self.parse_macro_call()?;
-                            // Grab all spans following our macro invocation spam
-                            if let Some(i) = self.spans.iter().position(|s| s.eq(&curr_spans[0])) {
-                                curr_spans.append(&mut self.spans[(i + 1)..].to_vec());
-                            }
+                            let mi = self.parse_macro_invocation(ident_str, ident_span)?;
                             statements.push(Statement {
-                                ty: StatementType::MacroInvocation(MacroInvocation {
-                                    macro_name: ident_str.to_string(),
-                                    args: lit_args,
-                                    span: AstSpan(curr_spans.clone()),
-                                }),
-                                span: AstSpan(curr_spans),
+                                span: mi.span.clone(),
+                                ty: StatementType::MacroInvocation(mi),
                             });
                         }
                         _ => {
                             tracing::info!(target: "parser", "LABEL CALL TO: {}", ident_str);
                             statements.push(Statement {
                                 ty: StatementType::LabelCall(ident_str),
-                                span: AstSpan(curr_spans),
+                                span: AstSpan(vec![ident_span]),
                             });
                         }
                     }
@@ -617,11 +764,21 @@ impl Parser {
                 }
                 TokenKind::LeftAngle => {
                     let (arg_call, arg_span) = self.parse_arg_call()?;
-                    tracing::info!(target: "parser", "PARSING MACRO BODY: [ARG CALL: {}]", arg_call);
-                    statements.push(Statement {
-                        ty: StatementType::ArgCall(arg_call),
-                        span: AstSpan(vec![arg_span]),
-                    });
+                    // Can be an arg call or an invocation of a macro passed as an argument
+                    if self.check(TokenKind::OpenParen) {
+                        tracing::info!(target: "parser", "PARSING MACRO BODY: [ARG MACRO INVOCATION: {}]", arg_call);
+                        let mi = self.parse_macro_invocation(arg_call, arg_span)?;
+                        statements.push(Statement {
+                            span: mi.span.clone(),
+                            ty: StatementType::ArgMacroInvocation(mi),
+                        });
+                    } else {
+                        tracing::info!(target: "parser", "PARSING MACRO BODY: [ARG CALL: {}]", arg_call);
+                        statements.push(Statement {
+                            ty: StatementType::ArgCall(arg_call),
+                            span: AstSpan(vec![arg_span]),
+                        });
+                    }
                 }
                 TokenKind::BuiltinFunction(f) => {
                     let mut curr_spans = vec![self.current_token.span.clone()];
@@ -638,6 +795,11 @@ impl Parser {
                         span: AstSpan(curr_spans),
                     });
                 }
+                TokenKind::If => {
+                    let conditional = self.parse_conditional()?;
+                    tracing::info!(target: "parser", "PARSING MACRO BODY: [{}]", conditional.ty);
+                    statements.push(conditional);
+                }
                 kind => {
                     tracing::error!(target: "parser", "TOKEN MISMATCH - MACRO BODY: {}", kind);
                     return Err(ParserError {
@@ -648,8 +810,6 @@ impl Parser {
                 }
             };
         }
-        // consume close brace
-        self.match_kind(TokenKind::CloseBrace)?;
         Ok(statements)
     }
 
@@ -668,7 +828,9 @@ impl Parser {
         let mut statements: Vec<Statement> = Vec::new();
         self.match_kind(TokenKind::Colon)?;
         while !self.check(TokenKind::Label("NEXT_LABEL".to_string())) &&
-            !self.check(TokenKind::CloseBrace)
+            !self.check(TokenKind::CloseBrace) &&
+            !self.check(TokenKind::Else) &&
+            !self.check(TokenKind::Endif)
         {
             match self.current_token.kind.clone() {
                 TokenKind::Literal(val) => {
@@ -690,32 +852,23 @@ impl Parser {
                     });
                 }
                 TokenKind::Ident(ident_str) => {
-                    let mut curr_spans = vec![self.current_token.span.clone()];
+                    let ident_span = self.current_token.span.clone();
                     tracing::info!(target: "parser", "PARSING LABEL BODY: [IDENT: {}]", ident_str);
                     self.match_kind(TokenKind::Ident("MACRO_NAME".to_string()))?;
                     // Can be a macro call or label call
                     match self.current_token.kind.clone() {
                         TokenKind::OpenParen => {
-                            // Parse Macro Call
-                            let lit_args = self.parse_macro_call()?;
-                            // Grab all spans following our macro invocation spam
-                            if let Some(i) = self.spans.iter().position(|s| s.eq(&curr_spans[0])) {
-                                curr_spans.append(&mut self.spans[(i + 1)..].to_vec());
-                            }
+                            let mi = self.parse_macro_invocation(ident_str, ident_span)?;
                             statements.push(Statement {
-                                ty: StatementType::MacroInvocation(MacroInvocation {
-                                    macro_name: ident_str.to_string(),
-                                    args: lit_args,
-                                    span: AstSpan(curr_spans.clone()),
-                                }),
-                                span: AstSpan(curr_spans),
+                                span: mi.span.clone(),
+                                ty: StatementType::MacroInvocation(mi),
                             });
                         }
                         _ => {
                             tracing::info!(target: "parser", "LABEL CALL TO: {}", ident_str);
                             statements.push(Statement {
                                 ty: StatementType::LabelCall(ident_str),
-                                span: AstSpan(curr_spans),
+                                span: AstSpan(vec![ident_span]),
                             });
                         }
                     }
@@ -730,11 +883,21 @@ impl Parser {
                 }
                 TokenKind::LeftAngle => {
                     let (arg_call, arg_span) = self.parse_arg_call()?;
-                    tracing::info!(target: "parser", "PARSING LABEL BODY: [ARG CALL: {}]", arg_call);
-                    statements.push(Statement {
-                        ty: StatementType::ArgCall(arg_call),
-                        span: AstSpan(vec![arg_span]),
-                    });
+                    // Can be an arg call or an invocation of a macro passed as an argument
+                    if self.check(TokenKind::OpenParen) {
+                        tracing::info!(target: "parser", "PARSING LABEL BODY: [ARG MACRO INVOCATION: {}]", arg_call);
+                        let mi = self.parse_macro_invocation(arg_call, arg_span)?;
+                        statements.push(Statement {
+                            span: mi.span.clone(),
+                            ty: StatementType::ArgMacroInvocation(mi),
+                        });
+                    } else {
+                        tracing::info!(target: "parser", "PARSING LABEL BODY: [ARG CALL: {}]", arg_call);
+                        statements.push(Statement {
+                            ty: StatementType::ArgCall(arg_call),
+                            span: AstSpan(vec![arg_span]),
+                        });
+                    }
                 }
                 TokenKind::BuiltinFunction(f) => {
                     let mut curr_spans = vec![self.current_token.span.clone()];
@@ -751,6 +914,11 @@ impl Parser {
                         span: AstSpan(curr_spans),
                     });
                 }
+                TokenKind::If => {
+                    let conditional = self.parse_conditional()?;
+                    tracing::info!(target: "parser", "PARSING LABEL BODY: [{}]", conditional.ty);
+                    statements.push(conditional);
+                }
                 kind => {
                     tracing::error!(target: "parser", "TOKEN MISMATCH - LABEL BODY: {}", kind);
                     return Err(ParserError {
@@ -764,6 +932,67 @@ impl Parser {
         Ok(statements)
     }
 
+    /// Parse a conditional block.
+    ///
+    /// ## Examples
+    ///
+    /// The statements are compiled if the constant is non-zero, otherwise the optional `#else`
+    /// statements are compiled.
+    ///
+    /// ```huff
+    /// #if DEBUG
+    ///     0x00 0x00 log0
+    /// #else
+    ///     0x00
+    /// #endif
+    /// ```
+    pub fn parse_conditional(&mut self) -> Result<Statement, ParserError> {
+        let mut curr_spans = vec![self.current_token.span.clone()];
+        self.match_kind(TokenKind::If)?;
+
+        let condition = match self.current_token.kind.clone() {
+            TokenKind::Ident(condition) => {
+                curr_spans.push(self.current_token.span.clone());
+                self.consume();
+                condition
+            }
+            kind => {
+                tracing::error!(target: "parser", "INVALID CONDITION: {}", kind);
+                return Err(ParserError {
+                    kind: ParserErrorKind::InvalidConstant(kind),
+                    hint: Some("Expected a constant name.".to_string()),
+                    spans: AstSpan(vec![self.current_token.span.clone()]),
+                })
+            }
+        };
+
+        let statements = self.parse_statements()?;
+        statements.iter().for_each(|s| curr_spans.extend_from_slice(&s.span.0));
+
+        let else_statements = if self.check(TokenKind::Else) {
+            curr_spans.push(self.current_token.span.clone());
+            self.consume();
+            let else_statements = self.parse_statements()?;
+            else_statements.iter().for_each(|s| curr_spans.extend_from_slice(&s.span.0));
+            else_statements
+        } else {
+            vec![]
+        };
+
+        curr_spans.push(self.current_token.span.clone());
+        self.match_kind(TokenKind::Endif)?;
+
+        Ok(Statement {
+            ty: StatementType::Conditional(Conditional {
+                condition,
+                statements,
+                else_statements,
+                span: AstSpan(curr_spans.clone()),
+            }),
+            span: AstSpan(curr_spans),
+        })
+    }
+
     /// Parse new lines.
     ///
     /// No-return since newlines are non-essential.
@@ -801,6 +1030,7 @@ impl Parser {
                         name: Some(s.to_owned()), // Place the string in the "name" field
                         arg_type: None,
                         indexed: false,
+                        kind: None,
                         span: AstSpan(vec![self.current_token.span.clone()]),
                         arg_location: None,
                     });
@@ -809,8 +1039,8 @@ impl Parser {
                     continue
                 }
 
-                // The builtin function `__RIGHTPAD` can accept a single literal as input. If the
-                // `is_builtin` flag was passed, check to see if a single literal is
+                // The builtin functions `__RIGHTPAD` and `__REPEAT` can accept a literal as
+                // input. If the `is_builtin` flag was passed, check to see if a literal is
                 // present.
                 if let TokenKind::Literal(l) = &self.current_token.kind {
                     args.push(Argument {
@@ -819,10 +1049,14 @@ impl Parser {
                         arg_location: None,
                         arg_type: None,
                         indexed: false,
+                        kind: None,
                         span: AstSpan(vec![self.current_token.span.clone()]),
                     });
 
                     self.consume();
+                    if self.check(TokenKind::Comma) {
+                        self.consume();
+                    }
                     continue
                 }
             }
@@ -833,7 +1067,7 @@ impl Parser {
             // type comes first
             if select_type {
                 arg_spans.push(self.current_token.span.clone());
-                arg.arg_type = Some(self.parse_arg_type()?.to_string());
+                arg.arg_type = Some(self.parse_param_type()?);
                 // Check if the argument is indexed
                 if has_indexed && self.check(TokenKind::Indexed) {
                     arg.indexed = true;
@@ -863,6 +1097,32 @@ impl Parser {
                 _ => {}
             }
 
+            // Macro parameters can be preceded by their kind, ie. `label dest`
+            if select_name && !select_type {
+                if let (TokenKind::Ident(kind), Some(TokenKind::Ident(_))) =
+                    (self.current_token.kind.clone(), self.peek().map(|t| t.kind))
+                {
+                    let param_kind = match MacroParamKind::try_from(kind.as_str()) {
+                        Ok(k) => k,
+                        Err(_) => {
+                            tracing::error!(target: "parser", "INVALID MACRO PARAMETER KIND: {}", kind);
+                            return Err(ParserError {
+                                kind: ParserErrorKind::InvalidMacroParamKind(kind),
+                                hint: Some(
+                                    "Expected one of: label, literal, opcode, constant, macro"
+                                        .to_string(),
+                                ),
+                                spans: AstSpan(vec![self.current_token.span.clone()]),
+                            })
+                        }
+                    };
+                    arg_spans.push(self.current_token.span.clone());
+                    arg.kind = Some(param_kind);
+                    self.consume();
+                    on_type = false;
+                }
+            }
+
             // name comes second (is optional)
             if select_name &&
                 (self.check(TokenKind::Ident("x".to_string())) ||
@@ -946,6 +1206,22 @@ impl Parser {
         Ok(value)
     }
 
+    /// Parses the arguments of an invocation of the macro, or macro argument, named at the span.
+    ///
+    /// The invocation spans the name and everything up to the closing parenthesis.
+    pub fn parse_macro_invocation(
+        &mut self,
+        macro_name: String,
+        name_span: Span,
+    ) -> Result<MacroInvocation, ParserError> {
+        let args = self.parse_macro_call()?;
+        let mut spans = vec![name_span];
+        if let Some(i) = self.spans.iter().position(|s| s.eq(&spans[0])) {
+            spans.extend_from_slice(&self.spans[(i + 1)..]);
+        }
+        Ok(MacroInvocation { macro_name, args, span: AstSpan(spans) })
+    }
+
     /// Parse call to a macro.
     pub fn parse_macro_call(&mut self) -> Result<Vec<MacroArg>, ParserError> {
         self.parse_macro_call_args()
@@ -1189,6 +1465,44 @@ impl Parser {
         }
     }
 
+    /// Parses the type of a function, event, error or struct parameter into its canonical form,
+    /// ie. `(address,uint256)[]`. Tuples may be nested, and struct names are kept as declared.
+    pub fn parse_param_type(&mut self) -> Result<String, ParserError> {
+        let base = match self.current_token.kind.clone() {
+            TokenKind::OpenParen => {
+                self.consume();
+                let mut components = vec![];
+                while !self.check(TokenKind::CloseParen) {
+                    components.push(self.parse_param_type()?);
+                    if !self.check(TokenKind::CloseParen) {
+                        self.match_kind(TokenKind::Comma)?;
+                    }
+                }
+                self.match_kind(TokenKind::CloseParen)?;
+                format!("({})", components.join(","))
+            }
+            TokenKind::Ident(struct_name) => {
+                self.consume();
+                struct_name
+            }
+            _ => return Ok(self.parse_arg_type()?.to_string()),
+        };
+
+        // Tuples and structs can be arrayed, ie. `(address,uint256)[2][]`
+        let mut sizes = String::new();
+        while self.check(TokenKind::OpenBracket) {
+            self.consume();
+            sizes.push('[');
+            if let TokenKind::Num(size) = self.current_token.kind {
+                sizes.push_str(&size.to_string());
+                self.consume();
+            }
+            self.match_kind(TokenKind::CloseBracket)?;
+            sizes.push(']');
+        }
+        Ok(format!("{}{}", base, sizes))
+    }
+
     /// Parses a primitive EVM type.
     /// Arrays of primitive types are not considered as primitive types themselves.
     pub fn parse_primitive_type(
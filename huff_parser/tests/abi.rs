@@ -18,8 +18,11 @@ fn build_abi_from_ast() {
     let abi = Abi::from(contract);
 
     assert_eq!(
-        abi.functions.get("test").unwrap().inputs[0].kind,
+        abi.functions.get("test(uint256[2][],string)").unwrap().inputs[0].kind,
         FunctionParamType::Array(Box::new(FunctionParamType::Uint(256)), vec![2, 0])
     );
-    assert_eq!(abi.functions.get("test").unwrap().inputs[1].kind, FunctionParamType::String);
+    assert_eq!(
+        abi.functions.get("test(uint256[2][],string)").unwrap().inputs[1].kind,
+        FunctionParamType::String
+    );
 }
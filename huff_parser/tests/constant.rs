@@ -12,12 +12,12 @@ fn test_parses_free_storage_pointer_constant() {
     let contract = parser.parse().unwrap();
     assert_eq!(parser.current_token.kind, TokenKind::Eof);
 
-    let fsp_constant = contract.constants.lock().unwrap()[0].clone();
+    let fsp_constant = contract.constants[0].clone();
     assert_eq!(
         fsp_constant,
         ConstantDefinition {
             name: "FSP_LOCATION".to_string(),
-            value: ConstVal::FreeStoragePointer(FreeStoragePointer {}),
+            value: ConstVal::FreeStoragePointer(FreeStoragePointer { namespace: None }),
             span: AstSpan(vec![
                 Span { start: 0, end: 7, file: None },
                 Span { start: 8, end: 16, file: None },
@@ -29,6 +29,54 @@ fn test_parses_free_storage_pointer_constant() {
     );
 }
 
+#[test]
+fn test_parses_namespaced_free_storage_pointer_constant() {
+    let source = "#define constant FSP_LOCATION = FREE_STORAGE_POINTER(\"example.main\")";
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+    let contract = parser.parse().unwrap();
+    assert_eq!(parser.current_token.kind, TokenKind::Eof);
+
+    let fsp_constant = contract.constants[0].clone();
+    assert_eq!(
+        fsp_constant.value,
+        ConstVal::FreeStoragePointer(FreeStoragePointer {
+            namespace: Some("example.main".to_string())
+        })
+    );
+}
+
+#[test]
+fn test_parses_free_transient_and_memory_pointer_constants() {
+    let source = "#define constant LOCK = FREE_TRANSIENT_POINTER()\n#define constant SCRATCH = FREE_MEMORY_POINTER(0x40)";
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+    let contract = parser.parse().unwrap();
+    assert_eq!(parser.current_token.kind, TokenKind::Eof);
+
+    let constants = &contract.constants;
+    assert_eq!(constants[0].value, ConstVal::FreeTransientPointer(FreeTransientPointer));
+    assert_eq!(constants[1].value, ConstVal::FreeMemoryPointer(FreeMemoryPointer { size: 64 }));
+}
+
+#[test]
+fn test_free_memory_pointer_requires_size() {
+    let source = "#define constant SCRATCH = FREE_MEMORY_POINTER()";
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+
+    match parser.parse() {
+        Ok(_) => panic!("moose"),
+        Err(e) => assert_eq!(e.kind, ParserErrorKind::InvalidConstantValue(TokenKind::Eof)),
+    }
+}
+
 #[test]
 fn test_parses_literal_constant() {
     let source = "#define constant LITERAL = 0x8C5BE1E5EBEC7D5BD14F71427D1E84F3DD0314C0F7B2291E5B200AC8C7C3B925";
@@ -44,7 +92,7 @@ fn test_parses_literal_constant() {
         str_to_bytes32("8C5BE1E5EBEC7D5BD14F71427D1E84F3DD0314C0F7B2291E5B200AC8C7C3B925");
 
     // Check Literal
-    let fsp_constant = contract.constants.lock().unwrap()[0].clone();
+    let fsp_constant = contract.constants[0].clone();
     assert_eq!(
         fsp_constant,
         ConstantDefinition {
@@ -22,6 +22,7 @@ fn test_parses_custom_error() {
                 arg_type: Some(String::from("uint256")),
                 name: None,
                 indexed: false,
+                kind: None,
                 span: AstSpan(vec![Span { start: 24, end: 31, file: None }]),
                 arg_location: None,
             }],
@@ -24,6 +24,7 @@ fn parses_valid_function_definition() {
                         name: None,
                         arg_type: Some(String::from("uint256")),
                         indexed: false,
+                        kind: None,
                         arg_location: None,
                         span: AstSpan(vec![Span { start: 22, end: 29, file: None }]),
                     },
@@ -31,6 +32,7 @@ fn parses_valid_function_definition() {
                         name: Some(String::from("b")),
                         arg_type: Some(String::from("bool")),
                         indexed: false,
+                        kind: None,
                         arg_location: None,
                         span: AstSpan(vec![
                             Span { start: 30, end: 34, file: None },
@@ -43,6 +45,7 @@ fn parses_valid_function_definition() {
                     name: None,
                     arg_type: Some(String::from("uint256")),
                     indexed: false,
+                    kind: None,
                     arg_location: None,
                     span: AstSpan(vec![Span { start: 51, end: 58, file: None }]),
                 }],
@@ -73,6 +76,7 @@ fn parses_valid_function_definition() {
                     name: None,
                     arg_type: Some(String::from("uint256")),
                     indexed: false,
+                    kind: None,
                     arg_location: None,
                     span: AstSpan(vec![Span { start: 22, end: 29, file: None }]),
                 }],
@@ -81,6 +85,7 @@ fn parses_valid_function_definition() {
                     name: None,
                     arg_type: Some(String::from("uint256")),
                     indexed: false,
+                    kind: None,
                     arg_location: None,
                     span: AstSpan(vec![Span { start: 44, end: 51, file: None }]),
                 }],
@@ -108,6 +113,7 @@ fn parses_valid_function_definition() {
                     name: None,
                     arg_type: Some(String::from("uint256")),
                     indexed: false,
+                    kind: None,
                     arg_location: None,
                     span: AstSpan(vec![Span { start: 22, end: 29, file: None }]),
                 }],
@@ -116,6 +122,7 @@ fn parses_valid_function_definition() {
                     name: None,
                     arg_type: Some(String::from("uint256")),
                     indexed: false,
+                    kind: None,
                     arg_location: None,
                     span: AstSpan(vec![Span { start: 50, end: 57, file: None }]),
                 }],
@@ -143,6 +150,7 @@ fn parses_valid_function_definition() {
                     name: None,
                     arg_type: Some(String::from("uint256")),
                     indexed: false,
+                    kind: None,
                     arg_location: None,
                     span: AstSpan(vec![Span { start: 22, end: 29, file: None }]),
                 }],
@@ -151,6 +159,7 @@ fn parses_valid_function_definition() {
                     name: None,
                     arg_type: Some(String::from("uint256")),
                     indexed: false,
+                    kind: None,
                     arg_location: None,
                     span: AstSpan(vec![Span { start: 47, end: 54, file: None }]),
                 }],
@@ -178,6 +187,7 @@ fn parses_valid_function_definition() {
                     name: None,
                     arg_type: Some(String::from("uint256[], bool[5]")),
                     indexed: false,
+                    kind: None,
                     arg_location: None,
                     span: AstSpan(vec![]),
                 }],
@@ -186,6 +196,7 @@ fn parses_valid_function_definition() {
                     name: None,
                     arg_type: Some(String::from("uint256")),
                     indexed: false,
+                    kind: None,
                     arg_location: None,
                     span: AstSpan(vec![]),
                 }],
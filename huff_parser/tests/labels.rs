@@ -225,6 +225,7 @@ pub fn builtins_under_labels() {
                                 arg_type: None,
                                 name: Some(String::from("TEST_TABLE")),
                                 indexed: false,
+                                kind: None,
                                 arg_location: None,
                                 span: AstSpan(vec![Span { start: 342, end: 352, file: None }]),
                             }],
@@ -245,6 +246,7 @@ pub fn builtins_under_labels() {
                                 arg_type: None,
                                 name: Some(String::from("TEST_TABLE")),
                                 indexed: false,
+                                kind: None,
                                 arg_location: None,
                                 span: AstSpan(vec![Span { start: 378, end: 388, file: None }]),
                             }],
@@ -265,6 +267,7 @@ pub fn builtins_under_labels() {
                                 arg_type: None,
                                 name: Some(String::from("SMALL_MACRO")),
                                 indexed: false,
+                                kind: None,
                                 arg_location: None,
                                 span: AstSpan(vec![Span { start: 413, end: 424, file: None }]),
                             }],
@@ -285,6 +288,7 @@ pub fn builtins_under_labels() {
                                 arg_type: None,
                                 name: Some(String::from("myFunc")),
                                 indexed: false,
+                                kind: None,
                                 arg_location: None,
                                 span: AstSpan(vec![Span { start: 449, end: 455, file: None }]),
                             }],
@@ -305,6 +309,7 @@ pub fn builtins_under_labels() {
                                 arg_type: None,
                                 name: Some(String::from("TestError")),
                                 indexed: false,
+                                kind: None,
                                 arg_location: None,
                                 span: AstSpan(vec![Span { start: 477, end: 486, file: None }]),
                             }],
@@ -325,6 +330,7 @@ pub fn builtins_under_labels() {
                                 arg_type: None,
                                 name: Some(String::from("bb")),
                                 indexed: false,
+                                kind: None,
                                 arg_location: None,
                                 span: AstSpan(vec![Span { start: 513, end: 515, file: None }]),
                             }],
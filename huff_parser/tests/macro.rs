@@ -261,6 +261,7 @@ fn macro_with_arg_calls() {
             arg_type: None,
             name: Some("error".to_string()),
             indexed: false,
+            kind: None,
             arg_location: None,
             span: AstSpan(vec![Span { start: 67, end: 72, file: None }]),
         }],
@@ -608,6 +609,7 @@ fn macro_invocation_with_arg_call() {
             arg_type: None,
             name: Some("error".to_string()),
             indexed: false,
+            kind: None,
             arg_location: None,
             span: AstSpan(vec![Span { start: 28, end: 33, file: None }]),
         }],
@@ -824,6 +826,7 @@ fn macro_with_builtin_fn_call() {
                     arg_type: None,
                     name: Some("TEST".to_string()),
                     indexed: false,
+                    kind: None,
                     arg_location: None,
                     span: AstSpan(vec![Span { start: 77, end: 81, file: None }]),
                 }],
@@ -1229,3 +1232,202 @@ fn empty_test_with_multi_flag_decorator() {
     assert_eq!(macro_definition, expected);
     assert_eq!(parser.current_token.kind, TokenKind::Eof);
 }
+
+#[test]
+fn macro_with_arg_macro_invocation() {
+    let source = "#define macro APPLY(body) = takes(0) returns(0) { <body>(0x01) }";
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+
+    let macro_definition = parser.parse().unwrap().macros[0].clone();
+    let span = AstSpan(vec![
+        Span { start: 51, end: 55, file: None },
+        Span { start: 55, end: 56, file: None },
+        Span { start: 56, end: 57, file: None },
+        Span { start: 59, end: 61, file: None },
+        Span { start: 61, end: 62, file: None },
+    ]);
+    assert_eq!(
+        macro_definition.statements,
+        vec![Statement {
+            ty: StatementType::ArgMacroInvocation(MacroInvocation {
+                macro_name: "body".to_string(),
+                args: vec![MacroArg::Literal(str_to_bytes32("01"))],
+                span: span.clone(),
+            }),
+            span,
+        }]
+    );
+}
+
+#[test]
+fn macro_with_conditional() {
+    let source = "#define macro MAIN() = takes(0) returns(0) { #if DEBUG 0x01 #else pop #endif }";
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+
+    let macro_definition = parser.parse().unwrap().macros[0].clone();
+    let span = AstSpan(vec![
+        Span { start: 45, end: 48, file: None },
+        Span { start: 49, end: 54, file: None },
+        Span { start: 57, end: 59, file: None },
+        Span { start: 60, end: 65, file: None },
+        Span { start: 66, end: 69, file: None },
+        Span { start: 70, end: 76, file: None },
+    ]);
+    assert_eq!(
+        macro_definition.statements,
+        vec![Statement {
+            ty: StatementType::Conditional(Conditional {
+                condition: "DEBUG".to_string(),
+                statements: vec![Statement {
+                    ty: StatementType::Literal(str_to_bytes32("01")),
+                    span: AstSpan(vec![Span { start: 57, end: 59, file: None }]),
+                }],
+                else_statements: vec![Statement {
+                    ty: StatementType::Opcode(Opcode::Pop),
+                    span: AstSpan(vec![Span { start: 66, end: 69, file: None }]),
+                }],
+                span: span.clone(),
+            }),
+            span,
+        }]
+    );
+}
+
+#[test]
+fn macro_with_unterminated_conditional() {
+    let source = "#define macro MAIN() = takes(0) returns(0) { #if DEBUG 0x01 }";
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+
+    match parser.parse() {
+        Ok(_) => panic!("moose"),
+        Err(e) => assert_eq!(e.kind, ParserErrorKind::UnexpectedType(TokenKind::CloseBrace)),
+    }
+}
+
+#[test]
+fn macro_with_typed_parameters() {
+    let source = "#define macro M(label dest, opcode op, size) = takes(0) returns(0) {}";
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+
+    let macro_definition = parser.parse().unwrap().macros[0].clone();
+    assert_eq!(
+        macro_definition.parameters,
+        vec![
+            Argument {
+                arg_type: None,
+                arg_location: None,
+                name: Some("dest".to_string()),
+                indexed: false,
+                kind: Some(MacroParamKind::Label),
+                span: AstSpan(vec![
+                    Span { start: 16, end: 21, file: None },
+                    Span { start: 22, end: 26, file: None },
+                ]),
+            },
+            Argument {
+                arg_type: None,
+                arg_location: None,
+                name: Some("op".to_string()),
+                indexed: false,
+                kind: Some(MacroParamKind::Opcode),
+                span: AstSpan(vec![
+                    Span { start: 28, end: 34, file: None },
+                    Span { start: 35, end: 37, file: None },
+                ]),
+            },
+            Argument {
+                arg_type: None,
+                arg_location: None,
+                name: Some("size".to_string()),
+                indexed: false,
+                kind: None,
+                span: AstSpan(vec![Span { start: 39, end: 43, file: None }]),
+            },
+        ]
+    );
+}
+
+#[test]
+fn macro_with_invalid_parameter_kind() {
+    let source = "#define macro M(word dest) = takes(0) returns(0) {}";
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+
+    match parser.parse() {
+        Ok(_) => panic!("moose"),
+        Err(e) => assert_eq!(
+            e,
+            ParserError {
+                kind: ParserErrorKind::InvalidMacroParamKind("word".to_string()),
+                hint: Some("Expected one of: label, literal, opcode, constant, macro".to_string()),
+                spans: AstSpan(vec![Span { start: 16, end: 20, file: None }]),
+            }
+        ),
+    }
+}
+
+#[test]
+fn macro_invoked_with_literal_for_label_parameter() {
+    let source = r#"
+    #define macro GOTO(label dest) = takes(0) returns(0) { <dest> jump }
+    #define macro MAIN() = takes(0) returns(0) { GOTO(0x01) }
+    "#;
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+
+    match parser.parse() {
+        Ok(_) => panic!("moose"),
+        Err(e) => assert_eq!(
+            e,
+            ParserError {
+                kind: ParserErrorKind::MacroArgumentKindMismatch(
+                    MacroParamKind::Label,
+                    "dest".to_string()
+                ),
+                hint: Some("Expected a label argument".to_string()),
+                spans: AstSpan(vec![
+                    Span { start: 123, end: 127, file: None },
+                    Span { start: 127, end: 128, file: None },
+                    Span { start: 130, end: 132, file: None },
+                    Span { start: 132, end: 133, file: None },
+                ]),
+            }
+        ),
+    }
+}
+
+#[test]
+fn macro_invoked_with_non_opcode_for_opcode_parameter() {
+    let source = r#"
+    #define macro APPLY(opcode op) = takes(2) returns(1) { <op> }
+    #define macro MAIN() = takes(0) returns(0) { 0x01 0x02 APPLY(add) APPLY(OFFSET) }
+    "#;
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+
+    match parser.parse() {
+        Ok(_) => panic!("moose"),
+        Err(e) => assert_eq!(
+            e.kind,
+            ParserErrorKind::MacroArgumentKindMismatch(MacroParamKind::Opcode, "op".to_string())
+        ),
+    }
+}
@@ -1,6 +1,7 @@
 use huff_lexer::*;
 use huff_parser::*;
 use huff_utils::prelude::*;
+use std::collections::BTreeMap;
 
 #[test]
 fn derives_storage_pointers() {
@@ -11,16 +12,16 @@ fn derives_storage_pointers() {
     let lexer = Lexer::new(flattened_source);
     let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
     let mut parser = Parser::new(tokens, None);
-    let mut contract = parser.parse().unwrap();
+    let contract = parser.parse().unwrap();
     assert_eq!(parser.current_token.kind, TokenKind::Eof);
 
     // Ensure that the constant definitions were parsed correctly
-    let fsp_constant = contract.constants.lock().unwrap()[0].clone();
+    let fsp_constant = contract.constants[0].clone();
     assert_eq!(
         fsp_constant,
         ConstantDefinition {
             name: "FSP_LOCATION".to_string(),
-            value: ConstVal::FreeStoragePointer(FreeStoragePointer {}),
+            value: ConstVal::FreeStoragePointer(FreeStoragePointer { namespace: None }),
             span: AstSpan(vec![
                 Span { start: 0, end: 7, file: None },
                 Span { start: 8, end: 16, file: None },
@@ -31,12 +32,12 @@ fn derives_storage_pointers() {
         }
     );
 
-    let fsp_constant = contract.constants.lock().unwrap()[1].clone();
+    let fsp_constant = contract.constants[1].clone();
     assert_eq!(
         fsp_constant,
         ConstantDefinition {
             name: "FSP_LOCATION_2".to_string(),
-            value: ConstVal::FreeStoragePointer(FreeStoragePointer {}),
+            value: ConstVal::FreeStoragePointer(FreeStoragePointer { namespace: None }),
             span: AstSpan(vec![
                 Span { start: 55, end: 62, file: None },
                 Span { start: 63, end: 71, file: None },
@@ -47,7 +48,7 @@ fn derives_storage_pointers() {
         }
     );
 
-    let num_constant = contract.constants.lock().unwrap()[2].clone();
+    let num_constant = contract.constants[2].clone();
     assert_eq!(
         num_constant,
         ConstantDefinition {
@@ -63,20 +64,299 @@ fn derives_storage_pointers() {
         }
     );
 
-    // Derive the AST's free storage pointers
-    contract.derive_storage_pointers();
+    // Derive the AST's free storage pointers, which aren't referenced from MAIN or CONSTRUCTOR
+    let env = ConstantEnv::new(&contract, None).unwrap();
+    let value =
+        |env: &ConstantEnv, name: &str| env.find_constant_by_name(name).unwrap().value.clone();
+    assert_eq!(
+        value(&env, "FSP_LOCATION"),
+        ConstVal::FreeStoragePointer(FreeStoragePointer { namespace: None })
+    );
+    assert_eq!(
+        value(&env, "FSP_LOCATION_2"),
+        ConstVal::FreeStoragePointer(FreeStoragePointer { namespace: None })
+    );
+    assert_eq!(value(&env, "NUM"), ConstVal::Literal(str_to_bytes32("a57B")));
+
+    // In definition order, the storage pointers are set whether or not they are referenced
+    let env = ConstantEnv::with_allocation(
+        &contract,
+        None,
+        Allocation { storage_order: StorageOrder::Definition, ..Default::default() },
+    )
+    .unwrap();
+    assert_eq!(value(&env, "FSP_LOCATION"), ConstVal::Literal(str_to_bytes32("0")));
+    assert_eq!(value(&env, "FSP_LOCATION_2"), ConstVal::Literal(str_to_bytes32("1")));
+    assert_eq!(value(&env, "NUM"), ConstVal::Literal(str_to_bytes32("a57B")));
+
+    // The AST itself is left untouched
+    assert_eq!(
+        contract.constants[0].value,
+        ConstVal::FreeStoragePointer(FreeStoragePointer { namespace: None })
+    );
+}
+
+#[test]
+fn applies_constant_overrides() {
+    let source =
+        "#define constant FSP_LOCATION = FREE_STORAGE_POINTER()\n#define constant NUM = 0x01";
+
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+    let contract = parser.parse().unwrap();
+
+    let overrides =
+        BTreeMap::from([("FSP_LOCATION", str_to_bytes32("ff")), ("EXTRA", str_to_bytes32("02"))]);
+    let allocation = Allocation { storage_order: StorageOrder::Definition, ..Default::default() };
+    let env = ConstantEnv::with_allocation(&contract, Some(&overrides), allocation).unwrap();
+    let default_env = ConstantEnv::with_allocation(&contract, None, allocation).unwrap();
+
+    // Overrides replace derived pointers and add missing constants
+    let value =
+        |env: &ConstantEnv, name: &str| env.find_constant_by_name(name).map(|c| c.value.clone());
+    assert_eq!(value(&env, "FSP_LOCATION"), Some(ConstVal::Literal(str_to_bytes32("ff"))));
+    assert_eq!(value(&env, "NUM"), Some(ConstVal::Literal(str_to_bytes32("01"))));
+    assert_eq!(value(&env, "EXTRA"), Some(ConstVal::Literal(str_to_bytes32("02"))));
+
+    // Environments are independent of each other
+    assert_eq!(value(&default_env, "FSP_LOCATION"), Some(ConstVal::Literal(str_to_bytes32("0"))));
+    assert_eq!(value(&default_env, "EXTRA"), None);
+}
+
+#[test]
+fn derives_storage_layout() {
+    let source = r#"
+    #define constant OWNER = FREE_STORAGE_POINTER()
+    #define constant BALANCE = FREE_STORAGE_POINTER()
+    #define constant NUM = 0x01
+    #define constant MAIN_STORAGE = FREE_STORAGE_POINTER("example.main")
+    #define constant UNUSED = FREE_STORAGE_POINTER()
+
+    #define macro MAIN() = takes(0) returns (0) {
+        [MAIN_STORAGE] sload
+        [BALANCE] sload
+        [OWNER] sload
+        [NUM]
+    }
+    "#;
 
-    // Ensure that the storage pointers were set for the FSP constants in the AST
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+    let contract = parser.parse().unwrap();
+
+    // By default, slots are assigned in the order pointers are referenced, and unreferenced
+    // pointers are left without a slot
+    let layout = ConstantEnv::new(&contract, None).unwrap().layout;
+    assert_eq!(
+        layout.storage.iter().map(|s| (s.constant.as_str(), s.slot.as_str())).collect::<Vec<_>>(),
+        vec![
+            ("BALANCE", "0x00"),
+            ("OWNER", "0x01"),
+            ("MAIN_STORAGE", "0x183a6125c38840424c4a85fa12bab2ab606c4b6d0e7cc73c0c06ba5300eab500"),
+        ]
+    );
+
+    let layout = ConstantEnv::with_allocation(
+        &contract,
+        None,
+        Allocation { storage_order: StorageOrder::Definition, ..Default::default() },
+    )
+    .unwrap()
+    .layout;
+
+    // In definition order, slots are assigned regardless of references, namespaced pointers use
+    // their ERC-7201 slot, and literal constants are excluded
     assert_eq!(
-        contract.constants.lock().unwrap()[0].value,
-        ConstVal::FreeStoragePointer(FreeStoragePointer)
+        layout.storage.iter().map(|s| (s.constant.as_str(), s.slot.as_str())).collect::<Vec<_>>(),
+        vec![
+            ("OWNER", "0x00"),
+            ("BALANCE", "0x01"),
+            ("UNUSED", "0x02"),
+            ("MAIN_STORAGE", "0x183a6125c38840424c4a85fa12bab2ab606c4b6d0e7cc73c0c06ba5300eab500"),
+        ]
+    );
+    assert_eq!(layout.storage[0].span, SourceSpan { file: None, start: 5, end: 52 });
+}
+
+#[test]
+fn derives_storage_slots_past_nine_in_decimal() {
+    // MAIN references the pointers in reverse
+    let source = format!(
+        "{}#define macro MAIN() = takes (0) returns (0) {{ {} }}",
+        (0..12)
+            .map(|i| format!("#define constant SLOT_{} = FREE_STORAGE_POINTER()\n", i))
+            .collect::<String>(),
+        (0..12).rev().map(|i| format!("[SLOT_{}] ", i)).collect::<String>()
     );
+
+    let flattened_source = FullFileSource { source: &source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+    let contract = parser.parse().unwrap();
+    let env = ConstantEnv::new(&contract, None).unwrap();
+
+    // The eleventh and twelfth referenced pointers are slots 0x10 and 0x11, as in earlier versions
     assert_eq!(
-        contract.constants.lock().unwrap()[1].value,
-        ConstVal::FreeStoragePointer(FreeStoragePointer)
+        env.layout.storage.iter().map(|s| s.slot.as_str()).collect::<Vec<_>>(),
+        vec![
+            "0x00", "0x01", "0x02", "0x03", "0x04", "0x05", "0x06", "0x07", "0x08", "0x09", "0x10",
+            "0x11"
+        ]
     );
+    let value = |name: &str| env.find_constant_by_name(name).unwrap().value.clone();
+    assert_eq!(value("SLOT_11"), ConstVal::Literal(str_to_bytes32("00")));
+    assert_eq!(value("SLOT_1"), ConstVal::Literal(str_to_bytes32("10")));
+    assert_eq!(value("SLOT_0"), ConstVal::Literal(str_to_bytes32("11")));
+}
+
+#[test]
+fn derives_storage_slots_past_nine_as_hex() {
+    let source = (0..12)
+        .map(|i| format!("#define constant SLOT_{} = FREE_STORAGE_POINTER()\n", i))
+        .collect::<String>();
+
+    let flattened_source = FullFileSource { source: &source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+    let contract = parser.parse().unwrap();
+    let env = ConstantEnv::with_allocation(
+        &contract,
+        None,
+        Allocation { storage_order: StorageOrder::Definition, ..Default::default() },
+    )
+    .unwrap();
+
+    // The eleventh and twelfth pointers are slots 0x0a and 0x0b, not 0x10 and 0x11
+    assert_eq!(
+        env.layout.storage.iter().map(|s| s.slot.as_str()).collect::<Vec<_>>(),
+        vec![
+            "0x00", "0x01", "0x02", "0x03", "0x04", "0x05", "0x06", "0x07", "0x08", "0x09", "0x0a",
+            "0x0b"
+        ]
+    );
+    let value = |name: &str| env.find_constant_by_name(name).unwrap().value.clone();
+    assert_eq!(value("SLOT_10"), ConstVal::Literal(str_to_bytes32("0a")));
+    assert_eq!(value("SLOT_11"), ConstVal::Literal(str_to_bytes32("0b")));
+}
+
+#[test]
+fn derives_transient_and_memory_pointers() {
+    let source = r#"
+    #define constant LOCK = FREE_TRANSIENT_POINTER()
+    #define constant OWNER = FREE_STORAGE_POINTER()
+    #define constant SCRATCH = FREE_MEMORY_POINTER(0x40)
+    #define constant BUFFER = FREE_MEMORY_POINTER(96)
+    #define constant CALLER = FREE_TRANSIENT_POINTER()
+    #define constant WORD = FREE_MEMORY_POINTER(0x20)
+    "#;
+
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+    let contract = parser.parse().unwrap();
+
+    let env = ConstantEnv::with_allocation(
+        &contract,
+        None,
+        Allocation { storage_order: StorageOrder::Definition, ..Default::default() },
+    )
+    .unwrap();
+    let layout = &env.layout;
+
+    // Transient slots are allocated independently of storage slots
+    assert_eq!(
+        layout.storage.iter().map(|s| (s.constant.as_str(), s.slot.as_str())).collect::<Vec<_>>(),
+        vec![("OWNER", "0x00")]
+    );
+    assert_eq!(
+        layout.transient.iter().map(|s| (s.constant.as_str(), s.slot.as_str())).collect::<Vec<_>>(),
+        vec![("LOCK", "0x00"), ("CALLER", "0x01")]
+    );
+
+    // Memory regions are laid out consecutively from the memory base
+    assert_eq!(
+        layout
+            .memory
+            .iter()
+            .map(|m| (m.constant.as_str(), m.offset.as_str(), m.size))
+            .collect::<Vec<_>>(),
+        vec![("SCRATCH", "0x80", 64), ("BUFFER", "0xc0", 96), ("WORD", "0x0120", 32)]
+    );
+
+    let value = |name: &str| env.find_constant_by_name(name).unwrap().value.clone();
+    assert_eq!(value("BUFFER"), ConstVal::Literal(str_to_bytes32("c0")));
+    assert_eq!(value("CALLER"), ConstVal::Literal(str_to_bytes32("01")));
+    assert_eq!(value("WORD"), ConstVal::Literal(str_to_bytes32("0120")));
+}
+
+#[test]
+fn memory_regions_start_at_the_memory_base() {
+    let source = r#"
+    #define constant SCRATCH = FREE_MEMORY_POINTER(0x20)
+    #define constant WORD = FREE_MEMORY_POINTER(0x20)
+    "#;
+
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+    let contract = parser.parse().unwrap();
+
+    let offsets = |env: ConstantEnv| {
+        env.layout.memory.iter().map(|m| m.offset.clone()).collect::<Vec<String>>()
+    };
+
+    // By default, regions don't overlap the scratch space, free memory pointer and zero slot
+    assert_eq!(MEMORY_BASE, 0x80);
+    assert_eq!(offsets(ConstantEnv::new(&contract, None).unwrap()), vec!["0x80", "0xa0"]);
+
+    let allocation = Allocation { memory_base: 0x0200, ..Default::default() };
+    assert_eq!(
+        offsets(ConstantEnv::with_allocation(&contract, None, allocation).unwrap()),
+        vec!["0x0200", "0x0220"]
+    );
+}
+
+#[test]
+fn memory_pointers_past_the_memory_bound() {
+    let source = r#"
+    #define constant HEAP = FREE_MEMORY_POINTER(0xffffff80)
+    #define constant WORD = FREE_MEMORY_POINTER(0x20)
+    "#;
+
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+    let contract = parser.parse().unwrap();
+
+    match ConstantEnv::new(&contract, None) {
+        Ok(_) => panic!("moose"),
+        Err(e) => assert_eq!(
+            e,
+            CodegenError {
+                kind: CodegenErrorKind::MemoryPointerOutOfBounds("WORD".to_string()),
+                span: contract.constants[1].span.clone(),
+                token: None,
+                backtrace: Box::default(),
+            }
+        ),
+    }
+}
+
+#[test]
+fn parses_storage_orders() {
+    assert_eq!(StorageOrder::try_from("traversal"), Ok(StorageOrder::Traversal));
+    assert_eq!(StorageOrder::try_from("definition"), Ok(StorageOrder::Definition));
     assert_eq!(
-        contract.constants.lock().unwrap()[2].value,
-        ConstVal::Literal(str_to_bytes32("a57B"))
+        StorageOrder::try_from("alphabetical"),
+        Err("Unknown storage order \"alphabetical\", expected definition or traversal".to_string())
     );
 }
@@ -0,0 +1,125 @@
+use huff_lexer::*;
+use huff_parser::*;
+use huff_utils::prelude::*;
+
+#[test]
+fn test_parses_tuple_types() {
+    let source = r#"
+        #define function aggregate((address,bytes)[] calls) payable returns (uint256, bytes[])
+        #define function tryAggregate(bool, (address, bytes)[]) payable returns ((bool,bytes)[2])
+        "#;
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+    let contract = parser.parse().unwrap();
+
+    let types = |args: &[Argument]| {
+        args.iter().map(|a| a.arg_type.clone().unwrap()).collect::<Vec<String>>()
+    };
+    assert_eq!(types(&contract.functions[0].inputs), vec!["(address,bytes)[]"]);
+    assert_eq!(contract.functions[0].inputs[0].name, Some("calls".to_string()));
+    assert_eq!(contract.functions[0].signature, [0x25, 0x2d, 0xba, 0x42]);
+    assert_eq!(types(&contract.functions[1].inputs), vec!["bool", "(address,bytes)[]"]);
+    assert_eq!(types(&contract.functions[1].outputs), vec!["(bool,bytes)[2]"]);
+    assert_eq!(contract.functions[1].signature, [0xbc, 0xe3, 0x8b, 0xd7]);
+}
+
+#[test]
+fn test_parses_struct_definition() {
+    let source = r#"
+        #define function exactInputSingle(ExactInputSingleParams params) payable returns (uint256)
+
+        #define struct ExactInputSingleParams(
+            address tokenIn,
+            address tokenOut,
+            uint24 fee,
+            address recipient,
+            uint256 deadline,
+            uint256 amountIn,
+            uint256 amountOutMinimum,
+            uint160 sqrtPriceLimitX96
+        )
+        "#;
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+    let mut contract = parser.parse().unwrap();
+
+    let definition = &contract.structs[0];
+    assert_eq!(definition.name, "ExactInputSingleParams");
+    assert_eq!(
+        definition
+            .fields
+            .iter()
+            .map(|f| (f.arg_type.clone().unwrap(), f.name.clone().unwrap()))
+            .collect::<Vec<(String, String)>>(),
+        vec![
+            ("address".to_string(), "tokenIn".to_string()),
+            ("address".to_string(), "tokenOut".to_string()),
+            ("uint24".to_string(), "fee".to_string()),
+            ("address".to_string(), "recipient".to_string()),
+            ("uint256".to_string(), "deadline".to_string()),
+            ("uint256".to_string(), "amountIn".to_string()),
+            ("uint256".to_string(), "amountOutMinimum".to_string()),
+            ("uint160".to_string(), "sqrtPriceLimitX96".to_string()),
+        ]
+    );
+
+    // The struct is expanded into a tuple for the selector, whether defined before or after use
+    contract.resolve_signatures().unwrap();
+    let function = &contract.functions[0];
+    assert_eq!(function.inputs[0].arg_type, Some("ExactInputSingleParams".to_string()));
+    assert_eq!(
+        contract.signature(&function.name, &function.inputs),
+        "exactInputSingle((address,address,uint24,address,uint256,uint256,uint256,uint160))"
+    );
+    assert_eq!(function.signature, [0x41, 0x4b, 0xf3, 0x89]);
+}
+
+#[test]
+fn test_nested_struct_arrays() {
+    let source = r#"
+        #define struct Call(address target, bytes callData)
+        #define struct Batch(Call[] calls, uint256 deadline)
+        #define error Failed(Batch[2] batches, (uint8,Call) last)
+        "#;
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+    let mut contract = parser.parse().unwrap();
+
+    let error = &contract.errors[0];
+    assert_eq!(
+        contract.signature(&error.name, &error.parameters),
+        "Failed(((address,bytes)[],uint256)[2],(uint8,(address,bytes)))"
+    );
+    assert!(contract.resolve_signatures().is_ok());
+}
+
+#[test]
+fn test_unknown_struct() {
+    let source = r#"
+        #define struct Node(Node[] children)
+        #define event Created(Position indexed)
+        "#;
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+    let mut contract = parser.parse().unwrap();
+
+    match contract.resolve_signatures() {
+        Err(e) => assert_eq!(e.kind, ParserErrorKind::UnknownType("Position".to_string())),
+        Ok(_) => panic!("moose"),
+    }
+
+    // Recursive structs can't be encoded
+    contract.events.clear();
+    match contract.resolve_signatures() {
+        Err(e) => assert_eq!(e.kind, ParserErrorKind::UnknownType("Node[]".to_string())),
+        Ok(_) => panic!("moose"),
+    }
+}
@@ -1,5 +1,5 @@
 use crate::{errors::RunnerError, runner::TestRunner, types::TestResult};
-use huff_utils::prelude::{Contract, MacroDefinition};
+use huff_utils::prelude::{ConstantEnv, Contract, MacroDefinition};
 use std::{borrow::Borrow, rc::Rc};
 
 /// The runner module
@@ -37,6 +37,9 @@ pub struct HuffTester<'t> {
     /// The AST of the contract
     pub ast: &'t Contract,
 
+    /// The resolved constants of the contract
+    pub env: &'t ConstantEnv,
+
     /// The test macros
     pub macros: TestMacros<'t>,
 
@@ -46,10 +49,11 @@ pub struct HuffTester<'t> {
 
 /// HuffTester implementation
 impl<'t> HuffTester<'t> {
-    /// Create a new instance of `HuffTester` from a contract's AST.
-    pub fn new(ast: &'t Contract, match_: Rc<Option<String>>) -> Self {
+    /// Create a new instance of `HuffTester` from a contract's AST and resolved constants.
+    pub fn new(ast: &'t Contract, env: &'t ConstantEnv, match_: Rc<Option<String>>) -> Self {
         Self {
             ast,
+            env,
             macros: {
                 // Filter all macros within the AST for `test` macros only
                 let mut macros: TestMacros<'t> = ast.macros.iter().filter(|m| m.test).collect();
@@ -74,7 +78,7 @@ impl<'t> HuffTester<'t> {
         // Execute our tests and return a vector of the results
         self.macros
             .into_iter()
-            .map(|macro_def| self.runner.run_test(macro_def, self.ast))
+            .map(|macro_def| self.runner.run_test(macro_def, self.ast, self.env))
             .collect::<Result<Vec<TestResult>, RunnerError>>()
     }
 }
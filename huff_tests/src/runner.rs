@@ -4,7 +4,7 @@ use ethers::{prelude::Address, types::U256, utils::hex};
 use huff_codegen::Codegen;
 use huff_utils::{
     ast::{DecoratorFlag, MacroDefinition},
-    prelude::{pad_n_bytes, CompilerError, Contract},
+    prelude::{pad_n_bytes, CompilerError, ConstantEnv, Contract},
 };
 use revm::{
     return_ok, return_revert, BlockEnv, CfgEnv, CreateScheme, Database, Env, InMemoryDB, Return,
@@ -158,13 +158,15 @@ impl TestRunner {
         &mut self,
         m: &MacroDefinition,
         contract: &Contract,
+        env: &ConstantEnv,
     ) -> Result<TestResult, RunnerError> {
         let name = m.name.to_owned();
 
         // Compile the passed test macro
         match Codegen::macro_to_bytecode(
-            m.to_owned(),
+            m,
             contract,
+            env,
             &mut vec![m.to_owned()],
             0,
             &mut Vec::default(),
@@ -9,7 +9,6 @@
 //! instance like so:
 //!
 //! ```rust
-//! use std::sync::{Arc, Mutex};
 //! use huff_utils::prelude::*;
 //!
 //! // Generate a default contract for demonstrative purposes.
@@ -18,7 +17,7 @@
 //!     macros: vec![],
 //!     invocations: vec![],
 //!     imports: vec![],
-//!     constants: Arc::new(Mutex::new(vec![])),
+//!     constants: vec![],
 //!     errors: vec![],
 //!     functions: vec![huff_utils::ast::Function {
 //!         name: "CONSTRUCTOR".to_string(),
@@ -30,6 +29,8 @@
 //!     }],
 //!     events: vec![],
 //!     tables: vec![],
+//!     structs: vec![],
+//!     symbols: SymbolIndex::default(),
 //! };
 //!
 //! // Create an ABI using that generate contract
@@ -39,20 +40,27 @@
 use serde::{Deserialize, Serialize};
 use std::{collections::BTreeMap, fmt};
 
-use crate::ast::{self, FunctionType};
+use crate::{
+    ast::{self, FunctionType},
+    bytes_util::hash_bytes,
+    types::PrimitiveEVMType,
+};
 
 /// #### Abi
 ///
 /// The ABI of the generated code.
+///
+/// Serialized as, and deserialized from, a standard JSON ABI array of [AbiItem]s.
 #[derive(Default, Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(into = "Vec<AbiItem>", try_from = "Vec<AbiItem>")]
 pub struct Abi {
     /// The constructor
     pub constructor: Option<Constructor>,
-    /// A list of functions and their definitions
+    /// The functions by signature, eg. `transfer(address,uint256)`, so that overloads are kept
     pub functions: BTreeMap<String, Function>,
-    /// A list of events and their definitions
+    /// The events by signature
     pub events: BTreeMap<String, Event>,
-    /// A list of errors and their definitions
+    /// The errors by signature
     pub errors: BTreeMap<String, Error>,
     /// If the contract defines receive logic
     pub receive: bool,
@@ -65,11 +73,324 @@ impl Abi {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// Serializes the Abi as a pretty-printed standard JSON ABI array
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Deserializes a standard JSON ABI array, or the `abi` of a JSON artifact such as those of
+    /// Foundry and Hardhat
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        let value: serde_json::Value = serde_json::from_str(json)?;
+        match value.get("abi") {
+            Some(abi) => serde_json::from_value(abi.clone()),
+            None => serde_json::from_value(value),
+        }
+    }
+}
+
+/// #### AbiItem
+///
+/// An entry of a standard JSON ABI array, as consumed by ethers, viem, Foundry and block
+/// explorers.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum AbiItem {
+    /// A function
+    Function {
+        /// The function name
+        name: String,
+        /// The function inputs
+        inputs: Vec<AbiParam>,
+        /// The function outputs
+        #[serde(default)]
+        outputs: Vec<AbiParam>,
+        /// The state mutability
+        #[serde(rename = "stateMutability", default = "non_payable")]
+        state_mutability: FunctionType,
+    },
+    /// An event
+    Event {
+        /// The event name
+        name: String,
+        /// The event inputs
+        inputs: Vec<AbiParam>,
+        /// Anonymity
+        #[serde(default)]
+        anonymous: bool,
+    },
+    /// A custom error
+    Error {
+        /// The error name
+        name: String,
+        /// The error inputs
+        inputs: Vec<AbiParam>,
+    },
+    /// The constructor
+    Constructor {
+        /// The constructor inputs
+        inputs: Vec<AbiParam>,
+        /// The state mutability
+        #[serde(rename = "stateMutability", default = "non_payable")]
+        state_mutability: FunctionType,
+    },
+    /// The receive function
+    Receive {
+        /// The state mutability, always `payable`
+        #[serde(rename = "stateMutability", default = "payable")]
+        state_mutability: FunctionType,
+    },
+    /// The fallback function
+    Fallback {
+        /// The state mutability
+        #[serde(rename = "stateMutability", default = "non_payable")]
+        state_mutability: FunctionType,
+    },
+}
+
+/// The default state mutability of JSON ABI functions
+fn non_payable() -> FunctionType {
+    FunctionType::NonPayable
+}
+
+/// The state mutability of the receive function
+fn payable() -> FunctionType {
+    FunctionType::Payable
+}
+
+/// #### AbiParam
+///
+/// A parameter of a standard JSON ABI entry. Tuples are described by their `components`.
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq, Eq, Clone)]
+pub struct AbiParam {
+    /// The parameter name, empty if the ABI omits it
+    #[serde(default)]
+    pub name: String,
+    /// The canonical type, eg. `uint256` or `tuple[]`
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// The internal type of the parameter
+    #[serde(rename = "internalType", default, skip_serializing_if = "Option::is_none")]
+    pub internal_type: Option<String>,
+    /// The components of a tuple
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub components: Option<Vec<AbiParam>>,
+    /// If an event parameter is indexed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub indexed: Option<bool>,
+}
+
+impl AbiParam {
+    /// Public associated function to instantiate a new AbiParam from a parameter type.
+    pub fn new(name: &str, kind: &FunctionParamType, internal_type: Option<String>) -> Self {
+        let (base, sizes) = match kind {
+            FunctionParamType::Array(inner, sizes) => (inner.as_ref(), sizes.as_slice()),
+            kind => (kind, &[][..]),
+        };
+        match base {
+            FunctionParamType::Tuple(inner) => Self {
+                name: name.to_string(),
+                kind: format!("tuple{}", array_suffix(sizes)),
+                internal_type,
+                components: Some(inner.iter().map(|k| AbiParam::new("", k, None)).collect()),
+                indexed: None,
+            },
+            FunctionParamType::Struct(struct_name, fields) => Self {
+                name: name.to_string(),
+                kind: format!("tuple{}", array_suffix(sizes)),
+                internal_type: internal_type
+                    .or_else(|| Some(format!("struct {}{}", struct_name, array_suffix(sizes)))),
+                components: Some(fields.iter().map(|(n, k)| AbiParam::new(n, k, None)).collect()),
+                indexed: None,
+            },
+            _ => Self {
+                name: name.to_string(),
+                kind: kind.to_string(),
+                internal_type,
+                components: None,
+                indexed: None,
+            },
+        }
+    }
+
+    /// Resolves the parameter type, including the components of tuples
+    pub fn param_type(&self) -> Result<FunctionParamType, String> {
+        let suffix = match self.kind.strip_prefix("tuple") {
+            Some(suffix) => suffix,
+            None => {
+                return FunctionParamType::convert_string_to_type(&self.kind)
+                    .map_err(|e| e.to_string())
+            }
+        };
+        let components = self
+            .components
+            .iter()
+            .flatten()
+            .map(|c| c.param_type().map(|kind| (c.name.clone(), kind)))
+            .collect::<Result<Vec<(String, FunctionParamType)>, String>>()?;
+        // Solidity describes structs by their internal type, ie. `struct Lib.Position[]`
+        let struct_name = self
+            .internal_type
+            .as_deref()
+            .and_then(|t| t.strip_prefix("struct "))
+            .map(|t| t.split('[').next().unwrap_or_default())
+            .map(|t| t.rsplit('.').next().unwrap_or_default().to_string());
+        let tuple = match struct_name {
+            Some(struct_name) => FunctionParamType::Struct(struct_name, components),
+            None => FunctionParamType::Tuple(components.into_iter().map(|(_, k)| k).collect()),
+        };
+        if suffix.is_empty() {
+            return Ok(tuple)
+        }
+        let sizes = parse_array_suffix(suffix)
+            .ok_or_else(|| format!("Invalid tuple type: {}", self.kind))?;
+        Ok(FunctionParamType::Array(Box::new(tuple), sizes))
+    }
+}
+
+impl From<&FunctionParam> for AbiParam {
+    fn from(param: &FunctionParam) -> Self {
+        AbiParam::new(&param.name, &param.kind, param.internal_type.clone())
+    }
+}
+
+impl From<&EventParam> for AbiParam {
+    fn from(param: &EventParam) -> Self {
+        Self { indexed: Some(param.indexed), ..AbiParam::new(&param.name, &param.kind, None) }
+    }
+}
+
+impl TryFrom<&AbiParam> for FunctionParam {
+    type Error = String;
+
+    fn try_from(param: &AbiParam) -> Result<Self, String> {
+        Ok(Self {
+            name: param.name.clone(),
+            kind: param.param_type()?,
+            internal_type: param.internal_type.clone(),
+        })
+    }
+}
+
+impl TryFrom<&AbiParam> for EventParam {
+    type Error = String;
+
+    fn try_from(param: &AbiParam) -> Result<Self, String> {
+        Ok(Self {
+            name: param.name.clone(),
+            kind: param.param_type()?,
+            indexed: param.indexed.unwrap_or_default(),
+        })
+    }
+}
+
+impl From<Abi> for Vec<AbiItem> {
+    fn from(abi: Abi) -> Self {
+        let mut items = vec![];
+        if let Some(constructor) = abi.constructor {
+            items.push(AbiItem::Constructor {
+                inputs: constructor.inputs.iter().map(AbiParam::from).collect(),
+                state_mutability: FunctionType::NonPayable,
+            });
+        }
+        items.extend(abi.functions.into_values().map(|f| AbiItem::Function {
+            inputs: f.inputs.iter().map(AbiParam::from).collect(),
+            outputs: f.outputs.iter().map(AbiParam::from).collect(),
+            name: f.name,
+            state_mutability: f.state_mutability,
+        }));
+        items.extend(abi.events.into_values().map(|e| AbiItem::Event {
+            inputs: e.inputs.iter().map(AbiParam::from).collect(),
+            name: e.name,
+            anonymous: e.anonymous,
+        }));
+        items.extend(abi.errors.into_values().map(|e| AbiItem::Error {
+            inputs: e.inputs.iter().map(AbiParam::from).collect(),
+            name: e.name,
+        }));
+        if abi.receive {
+            items.push(AbiItem::Receive { state_mutability: FunctionType::Payable });
+        }
+        if abi.fallback {
+            items.push(AbiItem::Fallback { state_mutability: FunctionType::NonPayable });
+        }
+        items
+    }
+}
+
+impl TryFrom<Vec<AbiItem>> for Abi {
+    type Error = String;
+
+    fn try_from(items: Vec<AbiItem>) -> Result<Self, String> {
+        let mut abi = Abi::new();
+        for item in items {
+            match item {
+                AbiItem::Function { name, inputs, outputs, state_mutability } => {
+                    let function = Function {
+                        name,
+                        inputs: inputs
+                            .iter()
+                            .map(FunctionParam::try_from)
+                            .collect::<Result<_, _>>()?,
+                        outputs: outputs
+                            .iter()
+                            .map(FunctionParam::try_from)
+                            .collect::<Result<_, _>>()?,
+                        constant: matches!(
+                            state_mutability,
+                            FunctionType::View | FunctionType::Pure
+                        ),
+                        state_mutability,
+                    };
+                    abi.functions.insert(function.signature(), function);
+                }
+                AbiItem::Event { name, inputs, anonymous } => {
+                    let event = Event {
+                        name,
+                        inputs: inputs
+                            .iter()
+                            .map(EventParam::try_from)
+                            .collect::<Result<_, _>>()?,
+                        anonymous,
+                    };
+                    abi.events.insert(event.signature(), event);
+                }
+                AbiItem::Error { name, inputs } => {
+                    let error = Error {
+                        name,
+                        inputs: inputs
+                            .iter()
+                            .map(FunctionParam::try_from)
+                            .collect::<Result<_, _>>()?,
+                    };
+                    abi.errors.insert(error.signature(), error);
+                }
+                AbiItem::Constructor { inputs, .. } => {
+                    abi.constructor = Some(Constructor {
+                        inputs: inputs
+                            .iter()
+                            .map(FunctionParam::try_from)
+                            .collect::<Result<_, _>>()?,
+                    });
+                }
+                AbiItem::Receive { .. } => abi.receive = true,
+                AbiItem::Fallback { .. } => abi.fallback = true,
+            }
+        }
+        Ok(abi)
+    }
 }
 
 // Allows for simple ABI Generation by directly translating the AST
 impl From<ast::Contract> for Abi {
     fn from(contract: ast::Contract) -> Self {
+        // Struct names are expanded into their fields
+        let param_type = |argument: &ast::Argument| {
+            let arg_type = argument.arg_type.clone().unwrap_or_default();
+            contract.param_type(&arg_type).unwrap_or_else(|| arg_type.into())
+        };
+
         // Try to get the constructor inputs from an overriden function
         // Otherwise, use the CONSTRUCTOR macro if one exists
         let constructor = contract
@@ -85,7 +406,7 @@ impl From<ast::Contract> for Abi {
                     .iter()
                     .map(|argument| FunctionParam {
                         name: argument.name.clone().unwrap_or_default(),
-                        kind: argument.arg_type.clone().unwrap_or_default().into(),
+                        kind: param_type(argument),
                         internal_type: None,
                     })
                     .collect(),
@@ -123,79 +444,184 @@ impl From<ast::Contract> for Abi {
                 .functions
                 .iter()
                 .filter(|function: &&ast::Function| function.name != "CONSTRUCTOR")
-                .map(|function| {
-                    (
-                        function.name.to_string(),
-                        Function {
-                            name: function.name.to_string(),
-                            inputs: function
-                                .inputs
-                                .iter()
-                                .map(|argument| FunctionParam {
-                                    name: argument.name.clone().unwrap_or_default(),
-                                    kind: argument.arg_type.clone().unwrap_or_default().into(),
-                                    internal_type: None,
-                                })
-                                .collect(),
-                            outputs: function
-                                .outputs
-                                .iter()
-                                .map(|argument| FunctionParam {
-                                    name: argument.name.clone().unwrap_or_default(),
-                                    kind: argument.arg_type.clone().unwrap_or_default().into(),
-                                    internal_type: None,
-                                })
-                                .collect(),
-                            constant: false,
-                            state_mutability: function.fn_type.clone(),
-                        },
-                    )
-                }),
+                .map(|function| Function {
+                    name: function.name.to_string(),
+                    inputs: function
+                        .inputs
+                        .iter()
+                        .map(|argument| FunctionParam {
+                            name: argument.name.clone().unwrap_or_default(),
+                            kind: param_type(argument),
+                            internal_type: None,
+                        })
+                        .collect(),
+                    outputs: function
+                        .outputs
+                        .iter()
+                        .map(|argument| FunctionParam {
+                            name: argument.name.clone().unwrap_or_default(),
+                            kind: param_type(argument),
+                            internal_type: None,
+                        })
+                        .collect(),
+                    constant: false,
+                    state_mutability: function.fn_type.clone(),
+                })
+                .map(|function| (function.signature(), function)),
         );
 
         // Translate contract events
-        events.extend(contract.events.iter().map(|event| {
-            (
-                event.name.to_string(),
-                Event {
+        events.extend(
+            contract
+                .events
+                .iter()
+                .map(|event| Event {
                     name: event.name.to_string(),
                     inputs: event
                         .parameters
                         .iter()
                         .map(|argument| EventParam {
                             name: argument.name.clone().unwrap_or_default(),
-                            kind: argument.arg_type.clone().unwrap_or_default().into(),
+                            kind: param_type(argument),
                             indexed: argument.indexed,
                         })
                         .collect(),
                     anonymous: false,
-                },
-            )
-        }));
+                })
+                .map(|event| (event.signature(), event)),
+        );
 
         // Translate contract errors
-        errors.extend(contract.errors.iter().map(|error| {
-            (
-                error.name.to_string(),
-                Error {
+        errors.extend(
+            contract
+                .errors
+                .iter()
+                .map(|error| Error {
                     name: error.name.to_string(),
                     inputs: error
                         .parameters
                         .iter()
                         .map(|argument| FunctionParam {
                             name: argument.name.clone().unwrap_or_default(),
-                            kind: argument.arg_type.clone().unwrap_or_default().into(),
+                            kind: param_type(argument),
                             internal_type: None,
                         })
                         .collect(),
-                },
-            )
-        }));
+                })
+                .map(|error| (error.signature(), error)),
+        );
 
         Self { constructor, functions, events, errors, receive: false, fallback: false }
     }
 }
 
+// Synthesizes the declarations of an ABI, ie. of an included `.abi.json` file
+impl From<&Abi> for ast::Contract {
+    fn from(abi: &Abi) -> Self {
+        let mut contract = ast::Contract::default();
+        let mut structs = vec![];
+
+        for function in abi.functions.values() {
+            let inputs = function
+                .inputs
+                .iter()
+                .map(|i| declare_argument(&i.name, &i.kind, false, &mut structs))
+                .collect();
+            let outputs = function
+                .outputs
+                .iter()
+                .map(|o| declare_argument(&o.name, &o.kind, false, &mut structs))
+                .collect();
+            contract.functions.push(ast::Function {
+                name: function.name.clone(),
+                signature: function.selector(),
+                inputs,
+                fn_type: function.state_mutability.clone(),
+                outputs,
+                span: ast::AstSpan(vec![]),
+            });
+        }
+
+        for event in abi.events.values() {
+            let mut hash = [0u8; 32];
+            hash_bytes(&mut hash, &event.signature());
+            let parameters = event
+                .inputs
+                .iter()
+                .map(|i| declare_argument(&i.name, &i.kind, i.indexed, &mut structs))
+                .collect();
+            contract.events.push(ast::Event {
+                name: event.name.clone(),
+                parameters,
+                span: ast::AstSpan(vec![]),
+                hash,
+            });
+        }
+
+        for error in abi.errors.values() {
+            let parameters = error
+                .inputs
+                .iter()
+                .map(|i| declare_argument(&i.name, &i.kind, false, &mut structs))
+                .collect();
+            contract.errors.push(ast::ErrorDefinition {
+                name: error.name.clone(),
+                selector: error.selector(),
+                parameters,
+                span: ast::AstSpan(vec![]),
+            });
+        }
+
+        contract.structs = structs;
+        contract
+    }
+}
+
+/// Declares a parameter as an AST argument, collecting the structs its type uses
+fn declare_argument(
+    name: &str,
+    kind: &FunctionParamType,
+    indexed: bool,
+    structs: &mut Vec<ast::StructDefinition>,
+) -> ast::Argument {
+    ast::Argument {
+        arg_type: Some(declare_type(kind, structs)),
+        arg_location: None,
+        name: if name.is_empty() { None } else { Some(name.to_string()) },
+        indexed,
+        kind: None,
+        span: ast::AstSpan(vec![]),
+    }
+}
+
+/// Renders a parameter type as declared in Huff, ie. `Position[]`, collecting the structs it uses
+fn declare_type(kind: &FunctionParamType, structs: &mut Vec<ast::StructDefinition>) -> String {
+    match kind {
+        FunctionParamType::Array(inner, sizes) => {
+            format!("{}{}", declare_type(inner, structs), array_suffix(sizes))
+        }
+        FunctionParamType::Tuple(inner) => format!(
+            "({})",
+            inner.iter().map(|k| declare_type(k, structs)).collect::<Vec<_>>().join(",")
+        ),
+        FunctionParamType::Struct(name, fields) => {
+            if !structs.iter().any(|s| &s.name == name) {
+                let fields = fields
+                    .iter()
+                    .map(|(field, k)| declare_argument(field, k, false, structs))
+                    .collect();
+                structs.push(ast::StructDefinition {
+                    name: name.clone(),
+                    fields,
+                    span: ast::AstSpan(vec![]),
+                });
+            }
+            name.clone()
+        }
+        kind => kind.to_string(),
+    }
+}
+
 /// #### Function
 ///
 /// A function definition.
@@ -213,6 +639,20 @@ pub struct Function {
     pub state_mutability: FunctionType,
 }
 
+impl Function {
+    /// Returns the canonical function signature, eg. `transfer(address,uint256)`
+    pub fn signature(&self) -> String {
+        canonical_signature(&self.name, self.inputs.iter().map(|i| &i.kind))
+    }
+
+    /// Computes the 4-byte function selector
+    pub fn selector(&self) -> [u8; 4] {
+        let mut selector = [0u8; 4];
+        hash_bytes(&mut selector, &self.signature());
+        selector
+    }
+}
+
 /// #### Event
 ///
 /// An Event definition.
@@ -226,6 +666,13 @@ pub struct Event {
     pub anonymous: bool,
 }
 
+impl Event {
+    /// Returns the canonical event signature, eg. `Transfer(address,address,uint256)`
+    pub fn signature(&self) -> String {
+        canonical_signature(&self.name, self.inputs.iter().map(|i| &i.kind))
+    }
+}
+
 /// #### EventParam
 ///
 /// Event parameters.
@@ -250,6 +697,29 @@ pub struct Error {
     pub inputs: Vec<FunctionParam>,
 }
 
+impl Error {
+    /// Returns the canonical error signature, eg. `InsufficientBalance(uint256)`
+    pub fn signature(&self) -> String {
+        canonical_signature(&self.name, self.inputs.iter().map(|i| &i.kind))
+    }
+
+    /// Computes the 4-byte error selector
+    pub fn selector(&self) -> [u8; 4] {
+        let mut selector = [0u8; 4];
+        hash_bytes(&mut selector, &self.signature());
+        selector
+    }
+}
+
+/// Builds a canonical signature string from a name and its parameter types
+fn canonical_signature<'a>(
+    name: &str,
+    kinds: impl Iterator<Item = &'a FunctionParamType>,
+) -> String {
+    let types = kinds.map(|kind| kind.to_string().replace(' ', "")).collect::<Vec<_>>();
+    format!("{}({})", name, types.join(","))
+}
+
 /// #### Constructor
 ///
 /// The contract constructor
@@ -295,17 +765,20 @@ pub enum FunctionParamType {
     FixedBytes(usize),
     /// A tuple of parameters
     Tuple(Vec<FunctionParamType>),
+    /// A named struct and its fields, encoded as a tuple
+    Struct(String, Vec<(String, FunctionParamType)>),
 }
 
 impl FunctionParamType {
-    /// Checks if the param type should be designated as "memory" for solidity interface
-    /// generation.
+    /// Checks if the param type needs a data location, "calldata" or "memory", for solidity
+    /// interface generation.
     pub fn is_memory_type(&self) -> bool {
         matches!(
             self,
             FunctionParamType::Bytes |
                 FunctionParamType::String |
                 FunctionParamType::Tuple(_) |
+                FunctionParamType::Struct(_, _) |
                 FunctionParamType::Array(_, _)
         )
     }
@@ -333,29 +806,58 @@ impl FunctionParamType {
             FunctionParamType::Uint(size) => write!(f, "uint{}", size),
             FunctionParamType::Bool => write!(f, "bool"),
             FunctionParamType::String => write!(f, "string"),
-            FunctionParamType::Array(fpt, sizes) => write!(
-                f,
-                "{}{}",
-                fpt,
-                sizes
-                    .iter()
-                    .map(|s| (!s.eq(&0))
-                        .then(|| format!("[{}]", s))
-                        .unwrap_or_else(|| "[]".to_string()))
-                    .collect::<Vec<_>>()
-                    .join("")
-            ),
+            FunctionParamType::Array(fpt, sizes) => write!(f, "{}{}", fpt, array_suffix(sizes)),
             FunctionParamType::FixedBytes(size) => write!(f, "bytes{}", size),
             FunctionParamType::Tuple(inner) => write!(
                 f,
                 "({})",
                 inner.iter().map(|fpt| fpt.to_string()).collect::<Vec<_>>().join(", ")
             ),
+            FunctionParamType::Struct(_, fields) => write!(
+                f,
+                "({})",
+                fields.iter().map(|(_, fpt)| fpt.to_string()).collect::<Vec<_>>().join(", ")
+            ),
         }
     }
 
+    /// Parses a type string such as `(address,uint256)[]`, resolving identifiers that aren't EVM
+    /// types, ie. struct names, with `resolve`.
+    pub fn parse_with(
+        string: &str,
+        resolve: &dyn Fn(&str) -> Option<FunctionParamType>,
+    ) -> Option<Self> {
+        let string = string.trim();
+        let base_end = match string.starts_with('(') {
+            true => closing_paren(string)? + 1,
+            false => string.find('[').unwrap_or(string.len()),
+        };
+        let (base, suffix) = string.split_at(base_end);
+        let kind = match base.strip_prefix('(').and_then(|b| b.strip_suffix(')')) {
+            Some(inner) => FunctionParamType::Tuple(
+                split_components(inner)
+                    .into_iter()
+                    .map(|c| FunctionParamType::parse_with(c, resolve))
+                    .collect::<Option<Vec<_>>>()?,
+            ),
+            None => match PrimitiveEVMType::try_from(base.to_string()) {
+                Ok(_) => FunctionParamType::convert_string_to_type(base).ok()?,
+                Err(_) => resolve(base)?,
+            },
+        };
+        if suffix.is_empty() {
+            return Some(kind)
+        }
+        Some(FunctionParamType::Array(Box::new(kind), parse_array_suffix(suffix)?))
+    }
+
     /// Convert string to type
     pub fn convert_string_to_type(string: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        if string.starts_with('(') {
+            return FunctionParamType::parse_with(string, &|_| None).ok_or_else(|| {
+                format!("Failed to create FunctionParamType from string: {}", string).into()
+            })
+        }
         let input = string.to_string().to_lowercase();
         let split_input: Vec<&str> = input.split('[').collect();
         if split_input.len() > 1 {
@@ -423,3 +925,58 @@ impl From<String> for FunctionParamType {
         FunctionParamType::convert_string_to_type(&string).unwrap()
     }
 }
+
+/// Formats array dimensions, eg. `[2][]`, where a size of `0` is a dynamic array
+pub(crate) fn array_suffix(sizes: &[usize]) -> String {
+    sizes
+        .iter()
+        .map(|s| if s.eq(&0) { "[]".to_string() } else { format!("[{}]", s) })
+        .collect::<Vec<_>>()
+        .join("")
+}
+
+/// Parses array dimensions, eg. `[2][]`, where a dynamic array has a size of `0`
+fn parse_array_suffix(suffix: &str) -> Option<Vec<usize>> {
+    suffix
+        .strip_prefix('[')?
+        .strip_suffix(']')?
+        .split("][")
+        .map(|size| if size.is_empty() { Some(0) } else { size.parse().ok() })
+        .collect()
+}
+
+/// Returns the position of the parenthesis closing the one a type string starts with
+fn closing_paren(string: &str) -> Option<usize> {
+    let mut depth = 0;
+    for (i, c) in string.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' if depth == 1 => return Some(i),
+            ')' => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Splits the inside of a tuple type string at its top-level commas
+fn split_components(inner: &str) -> Vec<&str> {
+    if inner.trim().is_empty() {
+        return vec![]
+    }
+    let mut components = vec![];
+    let (mut depth, mut start) = (0, 0);
+    for (i, c) in inner.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                components.push(&inner[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    components.push(&inner[start..]);
+    components
+}
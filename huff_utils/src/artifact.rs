@@ -3,10 +3,13 @@
 //! The artifacts generated from codegen.
 
 use serde::{Deserialize, Serialize};
-use std::{fs, path::Path, sync::Arc};
+use serde_json::json;
+use std::{collections::BTreeMap, fmt, fs, path::Path, sync::Arc};
 
 pub use crate::abi::Abi;
-use crate::prelude::FileSource;
+use crate::prelude::{
+    compress_source_map, str_to_vec, FileSource, MemoryRegion, SourceMapEntry, StorageSlot,
+};
 
 /// A Codegen Artifact
 #[derive(Default, Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
@@ -19,12 +22,86 @@ pub struct Artifact {
     pub runtime: String,
     /// The abi
     pub abi: Option<Abi>,
+    /// The function selectors, event topics and error selectors by signature
+    #[serde(flatten)]
+    pub selectors: Selectors,
+    /// The storage slots assigned to free storage pointer constants
+    #[serde(rename = "storageLayout", default)]
+    pub storage_layout: Vec<StorageSlot>,
+    /// The transient storage slots assigned to free transient pointer constants
+    #[serde(rename = "transientLayout", default)]
+    pub transient_layout: Vec<StorageSlot>,
+    /// The memory regions assigned to free memory pointer constants
+    #[serde(rename = "memoryLayout", default)]
+    pub memory_layout: Vec<MemoryRegion>,
+    /// The source map of the deployed bytecode
+    #[serde(rename = "sourceMap", default)]
+    pub source_map: Vec<SourceMapEntry>,
+    /// The source map of the runtime bytecode
+    #[serde(rename = "runtimeSourceMap", default)]
+    pub runtime_source_map: Vec<SourceMapEntry>,
+}
+
+/// The selectors of a contract's functions and errors, and the topics of its events, keyed by
+/// their signatures
+#[derive(Default, Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct Selectors {
+    /// The function selectors, ie. `"transfer(address,uint256)": "a9059cbb"`
+    #[serde(rename = "methodIdentifiers", default)]
+    pub method_identifiers: BTreeMap<String, String>,
+    /// The event topics, ie. `"Transfer(address,address,uint256)": "ddf2...b3ef"`
+    #[serde(rename = "eventTopics", default)]
+    pub event_topics: BTreeMap<String, String>,
+    /// The error selectors, ie. `"Unauthorized(address)": "8e4a23d6"`
+    #[serde(rename = "errorSelectors", default)]
+    pub error_selectors: BTreeMap<String, String>,
+}
+
+impl Selectors {
+    /// Serializes the selector tables as a JSON object
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+impl fmt::Display for Selectors {
+    /// Formats the selector tables as a table of kinds, selectors and signatures
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let rows = [
+            ("function", &self.method_identifiers),
+            ("event", &self.event_topics),
+            ("error", &self.error_selectors),
+        ];
+        let width = rows
+            .iter()
+            .flat_map(|(_, table)| table.values().map(|s| s.len() + 2))
+            .chain(std::iter::once("Selector".len()))
+            .max()
+            .unwrap_or_default();
+        writeln!(f, "{:<8}  {:<width$}  Signature", "Kind", "Selector", width = width)?;
+        for (kind, table) in rows {
+            for (signature, selector) in table {
+                let selector = format!("0x{}", selector);
+                writeln!(f, "{:<8}  {:<width$}  {}", kind, selector, signature, width = width)?;
+            }
+        }
+        Ok(())
+    }
 }
 
 impl Artifact {
     /// Exports an artifact to a json file
     pub fn export(&self, out: &str) -> std::result::Result<(), std::io::Error> {
-        let serialized_artifact = serde_json::to_string_pretty(self)?;
+        self.export_as(out, ArtifactFormat::Huff)
+    }
+
+    /// Exports an artifact to a json file in the given format
+    pub fn export_as(
+        &self,
+        out: &str,
+        format: ArtifactFormat,
+    ) -> std::result::Result<(), std::io::Error> {
+        let serialized_artifact = format.emit(self)?;
         let file_path = Path::new(out);
         if let Some(p) = file_path.parent() {
             tracing::debug!(target: "abi", "Creating directory: \"{:?}\"", p);
@@ -32,4 +109,127 @@ impl Artifact {
         }
         fs::write(file_path, serialized_artifact)
     }
+
+    /// Imports an artifact from a json file
+    pub fn import(path: &str) -> std::result::Result<Self, std::io::Error> {
+        let contents = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&contents)?)
+    }
+
+    /// The name of the artifact's contract, ie. `ERC20` for `./contracts/ERC20.huff`
+    pub fn contract_name(&self) -> String {
+        Path::new(&self.file.path)
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default()
+    }
+
+    /// The paths of the artifact's file and its dependencies, depth first, as indexed by
+    /// [compress_source_map]
+    pub fn source_files(&self) -> Vec<String> {
+        fn collect(file: &FileSource, files: &mut Vec<String>) {
+            if files.contains(&file.path) {
+                return
+            }
+            files.push(file.path.clone());
+            file.dependencies.iter().flatten().for_each(|d| collect(d, files));
+        }
+        let mut files = vec![];
+        collect(&self.file, &mut files);
+        files
+    }
+}
+
+/// The JSON layout artifacts are exported in
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ArtifactFormat {
+    /// The [Artifact] itself, which cached artifacts are read from
+    #[default]
+    Huff,
+    /// Foundry's `out/<File>/<Contract>.json` layout, as read by `vm.getCode`
+    Foundry,
+    /// Hardhat's `hh-sol-artifact-1` layout, as read by `getContractFactory`
+    Hardhat,
+}
+
+impl TryFrom<&str> for ArtifactFormat {
+    type Error = String;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        match s {
+            "huff" => Ok(ArtifactFormat::Huff),
+            "foundry" => Ok(ArtifactFormat::Foundry),
+            "hardhat" => Ok(ArtifactFormat::Hardhat),
+            _ => {
+                Err(format!("Unknown artifact format \"{}\", expected foundry, hardhat or huff", s))
+            }
+        }
+    }
+}
+
+impl ArtifactFormat {
+    /// The path of an artifact exported to the output directory
+    pub fn path(&self, output_dir: &str, artifact: &Artifact) -> String {
+        match self {
+            ArtifactFormat::Huff => format!(
+                "{}/{}.json",
+                output_dir,
+                artifact.file.path.to_uppercase().replacen("./", "", 1)
+            ),
+            // Foundry keys artifacts by file name, ie. `out/Token.huff/Token.json`
+            ArtifactFormat::Foundry => format!(
+                "{}/{}/{}.json",
+                output_dir,
+                Path::new(&artifact.file.path).file_name().unwrap_or_default().to_string_lossy(),
+                artifact.contract_name()
+            ),
+            ArtifactFormat::Hardhat => format!(
+                "{}/{}/{}.json",
+                output_dir,
+                artifact.file.path.replacen("./", "", 1),
+                artifact.contract_name()
+            ),
+        }
+    }
+
+    /// Serializes an artifact in the format
+    pub fn emit(&self, artifact: &Artifact) -> Result<String, serde_json::Error> {
+        let abi = serde_json::to_value(artifact.abi.clone().unwrap_or_default())?;
+        let value = match self {
+            ArtifactFormat::Huff => return serde_json::to_string_pretty(artifact),
+            ArtifactFormat::Foundry => {
+                let files = artifact.source_files();
+                let source_map = |code: &str, source_map: &[SourceMapEntry]| {
+                    let code = str_to_vec(code).unwrap_or_default();
+                    compress_source_map(&code, source_map, &files)
+                };
+                json!({
+                    "abi": abi,
+                    "bytecode": {
+                        "object": format!("0x{}", artifact.bytecode),
+                        "sourceMap": source_map(&artifact.bytecode, &artifact.source_map),
+                        "linkReferences": {},
+                    },
+                    "deployedBytecode": {
+                        "object": format!("0x{}", artifact.runtime),
+                        "sourceMap": source_map(&artifact.runtime, &artifact.runtime_source_map),
+                        "linkReferences": {},
+                        "immutableReferences": {},
+                    },
+                    "methodIdentifiers": artifact.selectors.method_identifiers,
+                })
+            }
+            ArtifactFormat::Hardhat => json!({
+                "_format": "hh-sol-artifact-1",
+                "contractName": artifact.contract_name(),
+                "sourceName": artifact.file.path.replacen("./", "", 1),
+                "abi": abi,
+                "bytecode": format!("0x{}", artifact.bytecode),
+                "deployedBytecode": format!("0x{}", artifact.runtime),
+                "linkReferences": {},
+                "deployedLinkReferences": {},
+            }),
+        };
+        serde_json::to_string_pretty(&value)
+    }
 }
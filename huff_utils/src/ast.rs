@@ -2,17 +2,19 @@ use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 
 use crate::{
+    abi::FunctionParamType,
+    artifact::Selectors,
     bytecode::*,
     bytes_util::*,
-    error::CodegenError,
+    error::{CodegenError, ParserError, ParserErrorKind},
     evm::Opcode,
-    prelude::{Span, TokenKind},
+    prelude::{FileSource, Span, TokenKind},
 };
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, HashMap},
     fmt::{Display, Formatter},
     path::PathBuf,
-    sync::{Arc, Mutex},
+    sync::Arc,
 };
 
 /// A contained literal
@@ -73,6 +75,34 @@ impl AstSpan {
         )
     }
 
+    /// Attributes the spans without a file to the given file.
+    ///
+    /// Spans that already have a file, ie. from an included file, keep it.
+    pub fn with_default_file(self, file: &Arc<FileSource>) -> Self {
+        let mut spans = self
+            .0
+            .into_iter()
+            .map(|mut s| {
+                if s.file.is_none() {
+                    s.file = Some(Arc::clone(file));
+                }
+                s
+            })
+            .collect::<Vec<Span>>();
+        spans.dedup();
+        AstSpan(spans)
+    }
+
+    /// The file and line of the first span in a file, ie. `./src/ERC20.huff:12`
+    pub fn location(&self) -> Option<String> {
+        let span = self.0.iter().find(|s| s.file.is_some())?;
+        let path = &span.file.as_ref()?.path;
+        Some(match span.line() {
+            Some(line) => format!("{}:{}", path, line),
+            None => path.clone(),
+        })
+    }
+
     /// Print just the file for missing
     pub fn file(&self) -> String {
         self.0.iter().fold("".to_string(), |acc, span| match &span.file {
@@ -96,8 +126,9 @@ pub struct Contract {
     pub invocations: Vec<MacroInvocation>,
     /// File Imports
     pub imports: Vec<FilePath>,
-    /// Constants
-    pub constants: Arc<Mutex<Vec<ConstantDefinition>>>,
+    /// Constants, as defined in the source. See [ConstantEnv](crate::env::ConstantEnv) for their
+    /// resolved values.
+    pub constants: Vec<ConstantDefinition>,
     /// Custom Errors
     pub errors: Vec<ErrorDefinition>,
     /// Functions
@@ -106,219 +137,186 @@ pub struct Contract {
     pub events: Vec<Event>,
     /// Tables
     pub tables: Vec<TableDefinition>,
+    /// Structs
+    pub structs: Vec<StructDefinition>,
+    /// Indexes of the macros and tables by name
+    pub symbols: SymbolIndex,
+}
+
+/// Indexes of a Contract's definitions by name
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SymbolIndex {
+    /// Positions of the macros in `Contract::macros`
+    pub macros: HashMap<String, usize>,
+    /// Positions of the tables in `Contract::tables`
+    pub tables: HashMap<String, usize>,
 }
 
 impl Contract {
-    /// Returns the first macro that matches the provided name
-    pub fn find_macro_by_name(&self, name: &str) -> Option<MacroDefinition> {
-        if let Some(m) = self.macros.iter().find(|m| m.name == name) {
-            Some(m.clone())
-        } else {
-            tracing::warn!("Failed to find macro \"{}\" in contract", name);
-            None
+    /// Indexes the macros and tables by name.
+    ///
+    /// Must be called again if the macros or tables are modified after parsing.
+    pub fn index_symbols(&mut self) {
+        let mut symbols = SymbolIndex::default();
+        for (i, m) in self.macros.iter().enumerate() {
+            symbols.macros.entry(m.name.clone()).or_insert(i);
+        }
+        for (i, t) in self.tables.iter().enumerate() {
+            symbols.tables.entry(t.name.clone()).or_insert(i);
         }
+        self.symbols = symbols;
     }
 
-    /// Returns the first table that matches the provided name
-    pub fn find_table_by_name(&self, name: &str) -> Option<TableDefinition> {
-        if let Some(t) = self.tables.iter().find(|t| t.name == name) {
-            Some(t.clone())
-        } else {
-            tracing::warn!("Failed to find table \"{}\" in contract", name);
-            None
-        }
+    /// Appends the definitions of another Contract, ie. of a file this Contract includes.
+    ///
+    /// The symbols aren't re-indexed, see [index_symbols](Contract::index_symbols).
+    pub fn extend(&mut self, other: &Contract) {
+        self.macros.extend(other.macros.iter().cloned());
+        self.invocations.extend(other.invocations.iter().cloned());
+        self.imports.extend(other.imports.iter().cloned());
+        self.constants.extend(other.constants.iter().cloned());
+        self.errors.extend(other.errors.iter().cloned());
+        self.functions.extend(other.functions.iter().cloned());
+        self.events.extend(other.events.iter().cloned());
+        self.tables.extend(other.tables.iter().cloned());
+        self.structs.extend(other.structs.iter().cloned());
+    }
+
+    /// Resolves the type of a function, event or error parameter, ie. `Position[]` or
+    /// `(address,uint256)`, expanding struct names into their fields.
+    ///
+    /// Returns `None` if the type names an undefined or recursive struct.
+    pub fn param_type(&self, arg_type: &str) -> Option<FunctionParamType> {
+        self.param_type_at(arg_type, 0)
     }
 
-    /// Derives the FreeStoragePointers into their bytes32 representation
-    pub fn derive_storage_pointers(&mut self) {
-        let mut storage_pointers: Vec<(String, [u8; 32])> = Vec::new();
-        let mut last_assigned_free_pointer = 0;
-
-        // Derive Constructor Storage Pointers
-        match self.find_macro_by_name("CONSTRUCTOR") {
-            Some(m) => self.recurse_ast_constants(
-                &m,
-                &mut storage_pointers,
-                &mut last_assigned_free_pointer,
-            ),
-            None => {
-                // The constructor is not required, so we can just warn
-                tracing::warn!(target: "ast", "'CONSTRUCTOR' MACRO NOT FOUND WHILE DERIVING STORAGE POINTERS!")
+    /// Resolves a parameter type nested `depth` structs deep
+    fn param_type_at(&self, arg_type: &str, depth: usize) -> Option<FunctionParamType> {
+        FunctionParamType::parse_with(arg_type, &|name| {
+            // Structs can only nest deeper than the number of structs if they are recursive
+            if depth >= self.structs.len() {
+                return None
             }
+            let definition = self.structs.iter().find(|s| s.name == name)?;
+            let fields = definition
+                .fields
+                .iter()
+                .map(|f| {
+                    let kind = self.param_type_at(f.arg_type.as_deref()?, depth + 1)?;
+                    Some((f.name.clone().unwrap_or_default(), kind))
+                })
+                .collect::<Option<Vec<_>>>()?;
+            Some(FunctionParamType::Struct(name.to_string(), fields))
+        })
+    }
+
+    /// The canonical signature of a function, event or error, ie. `open((address,uint256)[])`
+    ///
+    /// Types that can't be resolved are kept as declared.
+    pub fn signature(&self, name: &str, args: &[Argument]) -> String {
+        let types = args
+            .iter()
+            .map(|a| {
+                let arg_type = a.arg_type.as_deref().unwrap_or_default();
+                match self.param_type(arg_type) {
+                    Some(kind) => kind.to_string().replace(' ', ""),
+                    None => arg_type.replace(' ', ""),
+                }
+            })
+            .collect::<Vec<_>>();
+        format!("{}({})", name, types.join(","))
+    }
+
+    /// Recomputes the function selectors, event hashes and error selectors from the canonical
+    /// signatures, once the structs they use are defined.
+    ///
+    /// Errors on the first parameter whose type can't be resolved.
+    pub fn resolve_signatures(&mut self) -> Result<(), ParserError> {
+        let function_signatures =
+            self.functions.iter().map(|f| self.signature(&f.name, &f.inputs)).collect::<Vec<_>>();
+        for (f, sig) in self.functions.iter_mut().zip(function_signatures) {
+            hash_bytes(&mut f.signature, &sig);
+        }
+        let event_signatures =
+            self.events.iter().map(|e| self.signature(&e.name, &e.parameters)).collect::<Vec<_>>();
+        for (e, sig) in self.events.iter_mut().zip(event_signatures) {
+            hash_bytes(&mut e.hash, &sig);
+        }
+        let error_signatures =
+            self.errors.iter().map(|e| self.signature(&e.name, &e.parameters)).collect::<Vec<_>>();
+        for (e, sig) in self.errors.iter_mut().zip(error_signatures) {
+            hash_bytes(&mut e.selector, &sig);
         }
 
-        // Derive Main Storage Pointers
-        match self.find_macro_by_name("MAIN") {
-            Some(m) => self.recurse_ast_constants(
-                &m,
-                &mut storage_pointers,
-                &mut last_assigned_free_pointer,
-            ),
-            None => {
-                tracing::error!(target: "ast", "'MAIN' MACRO NOT FOUND WHILE DERIVING STORAGE POINTERS!")
+        let unknown = self
+            .functions
+            .iter()
+            .flat_map(|f| f.inputs.iter().chain(f.outputs.iter()))
+            .chain(self.events.iter().flat_map(|e| e.parameters.iter()))
+            .chain(self.errors.iter().flat_map(|e| e.parameters.iter()))
+            .chain(self.structs.iter().flat_map(|s| s.fields.iter()))
+            .find(|a| self.param_type(a.arg_type.as_deref().unwrap_or_default()).is_none());
+        match unknown {
+            Some(arg) => {
+                let arg_type = arg.arg_type.clone().unwrap_or_default();
+                tracing::error!(target: "parser", "UNKNOWN TYPE: {}", arg_type);
+                Err(ParserError {
+                    kind: ParserErrorKind::UnknownType(arg_type),
+                    hint: Some("Expected an EVM type, a tuple, or a defined struct.".to_string()),
+                    spans: arg.span.clone(),
+                })
             }
+            None => Ok(()),
         }
+    }
 
-        tracing::debug!(target: "ast", "Generate Storage pointers: {:?}", storage_pointers);
-        tracing::debug!(target: "ast", "ALL AST CONSTANTS: {:?}", storage_pointers);
-
-        // Set all the constants to their new values
-        for c in self.constants.lock().unwrap().iter_mut() {
-            match storage_pointers
+    /// The function selectors, event topics and error selectors, keyed by their signatures
+    pub fn selectors(&self) -> Selectors {
+        Selectors {
+            method_identifiers: self
+                .functions
                 .iter()
-                .filter(|pointer| pointer.0.eq(&c.name))
-                .collect::<Vec<&(String, [u8; 32])>>()
-                .get(0)
-            {
-                Some(p) => {
-                    *c = ConstantDefinition {
-                        name: c.name.to_string(),
-                        value: ConstVal::Literal(p.1),
-                        span: c.span.clone(),
-                    };
-                }
-                None => {
-                    tracing::warn!(target: "ast", "SET STORAGE POINTER BUT FAILED TO SET DERIVED CONSTANT VALUE FOR \"{}\"", c.name)
-                }
-            }
+                .filter(|f| f.name != "CONSTRUCTOR")
+                .map(|f| (self.signature(&f.name, &f.inputs), bytes_to_hex(&f.signature)))
+                .collect(),
+            event_topics: self
+                .events
+                .iter()
+                .map(|e| (self.signature(&e.name, &e.parameters), bytes_to_hex(&e.hash)))
+                .collect(),
+            error_selectors: self
+                .errors
+                .iter()
+                .map(|e| (self.signature(&e.name, &e.parameters), bytes_to_hex(&e.selector)))
+                .collect(),
         }
     }
 
-    /// Recurse down an AST Macro Definition to set Storage Pointers
-    ///
-    /// ## Overview
-    ///
-    /// For each statement in the macro definition:
-    ///     - If it's a free storage pointer constant, set the constant value if not already set and
-    ///       updated out `last_p` tracker value
-    ///     - If it's a literal constant, we can set the constant value directly to the literal if
-    ///       not already set
-    ///     - If it's a macro invocation, look for the macro definition and recurse into that macro
-    ///       definition using `recurse_ast_constants`
-    pub fn recurse_ast_constants(
-        &self,
-        macro_def: &MacroDefinition,
-        storage_pointers: &mut Vec<(String, [u8; 32])>,
-        last_p: &mut i32,
-    ) {
-        let mut statements = macro_def.statements.clone();
-        let mut i = 0;
-        loop {
-            if i >= statements.len() {
-                break
-            }
-            match &statements[i].clone().ty {
-                StatementType::Constant(const_name) => {
-                    tracing::debug!(target: "ast", "Found constant \"{}\" in macro def \"{}\" statements!", const_name, macro_def.name);
-                    if storage_pointers
-                        .iter()
-                        .filter(|pointer| pointer.0.eq(const_name))
-                        .collect::<Vec<&(String, [u8; 32])>>()
-                        .get(0)
-                        .is_none()
-                    {
-                        tracing::debug!(target: "ast", "No storage pointer already set for \"{}\"!", const_name);
-                        // Get the associated constant
-                        match self
-                            .constants
-                            .lock()
-                            .unwrap()
-                            .iter()
-                            .filter(|c| c.name.eq(const_name))
-                            .collect::<Vec<&ConstantDefinition>>()
-                            .get(0)
-                        {
-                            Some(c) => {
-                                let new_value = match c.value {
-                                    ConstVal::Literal(l) => l,
-                                    ConstVal::FreeStoragePointer(_) => {
-                                        let old_p = *last_p;
-                                        *last_p += 1;
-                                        str_to_bytes32(&format!("{}", old_p))
-                                    }
-                                };
-                                storage_pointers.push((const_name.to_string(), new_value));
-                            }
-                            None => {
-                                tracing::warn!(target: "ast", "CONSTANT \"{}\" NOT FOUND IN AST CONSTANTS", const_name)
-                            }
-                        }
-                    }
-                }
-                StatementType::MacroInvocation(mi) => {
-                    tracing::debug!(target: "ast", "Found macro invocation: \"{}\" in macro def: \"{}\"!", mi.macro_name, macro_def.name);
-                    match self
-                        .macros
-                        .iter()
-                        .filter(|md| md.name.eq(&mi.macro_name))
-                        .collect::<Vec<&MacroDefinition>>()
-                        .get(0)
-                    {
-                        Some(&md) => self.recurse_ast_constants(md, storage_pointers, last_p),
-                        None => {
-                            tracing::warn!(target: "ast", "MACRO \"{}\" INVOKED BUT NOT FOUND IN AST!", mi.macro_name)
-                        }
-                    }
-                }
-                StatementType::BuiltinFunctionCall(bfc) => {
-                    tracing::debug!(target: "ast", "Deriving Storage Pointrs: Found builtin function {:?}", bfc.kind);
-                    for a in &bfc.args {
-                        if let Some(name) = &a.name {
-                            match self
-                                .macros
-                                .iter()
-                                .filter(|md| md.name.eq(name))
-                                .collect::<Vec<&MacroDefinition>>()
-                                .get(0)
-                            {
-                                Some(&md) => {
-                                    self.recurse_ast_constants(md, storage_pointers, last_p)
-                                }
-                                None => {
-                                    tracing::warn!(target: "ast", "BUILTIN HAS ARG NAME \"{}\" BUT NOT FOUND IN AST!", name)
-                                }
-                            }
-                        }
-                    }
-                }
-                StatementType::Label(l) => {
-                    for state in l.inner.iter().rev() {
-                        statements.insert(i + 1, state.clone());
-                    }
-                }
-                _ => {}
-            }
-            i += 1;
+    /// Returns the first macro that matches the provided name
+    pub fn find_macro_by_name(&self, name: &str) -> Option<&MacroDefinition> {
+        let found = match self.symbols.macros.get(name).and_then(|i| self.macros.get(*i)) {
+            Some(m) if m.name == name => Some(m),
+            // Contracts that weren't indexed are scanned
+            _ if self.symbols.macros.is_empty() => self.macros.iter().find(|m| m.name == name),
+            _ => None,
+        };
+        if found.is_none() {
+            tracing::warn!("Failed to find macro \"{}\" in contract", name);
         }
-
-        // Breadth-first
-        // if !macros_to_recurse.is_empty() {
-        //     let next_md = macros_to_recurse.remove(0);
-        //     self.recurse_ast_constants(next_md, storage_pointers, last_p, macros_to_recurse);
-        // }
+        found
     }
 
-    /// Add override constants to the AST
-    ///
-    /// ## Overview
-    ///
-    /// For each override constant, add it to the AST if it doesn't already exist. Override
-    /// constants can be passed in via the CLI.
-    pub fn add_override_constants(&self, override_constants: &Option<BTreeMap<&str, Literal>>) {
-        if let Some(override_constants) = override_constants {
-            for (name, value) in override_constants {
-                let mut constants = self.constants.lock().unwrap();
-                if let Some(c) = constants.iter_mut().find(|c| c.name.as_str().eq(*name)) {
-                    c.value = ConstVal::Literal(*value);
-                } else {
-                    constants.push(ConstantDefinition {
-                        name: name.to_string(),
-                        value: ConstVal::Literal(*value),
-                        span: AstSpan::default(),
-                    });
-                }
-            }
+    /// Returns the first table that matches the provided name
+    pub fn find_table_by_name(&self, name: &str) -> Option<&TableDefinition> {
+        let found = match self.symbols.tables.get(name).and_then(|i| self.tables.get(*i)) {
+            Some(t) if t.name == name => Some(t),
+            _ if self.symbols.tables.is_empty() => self.tables.iter().find(|t| t.name == name),
+            _ => None,
+        };
+        if found.is_none() {
+            tracing::warn!("Failed to find table \"{}\" in contract", name);
         }
+        found
     }
 }
 
@@ -345,10 +343,55 @@ pub struct Argument {
     pub name: Option<String>,
     /// Is the argument indexed? TODO: should be valid for event arguments ONLY
     pub indexed: bool,
+    /// The kind of a macro parameter, if declared
+    pub kind: Option<MacroParamKind>,
     /// The argument span
     pub span: AstSpan,
 }
 
+/// The Kind of a Macro Parameter, ie. `label` in `macro M(label dest)`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MacroParamKind {
+    /// A jump label
+    Label,
+    /// A literal or constant value
+    Literal,
+    /// An opcode
+    Opcode,
+    /// A constant
+    Constant,
+    /// A macro
+    Macro,
+}
+
+impl Display for MacroParamKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            MacroParamKind::Label => "label",
+            MacroParamKind::Literal => "literal",
+            MacroParamKind::Opcode => "opcode",
+            MacroParamKind::Constant => "constant",
+            MacroParamKind::Macro => "macro",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl TryFrom<&str> for MacroParamKind {
+    type Error = ();
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "label" => Ok(MacroParamKind::Label),
+            "literal" => Ok(MacroParamKind::Literal),
+            "opcode" => Ok(MacroParamKind::Opcode),
+            "constant" => Ok(MacroParamKind::Constant),
+            "macro" => Ok(MacroParamKind::Macro),
+            _ => Err(()),
+        }
+    }
+}
+
 /// A Function Signature
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Function {
@@ -367,7 +410,10 @@ pub struct Function {
 }
 
 /// Function Types
+///
+/// Serialized as the `stateMutability` of a JSON ABI, eg. `nonpayable`.
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
 pub enum FunctionType {
     /// Viewable Function
     View,
@@ -386,7 +432,8 @@ impl FunctionType {
         match self {
             FunctionType::View => " view",
             FunctionType::Pure => " pure",
-            _ => "", // payable / nonpayable types not valid in Solidity interfaces
+            FunctionType::Payable => " payable",
+            FunctionType::NonPayable => "", // nonpayable is implicit in Solidity
         }
     }
 }
@@ -539,12 +586,11 @@ impl MacroDefinition {
                         span: statement.span.clone(),
                     });
                 }
-                StatementType::MacroInvocation(mi) => {
+                StatementType::MacroInvocation(_) |
+                StatementType::ArgMacroInvocation(_) |
+                StatementType::Conditional(_) => {
                     inner_irbytes.push(IRBytes {
-                        ty: IRByteType::Statement(Statement {
-                            ty: StatementType::MacroInvocation(mi.clone()),
-                            span: statement.span.clone(),
-                        }),
+                        ty: IRByteType::Statement(statement.clone()),
                         span: statement.span.clone(),
                     });
                 }
@@ -599,6 +645,20 @@ impl MacroDefinition {
 
         inner_irbytes
     }
+
+    /// Whether the statements define a label, including within nested labels and conditionals
+    pub fn defines_label(statements: &[Statement], label: &str) -> bool {
+        statements.iter().any(|s| match &s.ty {
+            StatementType::Label(l) => {
+                l.name == label || MacroDefinition::defines_label(&l.inner, label)
+            }
+            StatementType::Conditional(c) => {
+                MacroDefinition::defines_label(&c.statements, label) ||
+                    MacroDefinition::defines_label(&c.else_statements, label)
+            }
+            _ => false,
+        })
+    }
 }
 
 /// A Macro Invocation
@@ -613,7 +673,7 @@ pub struct MacroInvocation {
 }
 
 /// An argument passed when invoking a maco
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum MacroArg {
     /// Macro Literal Argument
     Literal(Literal),
@@ -623,9 +683,23 @@ pub enum MacroArg {
     ArgCall(String),
 }
 
-/// Free Storage Pointer Unit Struct
+/// Free Storage Pointer
+#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct FreeStoragePointer {
+    /// The ERC-7201 namespace id, eg. `FREE_STORAGE_POINTER("example.main")`
+    pub namespace: Option<String>,
+}
+
+/// Free Transient Storage Pointer Unit Struct
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
-pub struct FreeStoragePointer;
+pub struct FreeTransientPointer;
+
+/// Free Memory Pointer
+#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct FreeMemoryPointer {
+    /// The size of the memory region in bytes
+    pub size: usize,
+}
 
 /// A Constant Value
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
@@ -634,6 +708,10 @@ pub enum ConstVal {
     Literal(Literal),
     /// A Free Storage Pointer
     FreeStoragePointer(FreeStoragePointer),
+    /// A Free Transient Storage Pointer
+    FreeTransientPointer(FreeTransientPointer),
+    /// A Free Memory Pointer
+    FreeMemoryPointer(FreeMemoryPointer),
 }
 
 /// A Constant Definition
@@ -660,6 +738,30 @@ pub struct ErrorDefinition {
     pub span: AstSpan,
 }
 
+/// A Struct Definition, ie. `#define struct Position(address owner, uint256 amount)`
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct StructDefinition {
+    /// The Struct name
+    pub name: String,
+    /// The typed and named fields of the struct
+    pub fields: Vec<Argument>,
+    /// The Span of the Struct Definition
+    pub span: AstSpan,
+}
+
+/// A Conditional Block
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Conditional {
+    /// The Constant the condition is evaluated against
+    pub condition: String,
+    /// Statements compiled if the Constant is non-zero
+    pub statements: Vec<Statement>,
+    /// Statements compiled if the Constant is zero
+    pub else_statements: Vec<Statement>,
+    /// The conditional span
+    pub span: AstSpan,
+}
+
 /// A Jump Destination
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Label {
@@ -701,6 +803,8 @@ pub enum BuiltinFunctionKind {
     Error,
     /// Rightpad function
     RightPad,
+    /// Repeat function
+    Repeat,
 }
 
 impl From<String> for BuiltinFunctionKind {
@@ -713,6 +817,7 @@ impl From<String> for BuiltinFunctionKind {
             "__EVENT_HASH" => BuiltinFunctionKind::EventHash,
             "__ERROR" => BuiltinFunctionKind::Error,
             "__RIGHTPAD" => BuiltinFunctionKind::RightPad,
+            "__REPEAT" => BuiltinFunctionKind::Repeat,
             _ => panic!("Invalid Builtin Function Kind"), /* This should never be reached,
                                                            * builtins are validated with a
                                                            * `try_from` call in the lexer. */
@@ -720,6 +825,22 @@ impl From<String> for BuiltinFunctionKind {
     }
 }
 
+impl Display for BuiltinFunctionKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            BuiltinFunctionKind::Tablesize => "__tablesize",
+            BuiltinFunctionKind::Codesize => "__codesize",
+            BuiltinFunctionKind::Tablestart => "__tablestart",
+            BuiltinFunctionKind::FunctionSignature => "__FUNC_SIG",
+            BuiltinFunctionKind::EventHash => "__EVENT_HASH",
+            BuiltinFunctionKind::Error => "__ERROR",
+            BuiltinFunctionKind::RightPad => "__RIGHTPAD",
+            BuiltinFunctionKind::Repeat => "__REPEAT",
+        };
+        write!(f, "{}", name)
+    }
+}
+
 impl TryFrom<&String> for BuiltinFunctionKind {
     type Error = ();
 
@@ -732,6 +853,7 @@ impl TryFrom<&String> for BuiltinFunctionKind {
             "__EVENT_HASH" => Ok(BuiltinFunctionKind::EventHash),
             "__ERROR" => Ok(BuiltinFunctionKind::Error),
             "__RIGHTPAD" => Ok(BuiltinFunctionKind::RightPad),
+            "__REPEAT" => Ok(BuiltinFunctionKind::Repeat),
             _ => Err(()),
         }
     }
@@ -757,6 +879,10 @@ pub enum StatementType {
     Code(String),
     /// A Macro Invocation Statement
     MacroInvocation(MacroInvocation),
+    /// An Invocation of a Macro passed as an Argument, ie. `<body>()`
+    ///
+    /// The invocation's `macro_name` is the name of the argument.
+    ArgMacroInvocation(MacroInvocation),
     /// A Constant Push
     Constant(String),
     /// An Arg Call
@@ -767,6 +893,8 @@ pub enum StatementType {
     LabelCall(String),
     /// A built-in function call
     BuiltinFunctionCall(BuiltinFunctionCall),
+    /// A Conditional Block, ie. `#if CONSTANT ... #else ... #endif`
+    Conditional(Conditional),
 }
 
 impl Display for StatementType {
@@ -778,10 +906,14 @@ impl Display for StatementType {
             StatementType::MacroInvocation(m) => {
                 write!(f, "MACRO INVOCATION: {}", m.macro_name)
             }
+            StatementType::ArgMacroInvocation(m) => {
+                write!(f, "ARG MACRO INVOCATION: <{}>", m.macro_name)
+            }
             StatementType::Constant(c) => write!(f, "CONSTANT: {}", c),
             StatementType::ArgCall(c) => write!(f, "ARG CALL: {}", c),
             StatementType::Label(l) => write!(f, "LABEL: {}", l.name),
             StatementType::LabelCall(l) => write!(f, "LABEL CALL: {}", l),
+            StatementType::Conditional(c) => write!(f, "CONDITIONAL: {}", c.condition),
             StatementType::BuiltinFunctionCall(b) => {
                 write!(f, "BUILTIN FUNCTION CALL: {:?}", b.kind)
             }
@@ -0,0 +1,639 @@
+//! ## Bindings
+//!
+//! Typed Rust and TypeScript bindings of compiled artifacts, so that off-chain code calls a
+//! contract through its ABI rather than hand-encoded calldata. Bindings are a pure function of the
+//! artifacts, which lets CI check that committed bindings are up to date.
+
+use crate::{
+    abi::{Abi, FunctionParamType},
+    artifact::Artifact,
+    bytes_util::bytes_to_hex,
+};
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Component, Path},
+    sync::Arc,
+};
+
+/// The language bindings are generated in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BindingsLanguage {
+    /// An ethers-rs contract struct with typed call and event methods
+    Rust,
+    /// A typed ABI const and deploy helper for ethers.js
+    TypeScript,
+}
+
+impl TryFrom<&str> for BindingsLanguage {
+    type Error = String;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        match s {
+            "rust" | "rs" => Ok(BindingsLanguage::Rust),
+            "ts" | "typescript" => Ok(BindingsLanguage::TypeScript),
+            _ => Err(format!("Unknown bindings language \"{}\", expected rust or ts", s)),
+        }
+    }
+}
+
+impl BindingsLanguage {
+    /// The name of the `huffc bindings --lang` argument
+    pub fn arg(&self) -> &str {
+        match self {
+            BindingsLanguage::Rust => "rust",
+            BindingsLanguage::TypeScript => "ts",
+        }
+    }
+
+    /// The extension of generated files
+    pub fn extension(&self) -> &str {
+        match self {
+            BindingsLanguage::Rust => "rs",
+            BindingsLanguage::TypeScript => "ts",
+        }
+    }
+
+    /// The file name of an artifact's bindings, ie. `erc20.rs` or `ERC20.ts`
+    pub fn file_name(&self, artifact: &Artifact) -> String {
+        let name = identifier(&artifact.contract_name());
+        match self {
+            BindingsLanguage::Rust => format!("{}.rs", snake_case(&name)),
+            BindingsLanguage::TypeScript => format!("{}.ts", name),
+        }
+    }
+
+    /// Generates the bindings of an artifact
+    pub fn generate(&self, artifact: &Artifact) -> String {
+        match self {
+            BindingsLanguage::Rust => gen_rust_bindings(artifact),
+            BindingsLanguage::TypeScript => gen_ts_bindings(artifact),
+        }
+    }
+
+    /// Generates the module re-exporting the bindings of each file
+    fn index(&self, file_names: &[String]) -> (String, String) {
+        let stems =
+            file_names.iter().map(|f| f.trim_end_matches(&format!(".{}", self.extension())));
+        match self {
+            BindingsLanguage::Rust => (
+                "mod.rs".to_string(),
+                format!(
+                    "//! Bindings generated by huffc, do not edit.\n\n{}",
+                    stems.map(|s| format!("pub mod {};\n", s)).collect::<String>()
+                ),
+            ),
+            BindingsLanguage::TypeScript => (
+                "index.ts".to_string(),
+                format!(
+                    "// Bindings generated by huffc, do not edit.\n\n{}",
+                    stems.map(|s| format!("export * from \"./{}\";\n", s)).collect::<String>()
+                ),
+            ),
+        }
+    }
+}
+
+/// Generate the bindings of a vector of artifacts, and the module re-exporting them.
+///
+/// @param artifacts The vector of artifacts to generate bindings for.
+/// @param language The language of the bindings.
+/// @return The file names and contents of the bindings, sorted by file name, or an error if two
+/// contracts share a name.
+pub fn gen_bindings(
+    artifacts: &[Arc<Artifact>],
+    language: BindingsLanguage,
+) -> Result<Vec<(String, String)>, String> {
+    let mut files: BTreeMap<String, (&str, String)> = BTreeMap::new();
+    for artifact in artifacts {
+        let file_name = language.file_name(artifact);
+        if let Some((path, _)) = files.get(&file_name) {
+            return Err(format!(
+                "Contracts \"{}\" and \"{}\" both generate bindings \"{}\"",
+                path, artifact.file.path, file_name
+            ))
+        }
+        files.insert(file_name, (&artifact.file.path, language.generate(artifact)));
+    }
+
+    let file_names = files.keys().cloned().collect::<Vec<_>>();
+    let mut bindings =
+        files.into_iter().map(|(name, (_, contents))| (name, contents)).collect::<Vec<_>>();
+    bindings.push(language.index(&file_names));
+    Ok(bindings)
+}
+
+/// Writes bindings to a directory, creating it if needed
+pub fn export_bindings(dir: &str, bindings: &[(String, String)]) -> Result<(), std::io::Error> {
+    fs::create_dir_all(dir)?;
+    for (file_name, contents) in bindings {
+        fs::write(Path::new(dir).join(file_name), contents)?;
+    }
+    Ok(())
+}
+
+/// Compares bindings against those written to a directory.
+///
+/// @return The file names of bindings that are missing or differ, and of files with the bindings'
+/// extension that would no longer be generated.
+pub fn check_bindings(
+    dir: &str,
+    bindings: &[(String, String)],
+    language: BindingsLanguage,
+) -> Vec<String> {
+    let mut stale = bindings
+        .iter()
+        .filter(|(file_name, contents)| {
+            fs::read_to_string(Path::new(dir).join(file_name)).ok().as_ref() != Some(contents)
+        })
+        .map(|(file_name, _)| file_name.clone())
+        .collect::<Vec<_>>();
+
+    let mut removed = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .filter(|file_name| {
+            file_name.ends_with(&format!(".{}", language.extension())) &&
+                !bindings.iter().any(|(f, _)| f == file_name)
+        })
+        .collect::<Vec<_>>();
+    removed.sort();
+    stale.append(&mut removed);
+    stale
+}
+
+/// Generates an ethers-rs contract struct, with a method per function and an event filter per
+/// event, and the structs their parameters use
+fn gen_rust_bindings(artifact: &Artifact) -> String {
+    let abi = artifact.abi.clone().unwrap_or_default();
+    let name = identifier(&artifact.contract_name());
+    let constant = snake_case(&name).to_uppercase();
+
+    let mut out = format!(
+        "//! Bindings for the `{name}` contract, generated by huffc from `{}`.\n\
+        //!\n\
+        //! Do not edit, regenerate them with `huffc bindings --lang rust`.\n\
+        #![allow(clippy::all)]\n\n",
+        source_path(artifact)
+    );
+
+    out.push_str(&format!(
+        "/// The ABI of the `{name}` contract\n\
+        pub static {constant}_ABI: ::ethers::contract::Lazy<::ethers::core::abi::Abi> =\n    \
+        ::ethers::contract::Lazy::new(|| {{\n        \
+        ::ethers::core::utils::__serde_json::from_str(r#\"{}\"#).expect(\"invalid abi\")\n    \
+        }});\n\n",
+        serde_json::to_string(&abi).unwrap_or_else(|_| "[]".to_string())
+    ));
+    for (doc, suffix, code) in [
+        ("bytecode, including any constructor arguments passed to huffc", "", &artifact.bytecode),
+        ("runtime bytecode", "_DEPLOYED", &artifact.runtime),
+    ] {
+        out.push_str(&format!(
+            "/// The `{name}` contract's {doc}\n\
+            pub static {constant}{suffix}_BYTECODE: ::ethers::contract::Lazy<::ethers::core::types::Bytes> =\n    \
+            ::ethers::contract::Lazy::new(|| \"0x{code}\".parse().expect(\"invalid bytecode\"));\n\n"
+        ));
+    }
+
+    // The contract struct
+    out.push_str(&format!(
+        "/// The `{name}` contract\n\
+        pub struct {name}<M>(::ethers::contract::Contract<M>);\n\n\
+        impl<M> Clone for {name}<M> {{\n    \
+        fn clone(&self) -> Self {{\n        Self(self.0.clone())\n    }}\n}}\n\n\
+        impl<M> ::std::ops::Deref for {name}<M> {{\n    \
+        type Target = ::ethers::contract::Contract<M>;\n\n    \
+        fn deref(&self) -> &Self::Target {{\n        &self.0\n    }}\n}}\n\n\
+        impl<M> From<::ethers::contract::Contract<M>> for {name}<M> {{\n    \
+        fn from(contract: ::ethers::contract::Contract<M>) -> Self {{\n        Self(contract)\n    }}\n}}\n\n\
+        impl<M: ::ethers::providers::Middleware> {name}<M> {{\n    \
+        /// Creates a binding of the contract deployed at `address`\n    \
+        pub fn new<T: Into<::ethers::core::types::Address>>(address: T, client: ::std::sync::Arc<M>) -> Self {{\n        \
+        Self(::ethers::contract::Contract::new(address.into(), {constant}_ABI.clone(), client))\n    }}\n\n    \
+        /// Deploys the contract, ABI encoding the constructor arguments after its bytecode\n    \
+        pub fn deploy<T: ::ethers::core::abi::Tokenize>(\n        \
+        client: ::std::sync::Arc<M>,\n        \
+        constructor_args: T,\n    \
+        ) -> Result<::ethers::contract::ContractDeployer<M, Self>, ::ethers::contract::ContractError<M>> {{\n        \
+        let factory = ::ethers::contract::ContractFactory::new(\n            \
+        {constant}_ABI.clone(),\n            \
+        {constant}_BYTECODE.clone(),\n            \
+        client,\n        );\n        \
+        Ok(::ethers::contract::ContractDeployer::new(factory.deploy(constructor_args)?))\n    }}\n"
+    ));
+
+    // Constructors are deployed with, not called
+    let functions = abi
+        .functions
+        .values()
+        .filter(|f| f.name.to_lowercase() != "constructor")
+        .collect::<Vec<_>>();
+    let methods = overloads(functions.iter().map(|f| f.name.as_str()));
+    functions.iter().zip(methods).for_each(|(f, method)| {
+        let params = f
+            .inputs
+            .iter()
+            .enumerate()
+            .map(|(i, p)| (rust_ident(&p.name, i), rust_type(&p.kind)))
+            .collect::<Vec<_>>();
+        let names = params.iter().map(|(n, _)| n.clone()).collect::<Vec<_>>();
+        out.push_str(&format!(
+            "\n    /// Calls `{}`, with selector `0x{}`\n    \
+            pub fn {}({}) -> ::ethers::contract::builders::ContractCall<M, {}> {{\n        \
+            self.0\n            \
+            .method_hash({:?}, {})\n            \
+            .expect(\"method not found (this should never happen)\")\n    }}\n",
+            f.signature(),
+            bytes_to_hex(&f.selector()),
+            rust_ident(&method, 0),
+            match params.is_empty() {
+                true => "&self".to_string(),
+                false => format!(
+                    "\n        &self,\n{}    ",
+                    params
+                        .iter()
+                        .map(|(n, t)| format!("        {}: {},\n", n, t))
+                        .collect::<String>()
+                ),
+            },
+            rust_tuple(f.outputs.iter().map(|o| rust_type(&o.kind)).collect()),
+            f.selector(),
+            match names.len() {
+                1 => format!("({},)", names[0]),
+                _ => format!("({})", names.join(", ")),
+            },
+        ));
+    });
+
+    let events = overloads(abi.events.values().map(|e| e.name.as_str()));
+    let events = abi.events.values().zip(events).collect::<Vec<_>>();
+    events.iter().for_each(|(e, event)| {
+        out.push_str(&format!(
+            "\n    /// Returns a filter of `{}` events\n    \
+            pub fn {}_filter(&self) -> ::ethers::contract::builders::Event<::std::sync::Arc<M>, M, {}Filter> {{\n        \
+            self.0.event()\n    }}\n",
+            e.signature(),
+            snake_case(event),
+            identifier(event)
+        ));
+    });
+    out.push_str("}\n");
+
+    // The event structs
+    events.iter().for_each(|(e, event)| {
+        let fields = e
+            .inputs
+            .iter()
+            .enumerate()
+            .map(|(i, p)| {
+                // Indexed reference types are topics of their hash
+                match p.indexed {
+                    true if p.kind.is_memory_type() => format!(
+                        "    #[ethevent(indexed)]\n    pub {}: ::ethers::core::types::H256,\n",
+                        rust_ident(&p.name, i)
+                    ),
+                    true => format!(
+                        "    #[ethevent(indexed)]\n    pub {}: {},\n",
+                        rust_ident(&p.name, i),
+                        rust_type(&p.kind)
+                    ),
+                    false => {
+                        format!("    pub {}: {},\n", rust_ident(&p.name, i), rust_type(&p.kind))
+                    }
+                }
+            })
+            .collect::<String>();
+        out.push_str(&format!(
+            "\n/// The `{}` event\n\
+            #[derive(Clone, Debug, PartialEq, Eq, ::ethers::contract::EthEvent)]\n\
+            #[ethevent(name = \"{}\", abi = \"{}\"{})]\n\
+            pub struct {}Filter {}\n",
+            e.signature(),
+            e.name,
+            e.signature(),
+            if e.anonymous { ", anonymous" } else { "" },
+            identifier(event),
+            if fields.is_empty() { "{}".to_string() } else { format!("{{\n{}}}", fields) }
+        ));
+    });
+
+    // The structs of parameters
+    let mut structs = Vec::new();
+    abi.functions
+        .values()
+        .flat_map(|f| f.inputs.iter().chain(f.outputs.iter()).map(|p| &p.kind))
+        .chain(abi.events.values().flat_map(|e| e.inputs.iter().map(|p| &p.kind)))
+        .for_each(|kind| collect_structs(kind, &mut structs));
+    structs.iter().for_each(|(name, fields)| {
+        out.push_str(&format!(
+            "\n/// The `{}` struct\n\
+            #[derive(Clone, Debug, PartialEq, Eq, ::ethers::contract::EthAbiType, ::ethers::contract::EthAbiCodec)]\n\
+            pub struct {} {{\n{}}}\n",
+            name,
+            identifier(name),
+            fields
+                .iter()
+                .enumerate()
+                .map(|(i, (field, kind))| format!(
+                    "    pub {}: {},\n",
+                    rust_ident(field, i),
+                    rust_type(kind)
+                ))
+                .collect::<String>()
+        ));
+    });
+
+    out
+}
+
+/// Generates a typed ABI const, for ethers.js and abitype, and a helper deploying the contract
+/// with typed constructor arguments
+fn gen_ts_bindings(artifact: &Artifact) -> String {
+    let abi = artifact.abi.clone().unwrap_or_default();
+    let name = identifier(&artifact.contract_name());
+    let constant = camel_case(&name);
+    let args = abi
+        .constructor
+        .iter()
+        .flat_map(|c| c.inputs.iter())
+        .enumerate()
+        .map(|(i, p)| (ts_ident(&p.name, i), ts_type(&p.kind)))
+        .collect::<Vec<_>>();
+
+    format!(
+        "// Bindings for the `{name}` contract, generated by huffc from `{}`.\n\
+        // Do not edit, regenerate them with `huffc bindings --lang ts`.\n\n\
+        import {{ ContractFactory, type ContractRunner }} from \"ethers\";\n\n\
+        /** The ABI of the `{name}` contract */\n\
+        export const {constant}Abi = {} as const;\n\n\
+        /** The bytecode of the `{name}` contract, including any constructor arguments passed to huffc */\n\
+        export const {constant}Bytecode = \"0x{}\" as const;\n\n\
+        /** The runtime bytecode of the `{name}` contract */\n\
+        export const {constant}DeployedBytecode = \"0x{}\" as const;\n\n\
+        /** Deploys the `{name}` contract, and waits for its deployment */\n\
+        export async function deploy{name}(runner: ContractRunner{}) {{\n  \
+        const factory = new ContractFactory({constant}Abi, {constant}Bytecode, runner);\n  \
+        const contract = await factory.deploy({});\n  \
+        return contract.waitForDeployment();\n\
+        }}\n",
+        source_path(artifact),
+        abi_json(&abi),
+        artifact.bytecode,
+        artifact.runtime,
+        args.iter().map(|(n, t)| format!(", {}: {}", n, t)).collect::<String>(),
+        args.iter().map(|(n, _)| n.as_str()).collect::<Vec<_>>().join(", "),
+    )
+}
+
+/// The source path of an artifact without `.` components, so that bindings don't depend on how
+/// the contract was passed to huffc, ie. `./src/ERC20.huff` and `src/ERC20.huff`
+fn source_path(artifact: &Artifact) -> String {
+    Path::new(&artifact.file.path)
+        .components()
+        .filter(|c| !matches!(c, Component::CurDir))
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Pretty prints an ABI as a JSON array
+fn abi_json(abi: &Abi) -> String {
+    abi.to_json().unwrap_or_else(|_| "[]".to_string())
+}
+
+/// Names overloads apart by suffixing them with their index, in signature order
+fn overloads<'a>(names: impl Iterator<Item = &'a str>) -> Vec<String> {
+    let mut seen: BTreeMap<&str, usize> = BTreeMap::new();
+    names
+        .map(|name| {
+            let count = seen.entry(name).or_default();
+            *count += 1;
+            match *count {
+                1 => name.to_string(),
+                n => format!("{}{}", name, n - 1),
+            }
+        })
+        .collect()
+}
+
+/// Collects the named structs of a parameter type, their dependencies first
+fn collect_structs(
+    kind: &FunctionParamType,
+    structs: &mut Vec<(String, Vec<(String, FunctionParamType)>)>,
+) {
+    match kind {
+        FunctionParamType::Array(inner, _) => collect_structs(inner, structs),
+        FunctionParamType::Tuple(inner) => inner.iter().for_each(|k| collect_structs(k, structs)),
+        FunctionParamType::Struct(name, fields) => {
+            fields.iter().for_each(|(_, k)| collect_structs(k, structs));
+            if !structs.iter().any(|(n, _)| n == name) {
+                structs.push((name.clone(), fields.clone()));
+            }
+        }
+        _ => {}
+    }
+}
+
+/// The Rust type of a parameter type, as mapped by ethers-rs
+fn rust_type(kind: &FunctionParamType) -> String {
+    match kind {
+        FunctionParamType::Address => "::ethers::core::types::Address".to_string(),
+        FunctionParamType::Bytes => "::ethers::core::types::Bytes".to_string(),
+        FunctionParamType::Int(size) => match size {
+            0..=8 => "i8".to_string(),
+            9..=16 => "i16".to_string(),
+            17..=32 => "i32".to_string(),
+            33..=64 => "i64".to_string(),
+            65..=128 => "i128".to_string(),
+            _ => "::ethers::core::types::I256".to_string(),
+        },
+        FunctionParamType::Uint(size) => match size {
+            0..=8 => "u8".to_string(),
+            9..=16 => "u16".to_string(),
+            17..=32 => "u32".to_string(),
+            33..=64 => "u64".to_string(),
+            65..=128 => "u128".to_string(),
+            _ => "::ethers::core::types::U256".to_string(),
+        },
+        FunctionParamType::Bool => "bool".to_string(),
+        FunctionParamType::String => "::std::string::String".to_string(),
+        FunctionParamType::Array(inner, sizes) => {
+            sizes.iter().fold(rust_type(inner), |t, size| match size {
+                0 => format!("::std::vec::Vec<{}>", t),
+                size => format!("[{}; {}]", t, size),
+            })
+        }
+        FunctionParamType::FixedBytes(size) => format!("[u8; {}]", size),
+        FunctionParamType::Tuple(inner) => rust_tuple(inner.iter().map(rust_type).collect()),
+        FunctionParamType::Struct(name, _) => identifier(name),
+    }
+}
+
+/// Renders types as a tuple, or as the type itself if there is only one
+fn rust_tuple(types: Vec<String>) -> String {
+    match types.len() {
+        1 => types[0].clone(),
+        _ => format!("({})", types.join(", ")),
+    }
+}
+
+/// The TypeScript type of a parameter type, as accepted by ethers.js
+fn ts_type(kind: &FunctionParamType) -> String {
+    match kind {
+        FunctionParamType::Int(_) | FunctionParamType::Uint(_) => "bigint".to_string(),
+        FunctionParamType::Bool => "boolean".to_string(),
+        FunctionParamType::Address |
+        FunctionParamType::Bytes |
+        FunctionParamType::String |
+        FunctionParamType::FixedBytes(_) => "string".to_string(),
+        FunctionParamType::Array(inner, sizes) => {
+            sizes.iter().fold(ts_type(inner), |t, _| format!("readonly {}[]", t))
+        }
+        FunctionParamType::Tuple(inner) => {
+            format!("readonly [{}]", inner.iter().map(ts_type).collect::<Vec<_>>().join(", "))
+        }
+        FunctionParamType::Struct(_, fields) => format!(
+            "{{ {} }}",
+            fields
+                .iter()
+                .enumerate()
+                .map(|(i, (field, k))| format!("{}: {};", ts_ident(field, i), ts_type(k)))
+                .collect::<Vec<_>>()
+                .join(" ")
+        ),
+    }
+}
+
+/// Keywords that can't name a Rust parameter, and the binding's own associated functions
+const RUST_RESERVED: &[&str] = &[
+    "as", "async", "await", "box", "break", "const", "continue", "crate", "deploy", "do", "dyn",
+    "else", "enum", "extern", "false", "final", "fn", "for", "if", "impl", "in", "let", "loop",
+    "macro", "match", "mod", "move", "mut", "new", "override", "priv", "pub", "ref", "return",
+    "self", "static", "struct", "super", "trait", "true", "try", "type", "typeof", "unsafe",
+    "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+/// Keywords that can't name a TypeScript parameter
+const TS_RESERVED: &[&str] = &[
+    "break",
+    "case",
+    "catch",
+    "class",
+    "const",
+    "continue",
+    "debugger",
+    "default",
+    "delete",
+    "do",
+    "else",
+    "enum",
+    "export",
+    "extends",
+    "false",
+    "finally",
+    "for",
+    "function",
+    "if",
+    "import",
+    "in",
+    "instanceof",
+    "new",
+    "null",
+    "return",
+    "runner",
+    "super",
+    "switch",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typeof",
+    "var",
+    "void",
+    "while",
+    "with",
+];
+
+/// A Rust parameter or field name, `p<index>` if unnamed
+fn rust_ident(name: &str, index: usize) -> String {
+    match snake_case(&identifier(name)) {
+        n if n.is_empty() => format!("p{}", index),
+        n if RUST_RESERVED.contains(&n.as_str()) => format!("{}_", n),
+        n => n,
+    }
+}
+
+/// A TypeScript parameter or field name, `arg<index>` if unnamed
+fn ts_ident(name: &str, index: usize) -> String {
+    match identifier(name) {
+        n if n.is_empty() => format!("arg{}", index),
+        n if TS_RESERVED.contains(&n.as_str()) => format!("_{}", n),
+        n => n,
+    }
+}
+
+/// Replaces the characters that can't be part of an identifier, and prefixes leading digits
+fn identifier(name: &str) -> String {
+    let name = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '_' { c } else { '_' })
+        .collect::<String>();
+    match name.chars().next() {
+        Some(c) if c.is_ascii_digit() => format!("_{}", name),
+        _ => name,
+    }
+}
+
+/// Splits a name into lowercase words, on underscores and case changes, ie. `ERC20Token` into
+/// `erc20` and `token`
+fn words(name: &str) -> Vec<String> {
+    let chars = name.chars().collect::<Vec<_>>();
+    let mut words: Vec<String> = vec![];
+    let mut word = String::new();
+    for (i, c) in chars.iter().enumerate() {
+        if *c == '_' {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            continue
+        }
+        let prev = if i > 0 { chars.get(i - 1) } else { None };
+        let next = chars.get(i + 1);
+        let boundary = c.is_ascii_uppercase() &&
+            (matches!(prev, Some(p) if p.is_ascii_lowercase() || p.is_ascii_digit()) ||
+                (matches!(prev, Some(p) if p.is_ascii_uppercase()) &&
+                    matches!(next, Some(n) if n.is_ascii_lowercase())));
+        if boundary && !word.is_empty() {
+            words.push(std::mem::take(&mut word));
+        }
+        word.push(c.to_ascii_lowercase());
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+/// Converts a name to `snake_case`
+fn snake_case(name: &str) -> String {
+    words(name).join("_")
+}
+
+/// Converts a name to `camelCase`
+fn camel_case(name: &str) -> String {
+    words(name)
+        .iter()
+        .enumerate()
+        .map(|(i, w)| {
+            let mut chars = w.chars();
+            match (i, chars.next()) {
+                (0, Some(c)) => c.to_string() + chars.as_str(),
+                (_, Some(c)) => c.to_ascii_uppercase().to_string() + chars.as_str(),
+                _ => String::new(),
+            }
+        })
+        .collect()
+}
@@ -2,9 +2,9 @@
 //!
 //! Abstract translating state into bytecode.
 
-use crate::prelude::{AstSpan, Statement, TableDefinition};
+use crate::prelude::{AstSpan, MacroArg, SourceMapEntry, Statement, TableDefinition};
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, BTreeSet, HashMap},
     fmt::{self, Display},
 };
 
@@ -78,6 +78,8 @@ pub struct BytecodeRes {
     pub table_instances: Jumps,
     /// Utilized Tables
     pub utilized_tables: Vec<TableDefinition>,
+    /// Source Map
+    pub source_map: Vec<SourceMapEntry>,
 }
 
 impl Display for BytecodeRes {
@@ -98,6 +100,54 @@ impl Display for BytecodeRes {
     }
 }
 
+impl BytecodeRes {
+    /// Whether the bytecode is independent of the offset it was generated at, ie. it neither
+    /// defines nor references labels or tables.
+    pub fn is_position_independent(&self) -> bool {
+        self.label_indices.is_empty() &&
+            self.unmatched_jumps.is_empty() &&
+            self.table_instances.is_empty()
+    }
+
+    /// Moves position independent bytecode generated at one offset to another
+    pub fn relocate(&self, from: usize, to: usize) -> Self {
+        let mut res = self.clone();
+        res.bytes.iter_mut().for_each(|(o, _)| *o = *o - from + to);
+        res.source_map.iter_mut().for_each(|e| e.offset = e.offset - from + to);
+        res
+    }
+}
+
+/// Cached macro expansions
+///
+/// Inlined invocations without arg call arguments are cached if their bytecode is position
+/// independent. The size of macros passed to `__codesize` is cached separately, keyed by
+/// whether the macro was generated at the top level, where outlined functions are appended.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ExpansionCache {
+    /// Expansions of macro invocations, generated at offset 0, by macro name and arguments
+    pub invocations: HashMap<(String, Vec<MacroArg>), CachedExpansion>,
+    /// Sizes of macros, by macro name and whether they were generated at the top level
+    pub codesizes: HashMap<(String, bool), (usize, BTreeSet<String>)>,
+}
+
+impl ExpansionCache {
+    /// Removes all cached expansions
+    pub fn clear(&mut self) {
+        self.invocations.clear();
+        self.codesizes.clear();
+    }
+}
+
+/// A Cached Macro Expansion
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct CachedExpansion {
+    /// The generated bytecode
+    pub res: BytecodeRes,
+    /// The names of the macros expanded
+    pub macros: BTreeSet<String>,
+}
+
 /// A Jump
 #[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Jump {
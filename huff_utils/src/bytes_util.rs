@@ -65,9 +65,60 @@ pub fn str_to_vec(s: &str) -> Result<Vec<u8>, std::num::ParseIntError> {
     bytes
 }
 
+/// Convert a byte slice to an unprefixed hex string
+pub fn bytes_to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
 /// Hash a string with Keccak256
 pub fn hash_bytes(dest: &mut [u8], to_hash: &String) {
+    hash_raw_bytes(dest, to_hash.as_bytes());
+}
+
+/// Hash a byte slice with Keccak256
+pub fn hash_raw_bytes(dest: &mut [u8], to_hash: &[u8]) {
     let mut hasher = Keccak::v256();
-    hasher.update(to_hash.as_bytes());
+    hasher.update(to_hash);
     hasher.finalize(dest);
 }
+
+/// Derive an ERC-7201 namespaced storage slot:
+/// `keccak256(abi.encode(uint256(keccak256(id)) - 1)) & ~bytes32(uint256(0xff))`
+pub fn erc7201_slot(namespace: &str) -> [u8; 32] {
+    let mut id_hash = [0u8; 32];
+    hash_bytes(&mut id_hash, &namespace.to_string());
+
+    // Subtract one, borrowing from the higher order bytes
+    for b in id_hash.iter_mut().rev() {
+        let (sub, borrow) = b.overflowing_sub(1);
+        *b = sub;
+        if !borrow {
+            break
+        }
+    }
+
+    let mut slot = [0u8; 32];
+    hash_raw_bytes(&mut slot, &id_hash);
+    slot[31] = 0;
+    slot
+}
+
+/// Compute the address of a contract deployed with CREATE2:
+/// `keccak256(0xff ++ deployer ++ salt ++ keccak256(init_code))[12..]`
+pub fn create2_address(
+    deployer: &[u8; 20],
+    salt: &[u8; 32],
+    init_code_hash: &[u8; 32],
+) -> [u8; 20] {
+    let mut preimage = [0u8; 85];
+    preimage[0] = 0xff;
+    preimage[1..21].copy_from_slice(deployer);
+    preimage[21..53].copy_from_slice(salt);
+    preimage[53..85].copy_from_slice(init_code_hash);
+
+    let mut hash = [0u8; 32];
+    hash_raw_bytes(&mut hash, &preimage);
+    let mut address = [0u8; 20];
+    address.copy_from_slice(&hash[12..]);
+    address
+}
@@ -0,0 +1,142 @@
+//! ## Disassembler
+//!
+//! Decodes bytecode into a listing of instructions, optionally annotated with a source map.
+
+use crate::{
+    bytes_util::bytes_to_hex,
+    evm::Opcode,
+    source_map::{find_source, SourceMapEntry},
+};
+use std::{collections::BTreeSet, fmt::Write};
+
+/// A decoded instruction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Instruction {
+    /// An opcode and its immediate bytes
+    Opcode {
+        /// The program counter
+        pc: usize,
+        /// The opcode
+        opcode: Opcode,
+        /// The immediate bytes of a PUSH opcode
+        immediate: Vec<u8>,
+    },
+    /// Bytes that aren't executed as code, ie. a code table
+    Data {
+        /// The program counter
+        pc: usize,
+        /// The data bytes
+        bytes: Vec<u8>,
+    },
+}
+
+impl Instruction {
+    /// The program counter of the instruction
+    pub fn pc(&self) -> usize {
+        match self {
+            Instruction::Opcode { pc, .. } | Instruction::Data { pc, .. } => *pc,
+        }
+    }
+}
+
+/// Disassembles bytecode into instructions.
+///
+/// Bytes mapped to table data by the source map are decoded as data. Otherwise, unreachable bytes
+/// following a terminating opcode up to the next `JUMPDEST`, and unknown opcodes, are decoded as
+/// data.
+pub fn disassemble(code: &[u8], source_map: &[SourceMapEntry]) -> Vec<Instruction> {
+    let mut instructions: Vec<Instruction> = vec![];
+    let mut reachable = true;
+    let mut pc = 0;
+
+    while pc < code.len() {
+        let source = find_source(source_map, pc);
+        let is_data = match source {
+            Some(s) => s.data,
+            None => !reachable && code[pc] != Opcode::Jumpdest.byte(),
+        };
+
+        match Opcode::from_byte(code[pc]).filter(|_| !is_data) {
+            Some(opcode) => {
+                let end = (pc + 1 + opcode.immediate_size()).min(code.len());
+                instructions.push(Instruction::Opcode {
+                    pc,
+                    opcode,
+                    immediate: code[pc + 1..end].to_vec(),
+                });
+                reachable = !opcode.is_terminating();
+                pc = end;
+            }
+            None => {
+                // Coalesce consecutive data bytes
+                match instructions.last_mut() {
+                    Some(Instruction::Data { bytes, .. }) => bytes.push(code[pc]),
+                    _ => instructions.push(Instruction::Data { pc, bytes: vec![code[pc]] }),
+                }
+                pc += 1;
+            }
+        }
+    }
+
+    instructions
+}
+
+/// Formats disassembled instructions as a listing.
+///
+/// `JUMPDEST`s are labeled and jumps to them reference their label. Each range of instructions is
+/// preceded by the macro or table, and source line, it was generated from.
+pub fn format_disassembly(instructions: &[Instruction], source_map: &[SourceMapEntry]) -> String {
+    let jumpdests = instructions
+        .iter()
+        .filter_map(|i| match i {
+            Instruction::Opcode { pc, opcode: Opcode::Jumpdest, .. } => Some(*pc),
+            _ => None,
+        })
+        .collect::<BTreeSet<usize>>();
+
+    let mut listing = String::new();
+    let mut current_source = None;
+    for instruction in instructions {
+        let pc = instruction.pc();
+
+        // Only annotate where the macro or source line changes
+        let source = find_source(source_map, pc).map(|s| (&s.name, &s.file, s.line));
+        if source != current_source {
+            if let Some((name, file, line)) = source {
+                let location = match (file, line) {
+                    (Some(f), l) if l > 0 => format!(" ({}:{})", f, l),
+                    (Some(f), _) => format!(" ({})", f),
+                    _ => String::new(),
+                };
+                let _ = writeln!(listing, "        // {}{}", name, location);
+            }
+            current_source = source;
+        }
+
+        match instruction {
+            Instruction::Opcode { opcode, immediate, .. } => {
+                if jumpdests.contains(&pc) {
+                    let _ = writeln!(listing, "label_{:04x}:", pc);
+                }
+                let _ = write!(listing, "{:04x}    {}", pc, opcode.mnemonic().to_uppercase());
+                if !immediate.is_empty() {
+                    let _ = write!(listing, " 0x{}", bytes_to_hex(immediate));
+                    let target = (immediate.len() <= 4)
+                        .then(|| immediate.iter().fold(0usize, |acc, b| (acc << 8) | *b as usize));
+                    if let Some(target) = target.filter(|t| jumpdests.contains(t)) {
+                        let _ = write!(listing, " // label_{:04x}", target);
+                    }
+                }
+                listing.push('\n');
+            }
+            Instruction::Data { bytes, .. } => {
+                bytes.chunks(32).enumerate().for_each(|(i, chunk)| {
+                    let _ =
+                        writeln!(listing, "{:04x}    DATA 0x{}", pc + i * 32, bytes_to_hex(chunk));
+                });
+            }
+        }
+    }
+
+    listing
+}
@@ -0,0 +1,294 @@
+//! ## Constant Environment
+//!
+//! Constant values resolved once per compilation, with free pointers allocated and overrides
+//! applied, and passed through codegen in place of the contract's constant definitions.
+
+use crate::{
+    ast::{
+        ConstVal, ConstantDefinition, Contract, FreeMemoryPointer, FreeStoragePointer, Literal,
+        MacroDefinition, Statement, StatementType,
+    },
+    bytecode::ExpansionCache,
+    bytes_util::{erc7201_slot, str_to_bytes32},
+    error::{CodegenError, CodegenErrorKind},
+    layout::{MemoryRegion, PointerLayout, StorageSlot},
+};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    sync::Mutex,
+};
+
+/// The bound on memory offsets, past which memory expansion costs more gas than a block allows
+pub const MEMORY_BOUND: usize = 1 << 32;
+
+/// The offset static memory regions start at by default
+///
+/// Regions are placed past the scratch space (`0x00` to `0x3f`), free memory pointer (`0x40`) and
+/// zero slot (`0x60`), which hand-written Huff and Solidity-style libraries rely on.
+pub const MEMORY_BASE: usize = 0x80;
+
+/// The order `FREE_STORAGE_POINTER()` constants are assigned sequential storage slots in
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum StorageOrder {
+    /// The order the constants are first referenced in, traversing `CONSTRUCTOR` then `MAIN`
+    ///
+    /// This is the layout of earlier compiler versions, which deployed contracts rely on.
+    /// Constants that aren't reached are left underived, and slot numbers are written in decimal
+    /// but read as hex, ie. the eleventh pointer is assigned slot `0x10`.
+    #[default]
+    Traversal,
+    /// The order the constants are defined in, whether or not they are referenced
+    Definition,
+}
+
+impl TryFrom<&str> for StorageOrder {
+    type Error = String;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        match s {
+            "traversal" => Ok(StorageOrder::Traversal),
+            "definition" => Ok(StorageOrder::Definition),
+            _ => Err(format!("Unknown storage order \"{}\", expected definition or traversal", s)),
+        }
+    }
+}
+
+/// How the free pointer constants of a Contract are allocated
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Allocation {
+    /// The order sequential storage slots are assigned in
+    pub storage_order: StorageOrder,
+    /// The offset the first static memory region starts at
+    pub memory_base: usize,
+}
+
+impl Default for Allocation {
+    fn default() -> Self {
+        Allocation { storage_order: StorageOrder::default(), memory_base: MEMORY_BASE }
+    }
+}
+
+/// The resolved constants a Contract is compiled with
+///
+/// The parsed [Contract] is never modified: free pointers are derived and constant overrides
+/// applied into a separate environment, which is passed to code generation alongside the AST. A
+/// single AST can thus be compiled under several environments concurrently.
+#[derive(Debug, Default)]
+pub struct ConstantEnv {
+    /// The resolved constants by name, all of which have a literal value
+    pub constants: HashMap<String, ConstantDefinition>,
+    /// The slots and regions assigned to the free pointer constants
+    pub layout: PointerLayout,
+    /// Cached macro expansions, which are only valid for these constant values
+    pub expansions: Mutex<ExpansionCache>,
+}
+
+impl ConstantEnv {
+    /// Resolves the constants of a Contract with the default [Allocation]
+    ///
+    /// Overrides replace the value of the constant with the same name, or are added if the
+    /// Contract doesn't define it. Override constants can be passed in via the CLI.
+    ///
+    /// Errors if the memory regions extend past [MEMORY_BOUND].
+    pub fn new(
+        contract: &Contract,
+        overrides: Option<&BTreeMap<&str, Literal>>,
+    ) -> Result<Self, CodegenError> {
+        ConstantEnv::with_allocation(contract, overrides, Allocation::default())
+    }
+
+    /// Resolves the constants of a Contract, allocating its free pointers as configured
+    ///
+    /// Namespaced pointers, ie. `FREE_STORAGE_POINTER("namespace.id")`, are assigned their
+    /// ERC-7201 slot. All other storage pointers are assigned sequential slots in the configured
+    /// [StorageOrder]. Transient pointers are assigned sequential slots, and memory pointers
+    /// consecutive regions starting at the memory base, in the order the constants are defined.
+    ///
+    /// Storage pointers left underived keep their [ConstVal::FreeStoragePointer] value, which
+    /// codegen rejects if they are reached.
+    ///
+    /// Errors if the memory regions extend past [MEMORY_BOUND].
+    pub fn with_allocation(
+        contract: &Contract,
+        overrides: Option<&BTreeMap<&str, Literal>>,
+        allocation: Allocation,
+    ) -> Result<Self, CodegenError> {
+        // The position of each storage pointer in traversal order
+        let traversed = match allocation.storage_order {
+            StorageOrder::Traversal => Some(
+                referenced_constants(contract)
+                    .into_iter()
+                    .filter(|name| is_sequential_storage_pointer(contract, name))
+                    .enumerate()
+                    .map(|(p, name)| (name, p))
+                    .collect::<HashMap<String, usize>>(),
+            ),
+            StorageOrder::Definition => None,
+        };
+        let mut env = ConstantEnv::default();
+        let mut storage = vec![];
+        let mut last_assigned_free_pointer = 0;
+        let mut last_assigned_transient_pointer = 0;
+        let mut last_assigned_memory_offset = allocation.memory_base;
+
+        for c in contract.constants.iter() {
+            let value = match &c.value {
+                ConstVal::FreeStoragePointer(FreeStoragePointer { namespace: Some(ns) }) => {
+                    let slot = erc7201_slot(ns);
+                    storage.push((slot, StorageSlot::new(&c.name, &slot, &c.span)));
+                    ConstVal::Literal(slot)
+                }
+                ConstVal::FreeStoragePointer(FreeStoragePointer { namespace: None }) => {
+                    let slot = match &traversed {
+                        // Only the first definition of a constant is referenced
+                        Some(_) if env.constants.contains_key(&c.name) => None,
+                        Some(traversed) => {
+                            traversed.get(&c.name).map(|p| str_to_bytes32(&format!("{}", p)))
+                        }
+                        None => {
+                            let slot = str_to_bytes32(&format!("{:x}", last_assigned_free_pointer));
+                            last_assigned_free_pointer += 1;
+                            Some(slot)
+                        }
+                    };
+                    match slot {
+                        Some(slot) => {
+                            storage.push((slot, StorageSlot::new(&c.name, &slot, &c.span)));
+                            ConstVal::Literal(slot)
+                        }
+                        None => {
+                            tracing::warn!(target: "env", "STORAGE POINTER \"{}\" NOT REFERENCED FROM CONSTRUCTOR OR MAIN, LEAVING IT UNDERIVED", c.name);
+                            c.value.clone()
+                        }
+                    }
+                }
+                ConstVal::FreeTransientPointer(_) => {
+                    let slot = str_to_bytes32(&format!("{:x}", last_assigned_transient_pointer));
+                    last_assigned_transient_pointer += 1;
+                    env.layout.transient.push(StorageSlot::new(&c.name, &slot, &c.span));
+                    ConstVal::Literal(slot)
+                }
+                ConstVal::FreeMemoryPointer(FreeMemoryPointer { size }) => {
+                    let offset = last_assigned_memory_offset;
+                    last_assigned_memory_offset = match offset.checked_add(*size) {
+                        Some(end) if end <= MEMORY_BOUND => end,
+                        _ => {
+                            tracing::error!(target: "env", "MEMORY POINTER \"{}\" OUT OF BOUNDS", c.name);
+                            return Err(CodegenError {
+                                kind: CodegenErrorKind::MemoryPointerOutOfBounds(c.name.clone()),
+                                span: c.span.clone(),
+                                token: None,
+                                backtrace: Box::default(),
+                            })
+                        }
+                    };
+                    env.layout.memory.push(MemoryRegion::new(&c.name, offset, *size, &c.span));
+                    ConstVal::Literal(str_to_bytes32(&format!("{:x}", offset)))
+                }
+                ConstVal::Literal(l) => ConstVal::Literal(*l),
+            };
+            if !matches!(c.value, ConstVal::Literal(_)) {
+                tracing::debug!(target: "env", "Derived pointer \"{}\": {:?}", c.name, value);
+            }
+
+            // The first definition of a constant takes precedence
+            env.constants.entry(c.name.clone()).or_insert_with(|| ConstantDefinition {
+                name: c.name.clone(),
+                value,
+                span: c.span.clone(),
+            });
+        }
+
+        // Order the storage layout by slot
+        storage.sort_by_key(|(slot, _)| *slot);
+        env.layout.storage = storage.into_iter().map(|(_, slot)| slot).collect();
+
+        for (name, value) in overrides.into_iter().flatten() {
+            let constant =
+                env.constants.entry(name.to_string()).or_insert_with(|| ConstantDefinition {
+                    name: name.to_string(),
+                    value: ConstVal::Literal(*value),
+                    span: Default::default(),
+                });
+            constant.value = ConstVal::Literal(*value);
+        }
+
+        Ok(env)
+    }
+
+    /// Returns the constant with the provided name
+    pub fn find_constant_by_name(&self, name: &str) -> Option<&ConstantDefinition> {
+        self.constants.get(name)
+    }
+}
+
+/// Whether the first definition of a constant is a sequentially allocated storage pointer
+fn is_sequential_storage_pointer(contract: &Contract, name: &str) -> bool {
+    matches!(
+        contract.constants.iter().find(|c| c.name == name).map(|c| &c.value),
+        Some(ConstVal::FreeStoragePointer(FreeStoragePointer { namespace: None }))
+    )
+}
+
+/// The constants referenced from `CONSTRUCTOR` then `MAIN`, in the order they are first reached
+///
+/// Invoked macros, and macros passed to builtins, are traversed depth-first where they are
+/// referenced. Constants passed as macro arguments are not reached.
+fn referenced_constants(contract: &Contract) -> Vec<String> {
+    let mut referenced = vec![];
+    let mut visited = HashSet::new();
+    for name in ["CONSTRUCTOR", "MAIN"] {
+        if let Some(m) = contract.find_macro_by_name(name) {
+            traverse_macro(contract, m, &mut visited, &mut referenced);
+        }
+    }
+    referenced
+}
+
+fn traverse_macro<'a>(
+    contract: &'a Contract,
+    macro_def: &'a MacroDefinition,
+    visited: &mut HashSet<&'a str>,
+    referenced: &mut Vec<String>,
+) {
+    // A macro that was already traversed can't reference any new constants
+    if visited.insert(&macro_def.name) {
+        traverse_statements(contract, &macro_def.statements, visited, referenced);
+    }
+}
+
+fn traverse_statements<'a>(
+    contract: &'a Contract,
+    statements: &'a [Statement],
+    visited: &mut HashSet<&'a str>,
+    referenced: &mut Vec<String>,
+) {
+    for statement in statements {
+        match &statement.ty {
+            StatementType::Constant(name) if !referenced.contains(name) => {
+                referenced.push(name.clone());
+            }
+            StatementType::MacroInvocation(mi) => {
+                if let Some(m) = contract.find_macro_by_name(&mi.macro_name) {
+                    traverse_macro(contract, m, visited, referenced);
+                }
+            }
+            StatementType::BuiltinFunctionCall(bfc) => {
+                for m in bfc
+                    .args
+                    .iter()
+                    .filter_map(|a| a.name.as_ref())
+                    .filter_map(|name| contract.macros.iter().find(|m| m.name == *name))
+                {
+                    traverse_macro(contract, m, visited, referenced);
+                }
+            }
+            StatementType::Label(l) => traverse_statements(contract, &l.inner, visited, referenced),
+            StatementType::Conditional(c) => {
+                traverse_statements(contract, &c.statements, visited, referenced);
+                traverse_statements(contract, &c.else_statements, visited, referenced);
+            }
+            _ => {}
+        }
+    }
+}
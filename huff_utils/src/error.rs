@@ -1,7 +1,7 @@
 use crate::{
     files::{Span, Spanned},
     io::UnpackError,
-    prelude::{parse_extension, AstSpan},
+    prelude::{bytes_to_hex, parse_extension, AstSpan, MacroParamKind},
     report::{Report, Reporter},
     token::TokenKind,
 };
@@ -61,6 +61,16 @@ pub enum ParserErrorKind {
     InvalidDecoratorFlag(String),
     /// Invalid decorator flag argument
     InvalidDecoratorFlagArg(TokenKind),
+    /// Invalid macro parameter kind
+    InvalidMacroParamKind(String),
+    /// A macro argument doesn't match the kind of its parameter (parameter kind, parameter name)
+    MacroArgumentKindMismatch(MacroParamKind, String),
+    /// A parameter type that is neither an EVM type nor a defined struct
+    UnknownType(String),
+    /// An included JSON ABI file that can't be deserialized
+    InvalidAbi(String),
+    /// An included Solidity interface file that can't be parsed
+    InvalidSolInterface(String),
 }
 
 /// A Lexing Error
@@ -123,12 +133,46 @@ pub struct CodegenError {
     pub span: AstSpan,
     /// An Optional Token Kind
     pub token: Option<TokenKind>,
+    /// The macro expansions the error occured in, innermost first. Boxed to keep the error small,
+    /// as it's returned by most of codegen.
+    pub backtrace: Box<Vec<ExpansionFrame>>,
 }
 
 impl CodegenError {
     /// Public associated function to instatiate a new CodegenError.
     pub fn new(kind: CodegenErrorKind, spans: AstSpan, token: Option<TokenKind>) -> Self {
-        Self { kind, span: spans, token }
+        Self { kind, span: spans, token, backtrace: Box::default() }
+    }
+
+    /// Records the expansion of a macro the error occured in
+    pub fn in_expansion_of(mut self, macro_name: &str, invoked_from: &str, span: &AstSpan) -> Self {
+        self.backtrace.push(ExpansionFrame {
+            macro_name: macro_name.to_string(),
+            invoked_from: invoked_from.to_string(),
+            span: span.clone(),
+        });
+        self
+    }
+}
+
+/// A Macro Expansion in a Codegen Error Backtrace
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ExpansionFrame {
+    /// The expanded macro
+    pub macro_name: String,
+    /// The macro containing the invocation
+    pub invoked_from: String,
+    /// The span of the invocation
+    pub span: AstSpan,
+}
+
+impl fmt::Display for ExpansionFrame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "in expansion of {}, invoked from {}", self.macro_name, self.invoked_from)?;
+        match self.span.location() {
+            Some(location) => write!(f, " at {}", location),
+            None => Ok(()),
+        }
     }
 }
 
@@ -137,8 +181,8 @@ impl CodegenError {
 pub enum CodegenErrorKind {
     /// Locking Error
     LockingError,
-    /// Storage Pointers Not Derived
-    StoragePointersNotDerived,
+    /// A Storage Pointer Constant was not Derived
+    StoragePointerNotDerived(String),
     /// Invalid Macro Body Statement
     InvalidMacroStatement,
     /// The Macro Definition is Missing
@@ -173,6 +217,24 @@ pub enum CodegenErrorKind {
     InvalidCodeLength(usize),
     /// Test Invocation
     TestInvocation(String),
+    /// Definitions share a 4-byte selector (definition names, selector)
+    SelectorCollision(Vec<String>, [u8; 4]),
+    /// An Argument invoked as a Macro is missing from the Macro Invocation
+    MissingMacroArgument(String),
+    /// An Argument invoked as a Macro isn't a Macro (passed value)
+    InvalidMacroArgument(String),
+    /// A Macro is invoked with the wrong number of arguments (macro name, expected, passed)
+    MacroArityMismatch(String, usize, usize),
+    /// An Argument doesn't match the kind of its Macro parameter (parameter kind, parameter name)
+    MacroArgumentKindMismatch(MacroParamKind, String),
+    /// A Macro invokes itself, directly or through a cycle (invocation chain)
+    RecursiveMacroInvocation(Vec<String>),
+    /// A builtin argument names several overloads (overload signatures)
+    AmbiguousOverload(Vec<String>),
+    /// A free memory pointer's region extends past the memory bound (constant name)
+    MemoryPointerOutOfBounds(String),
+    /// A `__REPEAT` unrolls to more code than the contract size limit (repeated macro name)
+    RepeatTooLarge(String),
 }
 
 impl Spanned for CodegenError {
@@ -187,8 +249,8 @@ impl<W: Write> Report<W> for CodegenError {
             CodegenErrorKind::LockingError => {
                 write!(f.out, "Synchronisation Error - Please execute again!")
             }
-            CodegenErrorKind::StoragePointersNotDerived => {
-                write!(f.out, "Storage pointers not derived for AST!")
+            CodegenErrorKind::StoragePointerNotDerived(name) => {
+                write!(f.out, "Storage pointer \"{}\" not derived for AST!", name)
             }
             CodegenErrorKind::InvalidMacroStatement => write!(f.out, "Invalid Macro Statement!"),
             CodegenErrorKind::InvalidMacroInvocation(str) => {
@@ -231,6 +293,47 @@ impl<W: Write> Report<W> for CodegenError {
             CodegenErrorKind::TestInvocation(msg) => {
                 write!(f.out, "Test cannot be invoked: \"{}\"", msg)
             }
+            CodegenErrorKind::SelectorCollision(names, selector) => {
+                write!(
+                    f.out,
+                    "Selector collision: \"{}\" share selector 0x{}",
+                    names.join("\" and \""),
+                    bytes_to_hex(selector)
+                )
+            }
+            CodegenErrorKind::MissingMacroArgument(arg) => {
+                write!(f.out, "Missing macro argument \"{}\"", arg)
+            }
+            CodegenErrorKind::InvalidMacroArgument(value) => {
+                write!(f.out, "Argument \"{}\" invoked as a macro is not a macro", value)
+            }
+            CodegenErrorKind::MacroArityMismatch(name, expected, passed) => {
+                write!(
+                    f.out,
+                    "Macro \"{}\" takes {} arguments but {} were passed",
+                    name, expected, passed
+                )
+            }
+            CodegenErrorKind::MacroArgumentKindMismatch(kind, param) => {
+                write!(f.out, "Argument passed to parameter \"{}\" is not a {}", param, kind)
+            }
+            CodegenErrorKind::RecursiveMacroInvocation(chain) => {
+                write!(
+                    f.out,
+                    "Recursive invocation of macro \"{}\": {}",
+                    chain.last().cloned().unwrap_or_default(),
+                    chain.join(" -> ")
+                )
+            }
+            CodegenErrorKind::AmbiguousOverload(signatures) => {
+                write!(f.out, "Overloaded, pass one of the signatures: {}", signatures.join(", "))
+            }
+            CodegenErrorKind::MemoryPointerOutOfBounds(name) => {
+                write!(f.out, "Memory pointer \"{}\" extends past the 2^32 byte memory bound", name)
+            }
+            CodegenErrorKind::RepeatTooLarge(name) => {
+                write!(f.out, "Repeating macro \"{}\" exceeds the 24KB code size limit", name)
+            }
         }
     }
 }
@@ -468,98 +571,224 @@ impl<'a> fmt::Display for CompilerError<'a> {
                         pe.spans.error(pe.hint.as_ref())
                     )
                 }
-            },
-            CompilerError::PathBufRead(os_str) => {
-                write!(
-                    f,
-                    "\nError: Invalid Import Path: \"{}\"",
-                    os_str.as_os_str().to_str().unwrap_or("<unknown import>")
-                )
-            }
-            CompilerError::CodegenError(ce) => match &ce.kind {
-                CodegenErrorKind::LockingError => {
-                    write!(f, "\nError: Synchronisation Failure\n")
-                }
-                CodegenErrorKind::StoragePointersNotDerived => {
-                    write!(f, "\nError: Storage Pointers Not Derived\n{}\n", ce.span.error(None))
-                }
-                CodegenErrorKind::InvalidMacroStatement => {
-                    write!(f, "\nError: Invalid Macro Statement\n{}\n", ce.span.error(None))
-                }
-                CodegenErrorKind::MissingMacroDefinition(md) => {
+                ParserErrorKind::InvalidMacroParamKind(kind) => {
                     write!(
                         f,
-                        "\nError: Missing Macro Definition For \"{}\"\n{}",
-                        md,
-                        ce.span.file()
+                        "\nError: Invalid Macro Parameter Kind: \"{}\" \n{}\n",
+                        kind,
+                        pe.spans.error(pe.hint.as_ref())
                     )
                 }
-                CodegenErrorKind::InvalidMacroInvocation(mmi) => {
+                ParserErrorKind::MacroArgumentKindMismatch(kind, param) => {
                     write!(
                         f,
-                        "\nError: Missing Macro Definition For Invocation: \"{}\"\n{}\n",
-                        mmi,
-                        ce.span.error(None)
+                        "\nError: Argument passed to parameter \"{}\" is not a {} \n{}\n",
+                        param,
+                        kind,
+                        pe.spans.error(pe.hint.as_ref())
                     )
                 }
-                CodegenErrorKind::MissingFunctionInterface(func) => {
+                ParserErrorKind::UnknownType(ty) => {
                     write!(
                         f,
-                        "\nError: Missing Function Interface: \"{}\"\n{}\n",
-                        func,
-                        ce.span.error(None)
+                        "\nError: Unknown Type: \"{}\" \n{}\n",
+                        ty,
+                        pe.spans.error(pe.hint.as_ref())
                     )
                 }
-                CodegenErrorKind::MissingEventInterface(event) => {
+                ParserErrorKind::InvalidAbi(e) => {
                     write!(
                         f,
-                        "\nError: Missing Event Interface: \"{}\"\n{}\n",
-                        event,
-                        ce.span.error(None)
+                        "\nError: Invalid ABI: \"{}\" \n{}\n",
+                        e,
+                        pe.spans.error(pe.hint.as_ref())
                     )
                 }
-                CodegenErrorKind::MissingConstantDefinition(_) => {
-                    write!(f, "\nError: Missing Constant Definition\n{}\n", ce.span.error(None))
-                }
-                CodegenErrorKind::MissingErrorDefinition(_) => {
-                    write!(f, "\nError: Missing Error Definition\n{}\n", ce.span.error(None))
-                }
-                CodegenErrorKind::AbiGenerationFailure => {
-                    write!(f, "\nError: ABI Generation Failed\n{}\n", ce.span.error(None))
-                }
-                CodegenErrorKind::IOError(ioe) => {
-                    write!(f, "\nError: IO Error: {}\n{}", ioe, ce.span.file())
-                }
-                CodegenErrorKind::UnkownArgcallType => {
-                    write!(f, "\nError: Unknown Arg Call Type\n{}\n", ce.span.error(None))
-                }
-                CodegenErrorKind::MissingMacroInvocation(mmi) => {
+                ParserErrorKind::InvalidSolInterface(e) => {
                     write!(
                         f,
-                        "\nError: Missing Macro Invocation: \"{}\"\n{}\n",
-                        mmi,
-                        ce.span.error(None)
+                        "\nError: Invalid Solidity Interface: \"{}\" \n{}\n",
+                        e,
+                        pe.spans.error(pe.hint.as_ref())
                     )
                 }
-                CodegenErrorKind::UnmatchedJumpLabel => {
-                    write!(f, "\nError: Unmatched Jump Label\n{}\n", ce.span.error(None))
-                }
-                CodegenErrorKind::UsizeConversion(_) => {
-                    write!(f, "\nError: Usize Conversion\n{}\n", ce.span.error(None))
-                }
-                CodegenErrorKind::InvalidArguments(_) => {
-                    write!(f, "\nError: Invalid Arguments\n{}\n", ce.span.error(None))
-                }
-                CodegenErrorKind::InvalidTableStatement(_) => {
-                    write!(f, "\nError: Invalid Table Statement\n{}\n", ce.span.error(None))
-                }
-                CodegenErrorKind::InvalidCodeLength(_) => {
-                    write!(f, "\nError: Invalid Code Length\n{}\n", ce.span.error(None))
-                }
-                CodegenErrorKind::TestInvocation(_) => {
-                    write!(f, "\nError: Test Invocation\n{}\n", ce.span.error(None))
-                }
             },
+            CompilerError::PathBufRead(os_str) => {
+                write!(
+                    f,
+                    "\nError: Invalid Import Path: \"{}\"",
+                    os_str.as_os_str().to_str().unwrap_or("<unknown import>")
+                )
+            }
+            CompilerError::CodegenError(ce) => {
+                match &ce.kind {
+                    CodegenErrorKind::LockingError => {
+                        write!(f, "\nError: Synchronisation Failure\n")
+                    }
+                    CodegenErrorKind::StoragePointerNotDerived(name) => {
+                        write!(
+                            f,
+                            "\nError: Storage Pointer Not Derived: \"{}\"\n{}\n",
+                            name,
+                            ce.span.error(None)
+                        )
+                    }
+                    CodegenErrorKind::InvalidMacroStatement => {
+                        write!(f, "\nError: Invalid Macro Statement\n{}\n", ce.span.error(None))
+                    }
+                    CodegenErrorKind::MissingMacroDefinition(md) => {
+                        write!(
+                            f,
+                            "\nError: Missing Macro Definition For \"{}\"\n{}",
+                            md,
+                            ce.span.file()
+                        )
+                    }
+                    CodegenErrorKind::InvalidMacroInvocation(mmi) => {
+                        write!(
+                            f,
+                            "\nError: Missing Macro Definition For Invocation: \"{}\"\n{}\n",
+                            mmi,
+                            ce.span.error(None)
+                        )
+                    }
+                    CodegenErrorKind::MissingFunctionInterface(func) => {
+                        write!(
+                            f,
+                            "\nError: Missing Function Interface: \"{}\"\n{}\n",
+                            func,
+                            ce.span.error(None)
+                        )
+                    }
+                    CodegenErrorKind::MissingEventInterface(event) => {
+                        write!(
+                            f,
+                            "\nError: Missing Event Interface: \"{}\"\n{}\n",
+                            event,
+                            ce.span.error(None)
+                        )
+                    }
+                    CodegenErrorKind::MissingConstantDefinition(_) => {
+                        write!(f, "\nError: Missing Constant Definition\n{}\n", ce.span.error(None))
+                    }
+                    CodegenErrorKind::MissingErrorDefinition(_) => {
+                        write!(f, "\nError: Missing Error Definition\n{}\n", ce.span.error(None))
+                    }
+                    CodegenErrorKind::AbiGenerationFailure => {
+                        write!(f, "\nError: ABI Generation Failed\n{}\n", ce.span.error(None))
+                    }
+                    CodegenErrorKind::IOError(ioe) => {
+                        write!(f, "\nError: IO Error: {}\n{}", ioe, ce.span.file())
+                    }
+                    CodegenErrorKind::UnkownArgcallType => {
+                        write!(f, "\nError: Unknown Arg Call Type\n{}\n", ce.span.error(None))
+                    }
+                    CodegenErrorKind::MissingMacroInvocation(mmi) => {
+                        write!(
+                            f,
+                            "\nError: Missing Macro Invocation: \"{}\"\n{}\n",
+                            mmi,
+                            ce.span.error(None)
+                        )
+                    }
+                    CodegenErrorKind::UnmatchedJumpLabel => {
+                        write!(f, "\nError: Unmatched Jump Label\n{}\n", ce.span.error(None))
+                    }
+                    CodegenErrorKind::UsizeConversion(_) => {
+                        write!(f, "\nError: Usize Conversion\n{}\n", ce.span.error(None))
+                    }
+                    CodegenErrorKind::InvalidArguments(_) => {
+                        write!(f, "\nError: Invalid Arguments\n{}\n", ce.span.error(None))
+                    }
+                    CodegenErrorKind::InvalidTableStatement(_) => {
+                        write!(f, "\nError: Invalid Table Statement\n{}\n", ce.span.error(None))
+                    }
+                    CodegenErrorKind::InvalidCodeLength(_) => {
+                        write!(f, "\nError: Invalid Code Length\n{}\n", ce.span.error(None))
+                    }
+                    CodegenErrorKind::TestInvocation(_) => {
+                        write!(f, "\nError: Test Invocation\n{}\n", ce.span.error(None))
+                    }
+                    CodegenErrorKind::SelectorCollision(names, selector) => {
+                        write!(
+                            f,
+                            "\nError: Selector Collision: \"{}\" share selector 0x{}\n{}\n",
+                            names.join("\" and \""),
+                            bytes_to_hex(selector),
+                            ce.span.error(None)
+                        )
+                    }
+                    CodegenErrorKind::MissingMacroArgument(arg) => {
+                        write!(
+                            f,
+                            "\nError: Missing Macro Argument \"{}\"\n{}\n",
+                            arg,
+                            ce.span.error(None)
+                        )
+                    }
+                    CodegenErrorKind::InvalidMacroArgument(value) => {
+                        write!(
+                            f,
+                            "\nError: Argument \"{}\" invoked as a macro is not a macro\n{}\n",
+                            value,
+                            ce.span.error(None)
+                        )
+                    }
+                    CodegenErrorKind::MacroArityMismatch(name, expected, passed) => {
+                        write!(
+                            f,
+                            "\nError: Macro \"{}\" takes {} arguments but {} were passed\n{}\n",
+                            name,
+                            expected,
+                            passed,
+                            ce.span.error(None)
+                        )
+                    }
+                    CodegenErrorKind::MacroArgumentKindMismatch(kind, param) => {
+                        write!(
+                            f,
+                            "\nError: Argument passed to parameter \"{}\" is not a {}\n{}\n",
+                            param,
+                            kind,
+                            ce.span.error(None)
+                        )
+                    }
+                    CodegenErrorKind::RecursiveMacroInvocation(chain) => {
+                        write!(
+                            f,
+                            "\nError: Recursive Invocation of Macro \"{}\": {}\n{}\n",
+                            chain.last().cloned().unwrap_or_default(),
+                            chain.join(" -> "),
+                            ce.span.error(None)
+                        )
+                    }
+                    CodegenErrorKind::AmbiguousOverload(signatures) => {
+                        write!(
+                            f,
+                            "\nError: Ambiguous Overload, pass one of the signatures: {}\n{}\n",
+                            signatures.join(", "),
+                            ce.span.error(None)
+                        )
+                    }
+                    CodegenErrorKind::MemoryPointerOutOfBounds(name) => {
+                        write!(
+                            f,
+                            "\nError: Memory Pointer Out Of Bounds: \"{}\" extends past the 2^32 byte memory bound\n{}\n",
+                            name,
+                            ce.span.error(None)
+                        )
+                    }
+                    CodegenErrorKind::RepeatTooLarge(name) => {
+                        write!(
+                            f,
+                            "\nError: Repeat Too Large: repeating macro \"{}\" exceeds the 24KB code size limit\n{}\n",
+                            name,
+                            ce.span.error(None)
+                        )
+                    }
+                }?;
+                ce.backtrace.iter().try_for_each(|frame| writeln!(f, "  {}", frame))
+            }
             CompilerError::FailedCompiles(v) => {
                 v.iter().for_each(|ce| {
                     let _ = write!(f, "{}", ce);
@@ -1,3 +1,4 @@
+use lazy_static::lazy_static;
 use phf::phf_map;
 use std::fmt;
 use strum_macros::EnumString;
@@ -743,6 +744,51 @@ impl Opcode {
         };
         opcode_str.to_string()
     }
+
+    /// Decodes an Opcode from its byte, if the byte is a known opcode
+    pub fn from_byte(byte: u8) -> Option<Opcode> {
+        OPCODES_BY_BYTE[byte as usize]
+    }
+
+    /// The opcode's byte
+    pub fn byte(&self) -> u8 {
+        u8::from_str_radix(&self.string(), 16).unwrap_or_default()
+    }
+
+    /// The opcode's mnemonic, ie. `push1`
+    pub fn mnemonic(&self) -> &'static str {
+        OPCODES_MAP.entries().find(|(_, o)| *o == self).map(|(m, _)| *m).unwrap_or_default()
+    }
+
+    /// The number of immediate bytes following the opcode in bytecode
+    pub fn immediate_size(&self) -> usize {
+        match self.byte() {
+            b @ 0x60..=0x7f => (b - 0x5f) as usize,
+            _ => 0,
+        }
+    }
+
+    /// Whether execution can't continue to the next instruction after the opcode
+    pub fn is_terminating(&self) -> bool {
+        matches!(
+            self,
+            Opcode::Stop |
+                Opcode::Jump |
+                Opcode::Return |
+                Opcode::Revert |
+                Opcode::Invalid |
+                Opcode::Selfdestruct
+        )
+    }
+}
+
+lazy_static! {
+    /// Lookup table of opcodes by byte
+    static ref OPCODES_BY_BYTE: [Option<Opcode>; 256] = {
+        let mut opcodes = [None; 256];
+        OPCODES_MAP.values().for_each(|o| opcodes[o.byte() as usize] = Some(*o));
+        opcodes
+    };
 }
 
 impl fmt::Display for Opcode {
@@ -230,6 +230,18 @@ impl FileSource {
         }
     }
 
+    /// Whether a path is a JSON ABI file, ie. `./IERC20.abi.json`, which can be included for its
+    /// declarations
+    pub fn is_abi(path: &str) -> bool {
+        path.ends_with(".abi.json")
+    }
+
+    /// Whether a path is a Solidity interface file, ie. `./IVault.sol`, which can be included for
+    /// its declarations
+    pub fn is_sol_interface(path: &str) -> bool {
+        path.ends_with(".sol")
+    }
+
     /// Localizes a file path, if path is relative
     pub fn localize_file(parent: &str, child: &str) -> Option<String> {
         let mut prefix = match FileSource::derive_dir(parent) {
@@ -317,6 +329,13 @@ impl Span {
             .unwrap_or_default()
     }
 
+    /// The 1-indexed line the span starts on, if its source is known
+    pub fn line(&self) -> Option<usize> {
+        let source = self.file.as_ref()?.source.as_ref()?;
+        let start = self.start.min(source.len());
+        Some(source.as_bytes()[..start].iter().filter(|&&c| c == b'\n').count() + 1)
+    }
+
     /// Produces a source segment string
     pub fn source_seg(&self) -> String {
         self.file
@@ -0,0 +1,81 @@
+use crate::{
+    abi::Abi,
+    ast::{Argument, Contract, FunctionType},
+};
+
+/// Generate the Huff declarations of an ABI.
+///
+/// Lets a Huff contract implement an existing Solidity interface without hand-typing its
+/// functions, events, errors, and the structs they use.
+///
+/// @param abi The ABI to declare.
+/// @return The `#define` declarations, as Huff source.
+pub fn gen_huff_interface(abi: &Abi) -> String {
+    let contract = Contract::from(abi);
+
+    let sections = [
+        contract
+            .structs
+            .iter()
+            .map(|s| format!("#define struct {}({})", s.name, declare_args(&s.fields)))
+            .collect::<Vec<_>>(),
+        contract
+            .functions
+            .iter()
+            .map(|f| {
+                format!(
+                    "#define function {}({}) {} returns ({})",
+                    f.name,
+                    declare_args(&f.inputs),
+                    mutability(&f.fn_type),
+                    declare_args(&f.outputs)
+                )
+            })
+            .collect(),
+        contract
+            .events
+            .iter()
+            .map(|e| format!("#define event {}({})", e.name, declare_args(&e.parameters)))
+            .collect(),
+        contract
+            .errors
+            .iter()
+            .map(|e| format!("#define error {}({})", e.name, declare_args(&e.parameters)))
+            .collect(),
+    ];
+
+    let declarations = sections
+        .iter()
+        .filter(|s| !s.is_empty())
+        .map(|s| s.join("\n"))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    format!("{}\n", declarations)
+}
+
+/// Declares arguments as `type [indexed] [name]`
+fn declare_args(args: &[Argument]) -> String {
+    args.iter()
+        .map(|a| {
+            let mut declaration = a.arg_type.clone().unwrap_or_default();
+            if a.indexed {
+                declaration.push_str(" indexed");
+            }
+            if let Some(name) = &a.name {
+                declaration = format!("{} {}", declaration, name);
+            }
+            declaration
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// The Huff keyword of a function's state mutability
+fn mutability(fn_type: &FunctionType) -> &str {
+    match fn_type {
+        FunctionType::View => "view",
+        FunctionType::Pure => "pure",
+        FunctionType::Payable => "payable",
+        FunctionType::NonPayable => "nonpayable",
+    }
+}
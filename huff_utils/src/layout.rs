@@ -0,0 +1,171 @@
+//! ## Storage Layout
+//!
+//! Storage slot, transient slot, and memory region assignments derived for a contract's
+//! `FREE_STORAGE_POINTER()`, `FREE_TRANSIENT_POINTER()`, and `FREE_MEMORY_POINTER(size)` constants.
+
+use crate::{
+    ast::AstSpan,
+    bytes_util::{bytes32_to_string, format_even_bytes},
+};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// A storage slot assigned to a constant
+#[derive(Default, Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct StorageSlot {
+    /// The constant name
+    pub constant: String,
+    /// The hex encoded slot
+    pub slot: String,
+    /// Where the constant is defined
+    pub span: SourceSpan,
+}
+
+impl StorageSlot {
+    /// Public associated function to instantiate a new StorageSlot.
+    pub fn new(constant: &str, slot: &[u8; 32], span: &AstSpan) -> Self {
+        Self {
+            constant: constant.to_string(),
+            slot: bytes32_to_string(slot, true),
+            span: span.into(),
+        }
+    }
+}
+
+/// A static memory region assigned to a constant
+#[derive(Default, Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct MemoryRegion {
+    /// The constant name
+    pub constant: String,
+    /// The hex encoded offset of the region
+    pub offset: String,
+    /// The size of the region in bytes
+    pub size: usize,
+    /// Where the constant is defined
+    pub span: SourceSpan,
+}
+
+impl MemoryRegion {
+    /// Public associated function to instantiate a new MemoryRegion.
+    pub fn new(constant: &str, offset: usize, size: usize, span: &AstSpan) -> Self {
+        Self {
+            constant: constant.to_string(),
+            offset: format!("0x{}", format_even_bytes(format!("{:x}", offset))),
+            size,
+            span: span.into(),
+        }
+    }
+}
+
+/// The slots and regions assigned to a contract's free pointer constants
+#[derive(Default, Debug, PartialEq, Eq, Clone)]
+pub struct PointerLayout {
+    /// Storage slots, ordered by slot
+    pub storage: Vec<StorageSlot>,
+    /// Transient storage slots
+    pub transient: Vec<StorageSlot>,
+    /// Memory regions
+    pub memory: Vec<MemoryRegion>,
+}
+
+/// A serializable source location
+#[derive(Default, Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct SourceSpan {
+    /// The path of the file containing the span
+    pub file: Option<String>,
+    /// The start of the span
+    pub start: usize,
+    /// The end of the span
+    pub end: usize,
+}
+
+impl From<&AstSpan> for SourceSpan {
+    fn from(span: &AstSpan) -> Self {
+        Self {
+            file: span.0.iter().find_map(|s| s.file.as_ref().map(|f| f.path.clone())),
+            start: span.0.iter().map(|s| s.start).min().unwrap_or(0),
+            end: span.0.iter().map(|s| s.end).max().unwrap_or(0),
+        }
+    }
+}
+
+/// An incompatible change between two storage layouts
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum StorageLayoutChange {
+    /// A constant was assigned a different slot
+    Moved {
+        /// The constant name
+        constant: String,
+        /// The previous slot
+        from: String,
+        /// The new slot
+        to: String,
+    },
+    /// A constant was removed from the layout
+    Removed {
+        /// The constant name
+        constant: String,
+        /// The slot it occupied
+        slot: String,
+    },
+    /// A slot previously held by one constant is now held by another
+    Reassigned {
+        /// The slot
+        slot: String,
+        /// The constant that previously occupied the slot
+        previous: String,
+        /// The constant that now occupies the slot
+        current: String,
+    },
+}
+
+impl fmt::Display for StorageLayoutChange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StorageLayoutChange::Moved { constant, from, to } => {
+                write!(f, "\"{}\" moved from slot {} to slot {}", constant, from, to)
+            }
+            StorageLayoutChange::Removed { constant, slot } => {
+                write!(f, "\"{}\" removed from slot {}", constant, slot)
+            }
+            StorageLayoutChange::Reassigned { slot, previous, current } => {
+                write!(f, "slot {} reassigned from \"{}\" to \"{}\"", slot, previous, current)
+            }
+        }
+    }
+}
+
+/// Compares two storage layouts, returning every change that would corrupt the storage of a
+/// contract upgraded from `old` to `new`.
+///
+/// Constants appended at previously unused slots are compatible and are not reported.
+pub fn diff_storage_layouts(old: &[StorageSlot], new: &[StorageSlot]) -> Vec<StorageLayoutChange> {
+    let mut changes = vec![];
+
+    for o in old {
+        match new.iter().find(|n| n.constant == o.constant) {
+            Some(n) if n.slot != o.slot => changes.push(StorageLayoutChange::Moved {
+                constant: o.constant.clone(),
+                from: o.slot.clone(),
+                to: n.slot.clone(),
+            }),
+            Some(_) => {}
+            None => changes.push(StorageLayoutChange::Removed {
+                constant: o.constant.clone(),
+                slot: o.slot.clone(),
+            }),
+        }
+    }
+
+    for n in new {
+        if let Some(o) = old.iter().find(|o| o.slot == n.slot && o.constant != n.constant) {
+            changes.push(StorageLayoutChange::Reassigned {
+                slot: n.slot.clone(),
+                previous: o.constant.clone(),
+                current: n.constant.clone(),
+            })
+        }
+    }
+
+    changes
+}
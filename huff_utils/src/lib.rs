@@ -18,9 +18,15 @@ pub mod ast;
 /// Bytecode Traits Module
 pub mod bytecode;
 
+/// Constant Environment Module
+pub mod env;
+
 /// Token Module
 pub mod token;
 
+/// Disassembler Module
+pub mod disassembler;
+
 /// Lexing Error Module
 pub mod error;
 
@@ -30,6 +36,12 @@ pub mod evm;
 /// Files Module
 pub mod files;
 
+/// Storage Layout Module
+pub mod layout;
+
+/// Source Map Module
+pub mod source_map;
+
 /// Lexical Reporting Module
 pub mod report;
 
@@ -45,10 +57,20 @@ pub mod bytes_util;
 /// Solidity Interface Generator
 pub mod sol_interface;
 
+/// Huff Interface Generator
+pub mod huff_interface;
+
+/// Solidity Interface Parser
+pub mod sol_parser;
+
+/// Rust and TypeScript Bindings Generator
+pub mod bindings;
+
 /// Prelude wraps common utilities.
 pub mod prelude {
     pub use crate::{
-        abi::*, artifact::*, ast::*, bytecode::*, bytes_util::*, error::*, evm::*, files::*, io::*,
-        report::*, sol_interface::*, token::*, types::*,
+        abi::*, artifact::*, ast::*, bindings::*, bytecode::*, bytes_util::*, disassembler::*,
+        env::*, error::*, evm::*, files::*, huff_interface::*, io::*, layout::*, report::*,
+        sol_interface::*, sol_parser::*, source_map::*, token::*, types::*,
     };
 }
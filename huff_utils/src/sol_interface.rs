@@ -1,72 +1,152 @@
-use crate::prelude::Artifact;
+use crate::{
+    abi::array_suffix,
+    prelude::{Artifact, FileSource, FunctionParamType},
+};
 use std::{
     fs,
     path::{Path, PathBuf},
     sync::Arc,
 };
 
+/// The SPDX license identifier of generated interfaces, unless one is given
+pub const DEFAULT_INTERFACE_LICENSE: &str = "UNLICENSED";
+
+/// The solidity version pragma of generated interfaces, unless one is given. Custom errors require
+/// solc 0.8.4.
+pub const DEFAULT_INTERFACE_PRAGMA: &str = "^0.8.4";
+
 /// Generate solidity interfaces from a vector of artifacts.
 ///
+/// Parameters are named as in the Huff definitions, and `///` and `/** */` comments preceding a
+/// definition are carried over as its NatSpec.
+///
 /// @param artifacts The vector of artifacts to generate interfaces from.
+/// @param interface The name of the interface, `I<File>` by default.
+/// @param license The SPDX license identifier, [DEFAULT_INTERFACE_LICENSE] by default.
+/// @param pragma The solidity version pragma, [DEFAULT_INTERFACE_PRAGMA] by default.
 /// @return The vector of generated interfaces.
 pub fn gen_sol_interfaces(
     artifacts: &Vec<Arc<Artifact>>,
     interface: Option<String>,
+    license: Option<String>,
+    pragma: Option<String>,
 ) -> Vec<(PathBuf, String, String)> {
     let mut interfaces = Vec::new();
 
     for artifact in artifacts {
         if let Some(a) = &artifact.abi {
-            let mut defs = Vec::new();
-            a.events.iter().for_each(|(_, f)| {
+            // Tuple parameters are declared as structs
+            let mut structs = Vec::new();
+            a.events
+                .values()
+                .flat_map(|e| e.inputs.iter().map(|i| &i.kind))
+                .chain(a.errors.values().flat_map(|e| e.inputs.iter().map(|i| &i.kind)))
+                .chain(
+                    a.functions
+                        .values()
+                        .flat_map(|f| f.inputs.iter().chain(f.outputs.iter()).map(|i| &i.kind)),
+                )
+                .for_each(|kind| collect_structs(kind, &mut structs));
+
+            let mut docs = Vec::new();
+            collect_docs(&artifact.file, &mut docs, &mut Vec::new());
+            let natspec = |kind: &str, name: &str, params: &[&FunctionParamType]| {
+                let signature = format!(
+                    "{}({})",
+                    name,
+                    params.iter().map(|k| source_type(k)).collect::<Vec<_>>().join(",")
+                );
+                docs.iter()
+                    .find(|d| d.kind == kind && d.signature == signature)
+                    .map(|d| d.lines.iter().map(|l| format!("\t///{}\n", l)).collect::<String>())
+                    .unwrap_or_default()
+            };
+
+            let mut defs = structs
+                .iter()
+                .map(|(kind, name)| {
+                    let fields = match kind {
+                        FunctionParamType::Struct(_, fields) => fields.clone(),
+                        FunctionParamType::Tuple(inner) => {
+                            inner.iter().map(|k| (String::default(), k.clone())).collect()
+                        }
+                        _ => vec![],
+                    };
+                    let fields = fields
+                        .iter()
+                        .enumerate()
+                        .map(|(i, (field, k))| {
+                            let field = if field.is_empty() {
+                                format!("field{}", i)
+                            } else {
+                                field.clone()
+                            };
+                            format!("\t\t{} {};\n", sol_type(k, &structs), field)
+                        })
+                        .collect::<String>();
+                    format!("\tstruct {} {{\n{}\t}}", name, fields)
+                })
+                .collect::<Vec<_>>();
+            a.events.iter().for_each(|(_, e)| {
                 defs.push(format!(
-                    "{}event {}({});",
-                    "\t",
-                    f.name,
-                    f.inputs
+                    "{}\tevent {}({}){};",
+                    natspec(
+                        "event",
+                        &e.name,
+                        &e.inputs.iter().map(|i| &i.kind).collect::<Vec<_>>()
+                    ),
+                    e.name,
+                    e.inputs
                         .iter()
                         .map(|i| {
-                            format!(
-                                "{}{}",
-                                i.kind,
-                                if i.indexed {
-                                    String::from(" indexed")
-                                } else {
-                                    String::default()
-                                }
-                            )
+                            let modifier = if i.indexed { Some("indexed") } else { None };
+                            sol_param(&i.kind, &i.name, modifier, &structs)
                         })
                         .collect::<Vec<_>>()
                         .join(", "),
+                    if e.anonymous { " anonymous" } else { "" }
                 ));
             });
             a.errors.iter().for_each(|(_, e)| {
                 defs.push(format!(
-                    "{}error {}({});",
-                    "\t",
+                    "{}\terror {}({});",
+                    natspec(
+                        "error",
+                        &e.name,
+                        &e.inputs.iter().map(|i| &i.kind).collect::<Vec<_>>()
+                    ),
                     e.name,
                     e.inputs
                         .iter()
-                        .map(|i| format!(
-                            "{}{}",
-                            i.kind,
-                            if i.kind.is_memory_type() { " memory" } else { "" }
-                        ))
+                        .map(|i| sol_param(&i.kind, &i.name, None, &structs))
                         .collect::<Vec<_>>()
                         .join(", "),
                 ));
             });
             a.functions.iter().for_each(|(_, f)| {
+                // Reference types are read from calldata, and returned in memory
+                let location = |kind: &FunctionParamType, location| {
+                    if kind.is_memory_type() {
+                        Some(location)
+                    } else {
+                        None
+                    }
+                };
                 defs.push(format!(
-                    "{}function {}({}) external{}{};",
-                    "\t",
+                    "{}\tfunction {}({}) external{}{};",
+                    natspec(
+                        "function",
+                        &f.name,
+                        &f.inputs.iter().map(|i| &i.kind).collect::<Vec<_>>()
+                    ),
                     f.name,
                     f.inputs
                         .iter()
-                        .map(|i| format!(
-                            "{}{}",
-                            i.kind,
-                            if i.kind.is_memory_type() { " memory" } else { "" }
+                        .map(|i| sol_param(
+                            &i.kind,
+                            &i.name,
+                            location(&i.kind, "calldata"),
+                            &structs
                         ))
                         .collect::<Vec<_>>()
                         .join(", "),
@@ -78,10 +158,11 @@ pub fn gen_sol_interfaces(
                             " returns ({})",
                             f.outputs
                                 .iter()
-                                .map(|o| format!(
-                                    "{}{}",
-                                    o.kind,
-                                    if o.kind.is_memory_type() { " memory" } else { "" }
+                                .map(|o| sol_param(
+                                    &o.kind,
+                                    &o.name,
+                                    location(&o.kind, "memory"),
+                                    &structs
                                 ))
                                 .collect::<Vec<_>>()
                                 .join(", ")
@@ -96,7 +177,13 @@ pub fn gen_sol_interfaces(
                     artifact.file.path.split('/').last().unwrap().split('.').next().unwrap()
                 )
             });
-            let formatted_str = format!("interface {} {{\n{}\n}}", interface_name, defs.join("\n"));
+            let formatted_str = format!(
+                "// SPDX-License-Identifier: {}\npragma solidity {};\n\ninterface {} {{\n{}\n}}\n",
+                license.as_deref().unwrap_or(DEFAULT_INTERFACE_LICENSE),
+                pragma.as_deref().unwrap_or(DEFAULT_INTERFACE_PRAGMA),
+                interface_name,
+                defs.join("\n")
+            );
             interfaces.push((
                 Path::new(&artifact.file.path).parent().unwrap().to_path_buf(),
                 interface_name,
@@ -108,6 +195,212 @@ pub fn gen_sol_interfaces(
     interfaces
 }
 
+/// The NatSpec of a function, event or error definition
+struct Doc {
+    /// The kind of definition, ie. `function`
+    kind: String,
+    /// The name and parameter types of the definition as written, ie. `execute(Call[])`, to tell
+    /// overloads apart
+    signature: String,
+    /// The comment lines, without their `///` or `*` prefixes
+    lines: Vec<String>,
+}
+
+/// Collects the doc comments of the definitions in a file and its dependencies.
+///
+/// Only `///` and `/** */` comments directly preceding a definition are doc comments, so that
+/// section comments like `/* Interface */` aren't carried over.
+fn collect_docs(file: &FileSource, docs: &mut Vec<Doc>, visited: &mut Vec<String>) {
+    if visited.contains(&file.path) {
+        return
+    }
+    visited.push(file.path.clone());
+
+    let mut lines: Vec<String> = vec![];
+    let mut in_block = false;
+    for line in file.source.as_deref().unwrap_or_default().lines().map(str::trim) {
+        if in_block {
+            let (line, end) = match line.strip_suffix("*/") {
+                Some(l) => (l, true),
+                None => (line, false),
+            };
+            let line = line.trim_start_matches('*').trim_end();
+            if !(end && line.is_empty()) {
+                lines.push(format!(" {}", line.trim_start()).trim_end().to_string());
+            }
+            in_block = !end;
+        } else if let Some(comment) = line.strip_prefix("///") {
+            lines.push(comment.trim_end().to_string());
+        } else if let Some(comment) = line.strip_prefix("/**") {
+            lines.clear();
+            match comment.strip_suffix("*/") {
+                Some(c) if !c.trim().is_empty() => lines.push(format!(" {}", c.trim())),
+                Some(_) => {}
+                None => {
+                    if !comment.trim().is_empty() {
+                        lines.push(format!(" {}", comment.trim()));
+                    }
+                    in_block = true;
+                }
+            }
+        } else if let Some(definition) = line.strip_prefix("#define") {
+            let mut words = definition.trim_start().splitn(2, char::is_whitespace);
+            let kind = words.next().unwrap_or_default();
+            let rest = words.next().unwrap_or_default().trim_start();
+            if matches!(kind, "function" | "event" | "error") && !lines.is_empty() {
+                if let Some(open) = rest.find('(') {
+                    docs.push(Doc {
+                        kind: kind.to_string(),
+                        signature: format!(
+                            "{}({})",
+                            rest[..open].trim(),
+                            param_types(&rest[open + 1..]).join(",")
+                        ),
+                        lines: std::mem::take(&mut lines),
+                    });
+                }
+            }
+            lines.clear();
+        } else {
+            lines.clear();
+        }
+    }
+
+    file.dependencies.iter().flatten().for_each(|d| collect_docs(d, docs, visited));
+}
+
+/// The position of the parenthesis closing a list, or its length if it isn't closed
+fn closing_paren(list: &str) -> usize {
+    let mut depth = 0;
+    for (i, c) in list.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' if depth == 0 => return i,
+            ')' => depth -= 1,
+            _ => {}
+        }
+    }
+    list.len()
+}
+
+/// The types of a parameter list as written, up to its closing parenthesis, ie. `Call[]` and
+/// `uint256` for `Call[] calls, uint amount)`. The `uint` and `int` aliases are expanded.
+fn param_types(list: &str) -> Vec<String> {
+    let list = &list[..closing_paren(list)];
+    let mut params = vec![];
+    let (mut depth, mut start) = (0, 0);
+    for (i, c) in list.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                params.push(&list[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    params.push(&list[start..]);
+
+    params
+        .iter()
+        .map(|p| p.trim())
+        .filter(|p| !p.is_empty())
+        .map(|p| match p.strip_prefix('(') {
+            // Tuples are followed by their array suffix, if any
+            Some(tuple) => {
+                let close = closing_paren(tuple);
+                let suffix = tuple.get(close + 1..).unwrap_or_default();
+                format!(
+                    "({}){}",
+                    param_types(tuple).join(","),
+                    suffix.split_whitespace().next().unwrap_or_default()
+                )
+            }
+            None => {
+                let kind = p.split_whitespace().next().unwrap_or_default();
+                let (base, suffix) = kind.split_at(kind.find('[').unwrap_or(kind.len()));
+                match base {
+                    "uint" => format!("uint256{}", suffix),
+                    "int" => format!("int256{}", suffix),
+                    _ => kind.to_string(),
+                }
+            }
+        })
+        .collect()
+}
+
+/// Renders a parameter type as it's written in a Huff definition, naming structs rather than
+/// expanding them
+fn source_type(kind: &FunctionParamType) -> String {
+    match kind {
+        FunctionParamType::Array(inner, sizes) => {
+            format!("{}{}", source_type(inner), array_suffix(sizes))
+        }
+        FunctionParamType::Struct(name, _) => name.clone(),
+        FunctionParamType::Tuple(inner) => {
+            format!("({})", inner.iter().map(source_type).collect::<Vec<_>>().join(","))
+        }
+        _ => kind.to_string(),
+    }
+}
+
+/// Renders a parameter as its type, then an optional data location or `indexed`, then its name
+fn sol_param(
+    kind: &FunctionParamType,
+    name: &str,
+    modifier: Option<&str>,
+    structs: &[(FunctionParamType, String)],
+) -> String {
+    [Some(sol_type(kind, structs).as_str()), modifier, Some(name).filter(|n| !n.is_empty())]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Collects the structs and tuples of a parameter type, and those nested in them, with the name
+/// they are declared as. Nested types come first, and anonymous tuples are named `Tuple<n>`.
+fn collect_structs(kind: &FunctionParamType, structs: &mut Vec<(FunctionParamType, String)>) {
+    match kind {
+        FunctionParamType::Array(inner, _) => collect_structs(inner, structs),
+        FunctionParamType::Struct(name, fields) => {
+            fields.iter().for_each(|(_, k)| collect_structs(k, structs));
+            if !structs.iter().any(|(_, n)| n == name) {
+                structs.push((kind.clone(), name.clone()));
+            }
+        }
+        FunctionParamType::Tuple(inner) => {
+            inner.iter().for_each(|k| collect_structs(k, structs));
+            if !structs.iter().any(|(k, _)| k == kind) {
+                let tuples = structs
+                    .iter()
+                    .filter(|(k, _)| matches!(k, FunctionParamType::Tuple(_)))
+                    .count();
+                structs.push((kind.clone(), format!("Tuple{}", tuples)));
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Renders a parameter type in Solidity, naming structs and tuples as collected by
+/// [collect_structs].
+fn sol_type(kind: &FunctionParamType, structs: &[(FunctionParamType, String)]) -> String {
+    match kind {
+        FunctionParamType::Array(inner, sizes) => {
+            format!("{}{}", sol_type(inner, structs), array_suffix(sizes))
+        }
+        FunctionParamType::Struct(name, _) => name.clone(),
+        FunctionParamType::Tuple(_) => structs
+            .iter()
+            .find(|(k, _)| k == kind)
+            .map(|(_, name)| name.clone())
+            .unwrap_or_else(|| kind.to_string()),
+        _ => kind.to_string(),
+    }
+}
+
 /// Export generated solidity interfaces to a file.
 ///
 /// @param interfaces The vector of generated interfaces.
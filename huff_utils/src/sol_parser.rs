@@ -0,0 +1,439 @@
+//! ## Solidity Interface Parser
+//!
+//! Parses the declarations of Solidity interface files into an [Abi], without solc.
+//!
+//! Supports `interface` blocks and the `function`, `event`, `error`, `struct`, `enum` and
+//! user-defined value `type` declarations within them or at the file level. Types imported from
+//! other files can't be resolved.
+
+use crate::{
+    abi::{Abi, Error, Event, EventParam, Function, FunctionParam, FunctionParamType},
+    ast::FunctionType,
+    types::PrimitiveEVMType,
+};
+use std::collections::HashMap;
+
+/// Parses the declarations of a Solidity interface file into an [Abi].
+///
+/// Errors with the line of the first unsupported or invalid declaration.
+pub fn parse_sol_interface(source: &str) -> Result<Abi, String> {
+    let mut parser =
+        SolParser { tokens: tokenize(source)?, pos: 0, decls: Declarations::default() };
+    parser.parse()?;
+    parser.decls.into_abi()
+}
+
+/// A type as written, ie. `IVault.Position[2][]`
+#[derive(Debug, Clone)]
+struct SolType {
+    /// The type name, without any qualifier
+    name: String,
+    /// The array dimensions, where a dynamic array has a size of `0`
+    sizes: Vec<usize>,
+    /// The line the type is written on
+    line: usize,
+}
+
+/// A parameter or struct member
+#[derive(Debug, Clone)]
+struct SolParam {
+    kind: SolType,
+    name: String,
+    indexed: bool,
+}
+
+/// The declarations of a file, before their types are resolved
+#[derive(Debug, Default)]
+struct Declarations {
+    functions: Vec<(String, Vec<SolParam>, Vec<SolParam>, FunctionType)>,
+    events: Vec<(String, Vec<SolParam>, bool)>,
+    errors: Vec<(String, Vec<SolParam>)>,
+    structs: HashMap<String, Vec<SolParam>>,
+    enums: Vec<String>,
+    value_types: HashMap<String, SolType>,
+    interfaces: Vec<String>,
+    receive: bool,
+    fallback: bool,
+}
+
+impl Declarations {
+    /// Resolves the declared types into an [Abi]
+    fn into_abi(self) -> Result<Abi, String> {
+        let mut abi = Abi::new();
+        for (name, inputs, outputs, state_mutability) in &self.functions {
+            let function = Function {
+                name: name.clone(),
+                inputs: self.function_params(inputs)?,
+                outputs: self.function_params(outputs)?,
+                constant: matches!(state_mutability, FunctionType::View | FunctionType::Pure),
+                state_mutability: state_mutability.clone(),
+            };
+            abi.functions.insert(function.signature(), function);
+        }
+        for (name, params, anonymous) in &self.events {
+            let event = Event {
+                name: name.clone(),
+                inputs: params
+                    .iter()
+                    .map(|p| {
+                        Ok(EventParam {
+                            name: p.name.clone(),
+                            kind: self.resolve(&p.kind, 0)?,
+                            indexed: p.indexed,
+                        })
+                    })
+                    .collect::<Result<_, String>>()?,
+                anonymous: *anonymous,
+            };
+            abi.events.insert(event.signature(), event);
+        }
+        for (name, params) in &self.errors {
+            let error = Error { name: name.clone(), inputs: self.function_params(params)? };
+            abi.errors.insert(error.signature(), error);
+        }
+        abi.receive = self.receive;
+        abi.fallback = self.fallback;
+        Ok(abi)
+    }
+
+    fn function_params(&self, params: &[SolParam]) -> Result<Vec<FunctionParam>, String> {
+        params
+            .iter()
+            .map(|p| {
+                Ok(FunctionParam {
+                    name: p.name.clone(),
+                    kind: self.resolve(&p.kind, 0)?,
+                    internal_type: None,
+                })
+            })
+            .collect()
+    }
+
+    /// Resolves a type nested `depth` structs deep into its ABI type
+    fn resolve(&self, kind: &SolType, depth: usize) -> Result<FunctionParamType, String> {
+        let base = if PrimitiveEVMType::try_from(kind.name.clone()).is_ok() {
+            FunctionParamType::convert_string_to_type(&kind.name).map_err(|e| e.to_string())?
+        } else if let Some(members) = self.structs.get(&kind.name) {
+            // Structs can only nest deeper than the number of structs if they are recursive
+            if depth >= self.structs.len() {
+                return Err(format!("Recursive struct \"{}\" (line {})", kind.name, kind.line))
+            }
+            let fields = members
+                .iter()
+                .map(|m| Ok((m.name.clone(), self.resolve(&m.kind, depth + 1)?)))
+                .collect::<Result<Vec<_>, String>>()?;
+            FunctionParamType::Struct(kind.name.clone(), fields)
+        } else if self.enums.contains(&kind.name) {
+            FunctionParamType::Uint(8)
+        } else if let Some(underlying) = self.value_types.get(&kind.name) {
+            self.resolve(underlying, depth)?
+        } else if self.interfaces.contains(&kind.name) {
+            FunctionParamType::Address
+        } else {
+            return Err(format!("Unknown type \"{}\" (line {})", kind.name, kind.line))
+        };
+        if kind.sizes.is_empty() {
+            return Ok(base)
+        }
+        Ok(FunctionParamType::Array(Box::new(base), kind.sizes.clone()))
+    }
+}
+
+/// A token and the line it's on
+type SolToken = (String, usize);
+
+/// Splits Solidity source into identifiers, numbers, strings and punctuation, dropping comments
+fn tokenize(source: &str) -> Result<Vec<SolToken>, String> {
+    let mut tokens = vec![];
+    let mut line = 1;
+    let mut chars = source.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\n' => line += 1,
+            c if c.is_whitespace() => {}
+            '/' if chars.peek() == Some(&'/') => while chars.next_if(|c| *c != '\n').is_some() {},
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut last = ' ';
+                loop {
+                    match chars.next() {
+                        Some('/') if last == '*' => break,
+                        Some(c) => {
+                            if c == '\n' {
+                                line += 1;
+                            }
+                            last = c;
+                        }
+                        None => return Err(format!("Unterminated comment (line {})", line)),
+                    }
+                }
+            }
+            '"' | '\'' => {
+                let mut string = c.to_string();
+                loop {
+                    match chars.next() {
+                        Some(s) if s == c => break,
+                        Some(s) => string.push(s),
+                        None => return Err(format!("Unterminated string (line {})", line)),
+                    }
+                }
+                string.push(c);
+                tokens.push((string, line));
+            }
+            c if c.is_alphanumeric() || c == '_' || c == '$' => {
+                let mut word = c.to_string();
+                while let Some(n) =
+                    chars.peek().filter(|n| n.is_alphanumeric() || **n == '_' || **n == '$')
+                {
+                    word.push(*n);
+                    chars.next();
+                }
+                tokens.push((word, line));
+            }
+            c => tokens.push((c.to_string(), line)),
+        }
+    }
+    Ok(tokens)
+}
+
+/// A recursive descent parser over the tokens of a Solidity interface file
+struct SolParser {
+    tokens: Vec<SolToken>,
+    pos: usize,
+    decls: Declarations,
+}
+
+impl SolParser {
+    fn peek(&self) -> Option<&str> {
+        self.tokens.get(self.pos).map(|(t, _)| t.as_str())
+    }
+
+    fn line(&self) -> usize {
+        self.tokens.get(self.pos).or_else(|| self.tokens.last()).map(|(_, l)| *l).unwrap_or(1)
+    }
+
+    fn next(&mut self) -> Result<String, String> {
+        let token = self.tokens.get(self.pos).map(|(t, _)| t.clone());
+        self.pos += 1;
+        token.ok_or_else(|| "Unexpected end of file".to_string())
+    }
+
+    /// Consumes the token if it matches
+    fn eat(&mut self, token: &str) -> bool {
+        if self.peek() == Some(token) {
+            self.pos += 1;
+            return true
+        }
+        false
+    }
+
+    fn expect(&mut self, token: &str) -> Result<(), String> {
+        let line = self.line();
+        match self.next()? {
+            t if t == token => Ok(()),
+            t => Err(format!("Expected \"{}\", found \"{}\" (line {})", token, t, line)),
+        }
+    }
+
+    fn ident(&mut self) -> Result<String, String> {
+        let line = self.line();
+        let token = self.next()?;
+        match token.chars().next() {
+            Some(c) if c.is_alphabetic() || c == '_' || c == '$' => Ok(token),
+            _ => Err(format!("Expected an identifier, found \"{}\" (line {})", token, line)),
+        }
+    }
+
+    /// Whether the next token ends a parameter
+    fn at_separator(&self) -> bool {
+        matches!(self.peek(), Some("," | ")") | None)
+    }
+
+    /// Skips to the end of a statement
+    fn skip_statement(&mut self) -> Result<(), String> {
+        while self.next()? != ";" {}
+        Ok(())
+    }
+
+    fn parse(&mut self) -> Result<(), String> {
+        while let Some(token) = self.peek() {
+            match token {
+                "pragma" | "import" | "using" => self.skip_statement()?,
+                "interface" => self.parse_interface()?,
+                "struct" | "enum" | "error" | "event" | "type" => self.parse_member()?,
+                t => {
+                    return Err(format!(
+                        "Unsupported declaration \"{}\", expected an interface (line {})",
+                        t,
+                        self.line()
+                    ))
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn parse_interface(&mut self) -> Result<(), String> {
+        self.expect("interface")?;
+        let name = self.ident()?;
+        self.decls.interfaces.push(name);
+        // Inherited interfaces must be declared in the same file for their members to be included
+        if self.eat("is") {
+            while !self.eat("{") {
+                self.next()?;
+            }
+        } else {
+            self.expect("{")?;
+        }
+        while !self.eat("}") {
+            self.parse_member()?;
+        }
+        Ok(())
+    }
+
+    fn parse_member(&mut self) -> Result<(), String> {
+        let line = self.line();
+        match self.next()?.as_str() {
+            "function" => {
+                let name = self.ident()?;
+                let inputs = self.parse_params()?;
+                let (outputs, state_mutability) = self.parse_modifiers()?;
+                self.decls.functions.push((name, inputs, outputs, state_mutability));
+            }
+            kind @ ("receive" | "fallback") => {
+                if self.peek() == Some("(") {
+                    self.parse_params()?;
+                }
+                self.parse_modifiers()?;
+                match kind {
+                    "receive" => self.decls.receive = true,
+                    _ => self.decls.fallback = true,
+                }
+            }
+            "event" => {
+                let name = self.ident()?;
+                let params = self.parse_params()?;
+                let anonymous = self.eat("anonymous");
+                self.expect(";")?;
+                self.decls.events.push((name, params, anonymous));
+            }
+            "error" => {
+                let name = self.ident()?;
+                let params = self.parse_params()?;
+                self.expect(";")?;
+                self.decls.errors.push((name, params));
+            }
+            "struct" => {
+                let name = self.ident()?;
+                self.expect("{")?;
+                let mut members = vec![];
+                while !self.eat("}") {
+                    let kind = self.parse_type()?;
+                    let name = self.ident()?;
+                    self.expect(";")?;
+                    members.push(SolParam { kind, name, indexed: false });
+                }
+                self.decls.structs.insert(name, members);
+            }
+            "enum" => {
+                let name = self.ident()?;
+                self.expect("{")?;
+                while !self.eat("}") {
+                    self.next()?;
+                }
+                self.decls.enums.push(name);
+            }
+            "type" => {
+                let name = self.ident()?;
+                self.expect("is")?;
+                let underlying = self.parse_type()?;
+                self.expect(";")?;
+                self.decls.value_types.insert(name, underlying);
+            }
+            t => return Err(format!("Unsupported interface member \"{}\" (line {})", t, line)),
+        }
+        Ok(())
+    }
+
+    /// Parses the modifiers and return parameters of a function, up to its closing `;`
+    fn parse_modifiers(&mut self) -> Result<(Vec<SolParam>, FunctionType), String> {
+        let mut outputs = vec![];
+        let mut state_mutability = FunctionType::NonPayable;
+        loop {
+            let line = self.line();
+            match self.next()?.as_str() {
+                ";" => return Ok((outputs, state_mutability)),
+                "{" => {
+                    return Err(format!(
+                        "Interface functions can't have implementations (line {})",
+                        line
+                    ))
+                }
+                "view" => state_mutability = FunctionType::View,
+                "pure" => state_mutability = FunctionType::Pure,
+                "payable" => state_mutability = FunctionType::Payable,
+                "returns" => outputs = self.parse_params()?,
+                "override" if self.peek() == Some("(") => while self.next()? != ")" {},
+                // Visibility, `virtual`, `override`
+                _ => {}
+            }
+        }
+    }
+
+    /// Parses a parenthesized parameter list
+    fn parse_params(&mut self) -> Result<Vec<SolParam>, String> {
+        self.expect("(")?;
+        let mut params = vec![];
+        while !self.eat(")") {
+            let kind = self.parse_type()?;
+            let mut param = SolParam { kind, name: String::default(), indexed: false };
+            loop {
+                match self.peek() {
+                    Some("indexed") => param.indexed = true,
+                    Some("memory" | "calldata" | "storage") => {}
+                    Some(_) if param.name.is_empty() && !self.at_separator() => {
+                        param.name = self.ident()?;
+                        continue
+                    }
+                    _ => break,
+                }
+                self.pos += 1;
+            }
+            if !self.eat(",") && !self.at_separator() {
+                return Err(format!("Expected \",\" or \")\" (line {})", self.line()))
+            }
+            params.push(param);
+        }
+        Ok(params)
+    }
+
+    /// Parses a type name, ie. `address payable`, `IVault.Position[]` or `uint256[2]`
+    fn parse_type(&mut self) -> Result<SolType, String> {
+        let line = self.line();
+        let mut name = self.ident()?;
+        if matches!(name.as_str(), "mapping" | "function") {
+            return Err(format!("Unsupported parameter type \"{}\" (line {})", name, line))
+        }
+        // Types declared in other interfaces are qualified by the interface's name
+        while self.eat(".") {
+            name = self.ident()?;
+        }
+        if name == "address" {
+            self.eat("payable");
+        }
+        let mut sizes = vec![];
+        while self.eat("[") {
+            if self.eat("]") {
+                sizes.push(0);
+                continue
+            }
+            let size = self.next()?;
+            sizes.push(
+                size.parse()
+                    .map_err(|_| format!("Invalid array size \"{}\" (line {})", size, line))?,
+            );
+            self.expect("]")?;
+        }
+        Ok(SolType { name, sizes, line })
+    }
+}
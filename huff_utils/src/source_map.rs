@@ -0,0 +1,109 @@
+//! ## Source Map
+//!
+//! Maps ranges of generated bytecode back to the macros and tables they were generated from.
+
+use crate::{ast::AstSpan, evm::Opcode};
+use serde::{Deserialize, Serialize};
+
+/// A range of bytecode and the source it was generated from
+#[derive(Default, Serialize, Deserialize, Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub struct SourceMapEntry {
+    /// The bytecode offset of the range
+    pub offset: usize,
+    /// The length of the range in bytes
+    pub length: usize,
+    /// The name of the macro or table the range was generated from
+    pub name: String,
+    /// Whether the range is table data rather than code
+    pub data: bool,
+    /// The path of the file containing the source
+    pub file: Option<String>,
+    /// The 1-indexed source line, or `0` if unknown
+    pub line: usize,
+    /// The offset of the source in its file
+    #[serde(default)]
+    pub start: usize,
+    /// The end offset of the source in its file
+    #[serde(default)]
+    pub end: usize,
+}
+
+impl SourceMapEntry {
+    /// Public associated function to instantiate a new SourceMapEntry.
+    pub fn new(offset: usize, length: usize, name: &str, data: bool, span: &AstSpan) -> Self {
+        let first = span.0.iter().find(|s| s.file.is_some());
+        let file = first.and_then(|s| s.file.as_ref().map(|f| f.path.clone()));
+        // The source spans all of its tokens in the first file
+        let spans = span.0.iter().filter(|s| s.file.as_ref().map(|f| &f.path) == file.as_ref());
+        let (start, end) = match first {
+            Some(_) => {
+                spans.fold((usize::MAX, 0), |(start, end), s| (start.min(s.start), end.max(s.end)))
+            }
+            None => (0, 0),
+        };
+        Self {
+            offset,
+            length,
+            name: name.to_string(),
+            data,
+            file,
+            line: first.and_then(|s| s.line()).unwrap_or_default(),
+            start,
+            end,
+        }
+    }
+
+    /// Whether the range contains the given bytecode offset
+    pub fn contains(&self, offset: usize) -> bool {
+        self.offset <= offset && offset < self.offset + self.length
+    }
+
+    /// Returns the entry shifted by the given number of bytes
+    pub fn shifted(&self, by: usize) -> Self {
+        Self { offset: self.offset + by, ..self.clone() }
+    }
+}
+
+/// Compresses a source map into solc's `s:l:f:j` format, with an entry per instruction.
+///
+/// Instructions are decoded linearly, as tools consuming solc source maps do. `f` is the index of
+/// the source's file in `files`, and unmapped instructions have an `f` of `-1`.
+pub fn compress_source_map(code: &[u8], source_map: &[SourceMapEntry], files: &[String]) -> String {
+    let mut entries = vec![];
+    let mut previous: Vec<String> = vec![];
+    let mut pc = 0;
+    while pc < code.len() {
+        let fields = match find_source(source_map, pc) {
+            Some(e) => {
+                let file = e.file.as_ref().and_then(|f| files.iter().position(|p| p == f));
+                vec![
+                    e.start.to_string(),
+                    (e.end - e.start).to_string(),
+                    file.map(|f| f.to_string()).unwrap_or_else(|| "-1".to_string()),
+                    "-".to_string(),
+                ]
+            }
+            None => vec!["-1".to_string(), "-1".to_string(), "-1".to_string(), "-".to_string()],
+        };
+
+        // Fields that are unchanged from the previous entry are omitted
+        let mut compressed = fields
+            .iter()
+            .enumerate()
+            .map(|(i, f)| if previous.get(i) == Some(f) { "" } else { f.as_str() })
+            .collect::<Vec<&str>>();
+        while compressed.last() == Some(&"") {
+            compressed.pop();
+        }
+        entries.push(compressed.join(":"));
+        previous = fields;
+
+        pc += 1 + Opcode::from_byte(code[pc]).map(|o| o.immediate_size()).unwrap_or_default();
+    }
+    entries.join(";")
+}
+
+/// Finds the innermost source map entry containing the given bytecode offset
+pub fn find_source(source_map: &[SourceMapEntry], offset: usize) -> Option<&SourceMapEntry> {
+    source_map.iter().filter(|e| e.contains(offset)).min_by_key(|e| e.length)
+}
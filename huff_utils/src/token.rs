@@ -33,6 +33,12 @@ pub enum TokenKind {
     Define,
     /// "#include" keyword
     Include,
+    /// "#if" keyword
+    If,
+    /// "#else" keyword
+    Else,
+    /// "#endif" keyword
+    Endif,
     /// "macro" keyword
     Macro,
     /// "fn" keyword
@@ -47,6 +53,8 @@ pub enum TokenKind {
     Constant,
     /// "error" keyword
     Error,
+    /// "struct" keyword
+    Struct,
     /// "takes" keyword
     Takes,
     /// "returns" keyword
@@ -63,6 +71,10 @@ pub enum TokenKind {
     Indexed,
     /// "FREE_STORAGE_POINTER()" keyword
     FreeStoragePointer,
+    /// "FREE_TRANSIENT_POINTER()" keyword
+    FreeTransientPointer,
+    /// "FREE_MEMORY_POINTER()" keyword
+    FreeMemoryPointer,
     /// An Identifier
     Ident(String),
     /// Equal Sign
@@ -139,6 +151,9 @@ impl fmt::Display for TokenKind {
             TokenKind::Div => "/",
             TokenKind::Define => "#define",
             TokenKind::Include => "#include",
+            TokenKind::If => "#if",
+            TokenKind::Else => "#else",
+            TokenKind::Endif => "#endif",
             TokenKind::Macro => "macro",
             TokenKind::Fn => "fn",
             TokenKind::Test => "test",
@@ -146,6 +161,7 @@ impl fmt::Display for TokenKind {
             TokenKind::Event => "event",
             TokenKind::Constant => "constant",
             TokenKind::Error => "error",
+            TokenKind::Struct => "struct",
             TokenKind::View => "view",
             TokenKind::Pure => "pure",
             TokenKind::Payable => "payable",
@@ -154,6 +170,8 @@ impl fmt::Display for TokenKind {
             TokenKind::Takes => "takes",
             TokenKind::Returns => "returns",
             TokenKind::FreeStoragePointer => "FREE_STORAGE_POINTER()",
+            TokenKind::FreeTransientPointer => "FREE_TRANSIENT_POINTER()",
+            TokenKind::FreeMemoryPointer => "FREE_MEMORY_POINTER()",
             TokenKind::Ident(s) => return write!(f, "{}", s),
             TokenKind::Assign => "=",
             TokenKind::OpenParen => "(",
@@ -1,5 +1,6 @@
-use huff_utils::abi::*;
-use std::collections::HashMap;
+use huff_utils::{abi::*, ast::FunctionType};
+use serde_json::json;
+use std::collections::{BTreeMap, HashMap};
 
 #[test]
 fn convert_function_param_type() {
@@ -77,3 +78,223 @@ fn test_display_func_param_type() {
         }
     }
 }
+
+fn param(name: &str, kind: FunctionParamType) -> FunctionParam {
+    FunctionParam { name: name.to_string(), kind, internal_type: None }
+}
+
+fn example_abi() -> Abi {
+    let transfer = Function {
+        name: "transfer".to_string(),
+        inputs: vec![
+            param("to", FunctionParamType::Address),
+            param("amount", FunctionParamType::Uint(256)),
+        ],
+        outputs: vec![param("", FunctionParamType::Bool)],
+        constant: false,
+        state_mutability: FunctionType::NonPayable,
+    };
+    let positions = Function {
+        name: "positions".to_string(),
+        inputs: vec![],
+        outputs: vec![param(
+            "",
+            FunctionParamType::Array(
+                Box::new(FunctionParamType::Tuple(vec![
+                    FunctionParamType::Address,
+                    FunctionParamType::Uint(128),
+                ])),
+                vec![0],
+            ),
+        )],
+        constant: true,
+        state_mutability: FunctionType::View,
+    };
+    let event = Event {
+        name: "Transfer".to_string(),
+        inputs: vec![
+            EventParam {
+                name: "from".to_string(),
+                kind: FunctionParamType::Address,
+                indexed: true,
+            },
+            EventParam {
+                name: "amount".to_string(),
+                kind: FunctionParamType::Uint(256),
+                indexed: false,
+            },
+        ],
+        anonymous: false,
+    };
+    let error = Error {
+        name: "Unauthorized".to_string(),
+        inputs: vec![param("caller", FunctionParamType::Address)],
+    };
+    Abi {
+        constructor: Some(Constructor { inputs: vec![param("owner", FunctionParamType::Address)] }),
+        functions: BTreeMap::from([
+            ("positions()".to_string(), positions),
+            ("transfer(address,uint256)".to_string(), transfer),
+        ]),
+        events: BTreeMap::from([("Transfer(address,uint256)".to_string(), event)]),
+        errors: BTreeMap::from([("Unauthorized(address)".to_string(), error)]),
+        receive: false,
+        fallback: false,
+    }
+}
+
+#[test]
+fn serializes_standard_json_abi() {
+    let expected = json!([
+        {
+            "type": "constructor",
+            "inputs": [{ "name": "owner", "type": "address" }],
+            "stateMutability": "nonpayable"
+        },
+        {
+            "type": "function",
+            "name": "positions",
+            "inputs": [],
+            "outputs": [{
+                "name": "",
+                "type": "tuple[]",
+                "components": [
+                    { "name": "", "type": "address" },
+                    { "name": "", "type": "uint128" }
+                ]
+            }],
+            "stateMutability": "view"
+        },
+        {
+            "type": "function",
+            "name": "transfer",
+            "inputs": [
+                { "name": "to", "type": "address" },
+                { "name": "amount", "type": "uint256" }
+            ],
+            "outputs": [{ "name": "", "type": "bool" }],
+            "stateMutability": "nonpayable"
+        },
+        {
+            "type": "event",
+            "name": "Transfer",
+            "inputs": [
+                { "name": "from", "type": "address", "indexed": true },
+                { "name": "amount", "type": "uint256", "indexed": false }
+            ],
+            "anonymous": false
+        },
+        {
+            "type": "error",
+            "name": "Unauthorized",
+            "inputs": [{ "name": "caller", "type": "address" }]
+        }
+    ]);
+
+    assert_eq!(serde_json::to_value(example_abi()).unwrap(), expected);
+}
+
+#[test]
+fn roundtrips_standard_json_abi() {
+    let abi = example_abi();
+    let json = abi.to_json().unwrap();
+    assert_eq!(serde_json::from_str::<Abi>(&json).unwrap(), abi);
+}
+
+#[test]
+fn deserializes_solc_abi() {
+    let json = r#"[
+        {
+            "inputs": [
+                {
+                    "components": [
+                        { "internalType": "address", "name": "owner", "type": "address" },
+                        { "internalType": "uint256[2]", "name": "values", "type": "uint256[2]" }
+                    ],
+                    "internalType": "struct Vault.Position[]",
+                    "name": "positions",
+                    "type": "tuple[]"
+                }
+            ],
+            "name": "open",
+            "outputs": [],
+            "stateMutability": "payable",
+            "type": "function"
+        },
+        { "stateMutability": "payable", "type": "receive" },
+        { "stateMutability": "nonpayable", "type": "fallback" }
+    ]"#;
+
+    let abi: Abi = serde_json::from_str(json).unwrap();
+    let expected = Abi {
+        functions: BTreeMap::from([(
+            "open((address,uint256[2])[])".to_string(),
+            Function {
+                name: "open".to_string(),
+                inputs: vec![FunctionParam {
+                    name: "positions".to_string(),
+                    kind: FunctionParamType::Array(
+                        Box::new(FunctionParamType::Struct(
+                            "Position".to_string(),
+                            vec![
+                                ("owner".to_string(), FunctionParamType::Address),
+                                (
+                                    "values".to_string(),
+                                    FunctionParamType::Array(
+                                        Box::new(FunctionParamType::Uint(256)),
+                                        vec![2],
+                                    ),
+                                ),
+                            ],
+                        )),
+                        vec![0],
+                    ),
+                    internal_type: Some("struct Vault.Position[]".to_string()),
+                }],
+                outputs: vec![],
+                constant: false,
+                state_mutability: FunctionType::Payable,
+            },
+        )]),
+        receive: true,
+        fallback: true,
+        ..Default::default()
+    };
+    assert_eq!(abi, expected);
+}
+
+#[test]
+fn deserializes_unnamed_params() {
+    let json = r#"[
+        {
+            "inputs": [{ "type": "address" }],
+            "name": "balanceOf",
+            "outputs": [{ "type": "uint256" }],
+            "stateMutability": "view",
+            "type": "function"
+        }
+    ]"#;
+
+    let abi: Abi = serde_json::from_str(json).unwrap();
+    let expected = Abi {
+        functions: BTreeMap::from([(
+            "balanceOf(address)".to_string(),
+            Function {
+                name: "balanceOf".to_string(),
+                inputs: vec![param("", FunctionParamType::Address)],
+                outputs: vec![param("", FunctionParamType::Uint(256))],
+                constant: true,
+                state_mutability: FunctionType::View,
+            },
+        )]),
+        ..Default::default()
+    };
+    assert_eq!(abi, expected);
+}
+
+#[test]
+fn rejects_invalid_tuple_type() {
+    let json =
+        r#"[{ "type": "error", "name": "Bad", "inputs": [{ "name": "", "type": "tuple[x]" }] }]"#;
+    assert!(serde_json::from_str::<Abi>(json).is_err());
+}
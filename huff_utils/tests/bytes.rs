@@ -18,3 +18,25 @@ fn test_hex_to_usize() {
         assert_eq!(converted_usize, i);
     }
 }
+
+#[test]
+fn test_erc7201_slot() {
+    // Example slot from the ERC-7201 specification
+    let slot = erc7201_slot("example.main");
+    assert_eq!(
+        bytes32_to_string(&slot, false),
+        "183a6125c38840424c4a85fa12bab2ab606c4b6d0e7cc73c0c06ba5300eab500"
+    );
+}
+
+#[test]
+fn test_create2_address() {
+    // Example 0 from EIP-1014
+    let mut init_code_hash = [0u8; 32];
+    hash_raw_bytes(&mut init_code_hash, &[0x00]);
+    let address = create2_address(&[0u8; 20], &[0u8; 32], &init_code_hash);
+    assert_eq!(
+        address.iter().map(|b| format!("{:02x}", b)).collect::<String>(),
+        "4d1a2e2bb4f88f0250f26ffff098b0b30b26bf38"
+    );
+}
@@ -0,0 +1,102 @@
+use huff_utils::prelude::*;
+
+#[test]
+fn test_disassemble() {
+    // PUSH1 0x01 PUSH2 0x0007 JUMP INVALID 0x0c JUMPDEST STOP
+    let code = [0x60, 0x01, 0x61, 0x00, 0x07, 0x56, 0x0c, 0x5b, 0x00];
+    let instructions = disassemble(&code, &[]);
+
+    assert_eq!(
+        instructions,
+        vec![
+            Instruction::Opcode { pc: 0, opcode: Opcode::Push1, immediate: vec![0x01] },
+            Instruction::Opcode { pc: 2, opcode: Opcode::Push2, immediate: vec![0x00, 0x07] },
+            Instruction::Opcode { pc: 5, opcode: Opcode::Jump, immediate: vec![] },
+            Instruction::Data { pc: 6, bytes: vec![0x0c] },
+            Instruction::Opcode { pc: 7, opcode: Opcode::Jumpdest, immediate: vec![] },
+            Instruction::Opcode { pc: 8, opcode: Opcode::Stop, immediate: vec![] },
+        ]
+    );
+}
+
+#[test]
+fn test_disassemble_unreachable_data() {
+    // STOP followed by bytes that decode to valid opcodes but aren't reachable
+    let code = [0x00, 0x60, 0x01, 0x5b, 0x60, 0x01];
+    let instructions = disassemble(&code, &[]);
+
+    assert_eq!(
+        instructions,
+        vec![
+            Instruction::Opcode { pc: 0, opcode: Opcode::Stop, immediate: vec![] },
+            Instruction::Data { pc: 1, bytes: vec![0x60, 0x01] },
+            Instruction::Opcode { pc: 3, opcode: Opcode::Jumpdest, immediate: vec![] },
+            Instruction::Opcode { pc: 4, opcode: Opcode::Push1, immediate: vec![0x01] },
+        ]
+    );
+}
+
+#[test]
+fn test_disassemble_truncated_push() {
+    let code = [0x61, 0x01];
+    let instructions = disassemble(&code, &[]);
+
+    assert_eq!(
+        instructions,
+        vec![Instruction::Opcode { pc: 0, opcode: Opcode::Push2, immediate: vec![0x01] }]
+    );
+}
+
+#[test]
+fn test_format_disassembly_with_source_map() {
+    // PUSH1 0x04 JUMP JUMPDEST STOP 0x0003
+    let code = [0x60, 0x03, 0x56, 0x5b, 0x00, 0x00, 0x03];
+    let source_map = vec![
+        SourceMapEntry {
+            offset: 0,
+            length: 5,
+            name: "MAIN".to_string(),
+            data: false,
+            file: Some("main.huff".to_string()),
+            line: 3,
+            start: 24,
+            end: 102,
+        },
+        SourceMapEntry {
+            offset: 5,
+            length: 2,
+            name: "TABLE".to_string(),
+            data: true,
+            file: Some("main.huff".to_string()),
+            line: 9,
+            start: 104,
+            end: 150,
+        },
+    ];
+    let instructions = disassemble(&code, &source_map);
+
+    assert_eq!(
+        format_disassembly(&instructions, &source_map),
+        [
+            "        // MAIN (main.huff:3)",
+            "0000    PUSH1 0x03 // label_0003",
+            "0002    JUMP",
+            "label_0003:",
+            "0003    JUMPDEST",
+            "0004    STOP",
+            "        // TABLE (main.huff:9)",
+            "0005    DATA 0x0003",
+            "",
+        ]
+        .join("\n")
+    );
+}
+
+#[test]
+fn test_opcode_from_byte() {
+    assert_eq!(Opcode::from_byte(0x7f), Some(Opcode::Push32));
+    assert_eq!(Opcode::from_byte(0x0c), None);
+    assert_eq!(Opcode::Push32.immediate_size(), 32);
+    assert_eq!(Opcode::Push32.mnemonic(), "push32");
+    assert!(Opcode::Revert.is_terminating());
+}
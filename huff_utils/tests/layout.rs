@@ -0,0 +1,59 @@
+use huff_utils::prelude::*;
+
+fn slot(constant: &str, slot: &str) -> StorageSlot {
+    StorageSlot { constant: constant.to_string(), slot: slot.to_string(), ..Default::default() }
+}
+
+#[test]
+fn test_compatible_storage_layouts() {
+    let old = vec![slot("OWNER", "0x00"), slot("BALANCE", "0x01")];
+    let new = vec![slot("OWNER", "0x00"), slot("BALANCE", "0x01"), slot("ALLOWANCE", "0x02")];
+
+    assert!(diff_storage_layouts(&old, &new).is_empty());
+}
+
+#[test]
+fn test_reordered_storage_layouts() {
+    let old = vec![slot("OWNER", "0x00"), slot("BALANCE", "0x01")];
+    let new = vec![slot("BALANCE", "0x00"), slot("OWNER", "0x01")];
+
+    assert_eq!(
+        diff_storage_layouts(&old, &new),
+        vec![
+            StorageLayoutChange::Moved {
+                constant: "OWNER".to_string(),
+                from: "0x00".to_string(),
+                to: "0x01".to_string()
+            },
+            StorageLayoutChange::Moved {
+                constant: "BALANCE".to_string(),
+                from: "0x01".to_string(),
+                to: "0x00".to_string()
+            },
+            StorageLayoutChange::Reassigned {
+                slot: "0x00".to_string(),
+                previous: "OWNER".to_string(),
+                current: "BALANCE".to_string()
+            },
+            StorageLayoutChange::Reassigned {
+                slot: "0x01".to_string(),
+                previous: "BALANCE".to_string(),
+                current: "OWNER".to_string()
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_removed_storage_slot() {
+    let old = vec![slot("OWNER", "0x00"), slot("BALANCE", "0x01")];
+    let new = vec![slot("BALANCE", "0x01")];
+
+    assert_eq!(
+        diff_storage_layouts(&old, &new),
+        vec![StorageLayoutChange::Removed {
+            constant: "OWNER".to_string(),
+            slot: "0x00".to_string()
+        }]
+    );
+}